@@ -1,8 +1,10 @@
 //! ADaM-IG (Analysis Data Model) metadata parser.
 
+use std::io::Read;
 use std::path::Path;
 
 use crate::error::{Error, Result};
+use crate::loader::split_list;
 use crate::types::{DatasetDef, Standard, VarType, Variable};
 
 /// Load ADaM-IG metadata from a directory.
@@ -18,7 +20,19 @@ pub fn load_adam(dir: &Path) -> Result<Standard> {
     let datasets = load_data_structures(dir)?;
     let variables = load_variables(dir)?;
 
-    Ok(Standard {
+    Ok(standard(datasets, variables))
+}
+
+/// Parse ADaM-IG metadata from the contents of `DataStructures.csv` and `Variables.csv`.
+pub(crate) fn parse_adam(datasets: &str, variables: &str) -> Result<Standard> {
+    let datasets = read_data_structures(csv::Reader::from_reader(datasets.as_bytes()))?;
+    let variables = read_variables(csv::Reader::from_reader(variables.as_bytes()))?;
+    Ok(standard(datasets, variables))
+}
+
+/// Build the ADaM-IG standard from its definitions.
+fn standard(datasets: Vec<DatasetDef>, variables: Vec<Variable>) -> Standard {
+    Standard {
         name: "ADaM-IG".to_string(),
         version: String::new(),
         full_name: "Analysis Data Model Implementation Guide".to_string(),
@@ -26,7 +40,7 @@ pub fn load_adam(dir: &Path) -> Result<Standard> {
         effective_date: None,
         datasets,
         variables,
-    })
+    }
 }
 
 /// Load data structure definitions from DataStructures.csv.
@@ -36,7 +50,11 @@ fn load_data_structures(dir: &Path) -> Result<Vec<DatasetDef>> {
         return Err(Error::MissingFile(path));
     }
 
-    let mut reader = csv::Reader::from_path(&path)?;
+    read_data_structures(csv::Reader::from_path(&path)?)
+}
+
+/// Read data structure definitions from the contents of DataStructures.csv.
+fn read_data_structures<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<DatasetDef>> {
     let mut datasets = Vec::new();
 
     for result in reader.records() {
@@ -75,7 +93,11 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
         return Err(Error::MissingFile(path));
     }
 
-    let mut reader = csv::Reader::from_path(&path)?;
+    read_variables(csv::Reader::from_path(&path)?)
+}
+
+/// Read variable definitions from the contents of Variables.csv.
+fn read_variables<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<Variable>> {
     let mut variables = Vec::new();
     let mut order_counter: u32 = 0;
 
@@ -103,6 +125,9 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
             .map(|s| s.to_string())
             .filter(|s| !s.is_empty());
 
+        let codelist_codes = split_list(record.get(6).unwrap_or(""));
+        let codelist_values = split_list(record.get(7).unwrap_or(""));
+
         let var_type = if type_str.eq_ignore_ascii_case("Num") {
             VarType::Num
         } else {
@@ -119,6 +144,8 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
                 dataset,
                 role: variable_set, // ADaM uses Variable Set instead of Role
                 core,
+                codelist_codes,
+                codelist_values,
                 notes,
             });
         }
@@ -0,0 +1,185 @@
+//! NCI EVS controlled terminology parser.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::split_list;
+use crate::types::{Codelist, Term, Terminology};
+
+/// Load a controlled terminology package from an NCI EVS tab-delimited file.
+///
+/// These are the `.txt` files published for each CT package (e.g.
+/// `SDTM Terminology.txt`), with the columns:
+/// - `Code` - NCI code of the codelist or term
+/// - `Codelist Code` - Empty for codelists, the parent codelist for terms
+/// - `Codelist Extensible (Yes/No)`
+/// - `Codelist Name`
+/// - `CDISC Submission Value`
+/// - `CDISC Synonym(s)`
+/// - `CDISC Definition`
+/// - `NCI Preferred Term`
+///
+/// Columns are located by their exact header name, ignoring surrounding
+/// whitespace, so the order does not matter.
+///
+/// # Errors
+///
+/// Returns an error if the file is missing or a required column is absent.
+pub fn load_terminology(path: &Path) -> Result<Terminology> {
+    if !path.exists() {
+        return Err(Error::MissingFile(path.to_path_buf()));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .quoting(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| -> Result<usize> {
+        headers
+            .iter()
+            .position(|h| h.trim_start_matches('\u{feff}').trim() == name)
+            .ok_or_else(|| Error::InvalidFormat(format!("missing column '{name}'")))
+    };
+    let code_col = column("Code")?;
+    let codelist_col = column("Codelist Code")?;
+    let extensible_col = column("Codelist Extensible (Yes/No)")?;
+    let name_col = column("Codelist Name")?;
+    let value_col = column("CDISC Submission Value")?;
+    let synonym_col = column("CDISC Synonym(s)").ok();
+    let definition_col = column("CDISC Definition").ok();
+    let preferred_col = column("NCI Preferred Term").ok();
+
+    let mut codelists: Vec<Codelist> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut terms: Vec<(String, Term)> = Vec::new();
+
+    for result in reader.records() {
+        let record = result?;
+        let get = |col: usize| record.get(col).unwrap_or("").trim();
+        let optional =
+            |col: Option<usize>| col.map(get).filter(|s| !s.is_empty()).map(String::from);
+
+        let code = get(code_col);
+        if code.is_empty() {
+            continue;
+        }
+        let parent = get(codelist_col);
+
+        if parent.is_empty() {
+            index.insert(code.to_string(), codelists.len());
+            codelists.push(Codelist {
+                code: code.to_string(),
+                name: get(name_col).to_string(),
+                submission_value: get(value_col).to_string(),
+                extensible: get(extensible_col).eq_ignore_ascii_case("Yes"),
+                terms: Vec::new(),
+            });
+        } else {
+            terms.push((
+                parent.to_string(),
+                Term {
+                    code: code.to_string(),
+                    submission_value: get(value_col).to_string(),
+                    synonyms: split_list(synonym_col.map(get).unwrap_or("")),
+                    definition: optional(definition_col),
+                    preferred_term: optional(preferred_col),
+                },
+            ));
+        }
+    }
+
+    // Terms are attached once all codelists are known
+    for (parent, term) in terms {
+        let Some(&i) = index.get(&parent) else {
+            return Err(Error::InvalidFormat(format!(
+                "term {} refers to unknown codelist {parent}",
+                term.code
+            )));
+        };
+        codelists[i].terms.push(term);
+    }
+
+    Ok(Terminology { codelists })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const HEADER: &str = "Code\tCodelist Code\tCodelist Extensible (Yes/No)\tCodelist Name\t\
+                          CDISC Submission Value\tCDISC Synonym(s)\tCDISC Definition\t\
+                          NCI Preferred Term\n";
+
+    #[test]
+    fn test_load_terminology() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "{HEADER}\
+             C66769\t\tNo\tSeverity/Intensity Scale for Adverse Events\tAESEV\t\
+             Severity\tA scale.\tCDISC SDTM Severity Terminology\n\
+             C41338\tC66769\t\tSeverity/Intensity Scale for Adverse Events\tMILD\t\
+             Grade 1; Mild\tMild.\tMild Adverse Event\n\
+             C41339\tC66769\t\tSeverity/Intensity Scale for Adverse Events\tMODERATE\t\t\t\n"
+        )
+        .unwrap();
+
+        let ct = load_terminology(file.path()).unwrap();
+        assert_eq!(ct.codelists.len(), 1);
+
+        let aesev = ct.codelist("C66769").unwrap();
+        assert_eq!(aesev.submission_value, "AESEV");
+        assert!(!aesev.extensible);
+        assert!(aesev.contains("MILD"));
+        assert!(aesev.contains("MODERATE"));
+        assert!(!aesev.contains("SEVERE"));
+        assert_eq!(aesev.term("MILD").unwrap().synonyms, ["Grade 1", "Mild"]);
+        assert!(aesev.term("MODERATE").unwrap().definition.is_none());
+        assert!(ct.codelist("AESEV").is_some());
+    }
+
+    #[test]
+    fn test_load_terminology_header_names() {
+        // "Codelist Code" comes first and must not be taken for "Code"
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "Codelist Code\t Code \tCodelist Extensible (Yes/No)\tCodelist Name\t\
+             CDISC Submission Value\n\
+             \tC66769\tNo\tSeverity/Intensity Scale for Adverse Events\tAESEV\n\
+             C66769\tC41338\t\tSeverity/Intensity Scale for Adverse Events\tMILD\n"
+        )
+        .unwrap();
+
+        let ct = load_terminology(file.path()).unwrap();
+        assert!(ct.codelist("C66769").unwrap().contains("MILD"));
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "Code\tCodelist Code\tCodelist Extensible\tCodelist Name\t\
+             CDISC Submission Value\n"
+        )
+        .unwrap();
+        assert!(matches!(
+            load_terminology(file.path()),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_terminology_unknown_codelist() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{HEADER}C41338\tC66769\t\t\tMILD\t\t\t\n").unwrap();
+
+        assert!(matches!(
+            load_terminology(file.path()),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+}
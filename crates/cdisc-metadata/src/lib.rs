@@ -12,9 +12,23 @@
 //! - SEND-IG v3.1.1
 //! - ADaM-IG v1.3
 //!
+//! With the `bundled` feature (enabled by default) the metadata files are
+//! embedded in the binary at compile time, so the bundled loaders work
+//! wherever the binary runs. Other and newer IG versions can be loaded from
+//! a directory with [`load_standard`], or from memory with
+//! [`parse_standard`].
+//!
+//! # Controlled Terminology
+//!
+//! Variables carry the CDISC CT codelists they refer to. CT packages are not
+//! bundled; load one from the NCI EVS tab-delimited file with
+//! [`load_terminology`].
+//!
 //! # Example
 //!
 //! ```
+//! # #[cfg(feature = "bundled")]
+//! # {
 //! use cdisc_metadata::{sdtm_ig_v3_4, Standard};
 //!
 //! let standard = sdtm_ig_v3_4().expect("Failed to load SDTM-IG v3.4");
@@ -24,35 +38,42 @@
 //! // Get variables for a specific domain
 //! let dm_vars = standard.variables_for_dataset("DM");
 //! println!("DM has {} variables", dm_vars.len());
+//! # }
 //! ```
 
 use std::path::{Path, PathBuf};
 
 mod adam;
+mod ct;
 mod error;
 mod loader;
 mod sdtm;
 mod send;
 mod types;
 
+pub use ct::load_terminology;
 pub use error::{Error, Result};
-pub use loader::load_standard;
-pub use types::{DatasetDef, Standard, VarType, Variable};
+pub use loader::{load_standard, parse_standard};
+pub use types::{Codelist, DatasetDef, Standard, Term, Terminology, VarType, Variable};
 
 // Re-export standard-specific loaders for direct access
 pub use adam::load_adam;
 pub use sdtm::load_sdtm;
 pub use send::load_send;
 
-/// Returns the path to the bundled CDISC metadata directory.
+/// Returns the path to the bundled CDISC metadata directory in the source tree.
 ///
-/// This directory contains SDTM, SEND, and ADaM metadata files.
+/// This directory contains SDTM, SEND, and ADaM metadata files. The path is
+/// fixed when the crate is compiled, so it only exists on the machine that
+/// built it; the bundled loaders such as [`sdtm_ig_v3_4`] do not depend on it.
 #[must_use]
 pub fn data_dir() -> PathBuf {
     Path::new(env!("CARGO_MANIFEST_DIR")).join("data")
 }
 
-/// Returns the path to a specific bundled standard.
+/// Returns the path to a specific bundled standard in the source tree.
+///
+/// See [`data_dir`] for where this path is valid.
 ///
 /// # Arguments
 ///
@@ -63,6 +84,18 @@ pub fn standard_path(standard: &str, ig_version: &str) -> PathBuf {
     data_dir().join(standard).join("ig").join(ig_version)
 }
 
+/// Parses a standard embedded from `data/<standard>/ig/<version>`.
+#[cfg(feature = "bundled")]
+macro_rules! bundled_standard {
+    ($dir:literal, $datasets:literal) => {
+        parse_standard(
+            include_str!(concat!("../data/", $dir, "/metadata.toml")),
+            include_str!(concat!("../data/", $dir, "/", $datasets)),
+            include_str!(concat!("../data/", $dir, "/Variables.csv")),
+        )
+    };
+}
+
 /// Loads the bundled SDTM-IG v3.4 standard.
 ///
 /// # Errors
@@ -77,8 +110,9 @@ pub fn standard_path(standard: &str, ig_version: &str) -> PathBuf {
 /// let standard = sdtm_ig_v3_4().unwrap();
 /// assert_eq!(standard.name, "SDTM-IG");
 /// ```
+#[cfg(feature = "bundled")]
 pub fn sdtm_ig_v3_4() -> Result<Standard> {
-    load_standard(&standard_path("sdtm", "v3.4"))
+    bundled_standard!("sdtm/ig/v3.4", "Datasets.csv")
 }
 
 /// Loads the bundled SEND-IG v3.1.1 standard.
@@ -95,8 +129,9 @@ pub fn sdtm_ig_v3_4() -> Result<Standard> {
 /// let standard = send_ig_v3_1_1().unwrap();
 /// assert_eq!(standard.name, "SEND-IG");
 /// ```
+#[cfg(feature = "bundled")]
 pub fn send_ig_v3_1_1() -> Result<Standard> {
-    load_standard(&standard_path("send", "v3.1.1"))
+    bundled_standard!("send/ig/v3.1.1", "Datasets.csv")
 }
 
 /// Loads the bundled ADaM-IG v1.3 standard.
@@ -113,8 +148,9 @@ pub fn send_ig_v3_1_1() -> Result<Standard> {
 /// let standard = adam_ig_v1_3().unwrap();
 /// assert_eq!(standard.name, "ADaM-IG");
 /// ```
+#[cfg(feature = "bundled")]
 pub fn adam_ig_v1_3() -> Result<Standard> {
-    load_standard(&standard_path("adam", "v1.3"))
+    bundled_standard!("adam/ig/v1.3", "DataStructures.csv")
 }
 
 #[cfg(test)]
@@ -134,6 +170,18 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "bundled")]
+    fn test_bundled_matches_directory() {
+        let bundled = sdtm_ig_v3_4().unwrap();
+        let loaded = load_standard(&standard_path("sdtm", "v3.4")).unwrap();
+        assert_eq!(bundled.version, loaded.version);
+        assert_eq!(bundled.effective_date, loaded.effective_date);
+        assert_eq!(bundled.datasets.len(), loaded.datasets.len());
+        assert_eq!(bundled.variables.len(), loaded.variables.len());
+    }
+
+    #[test]
+    #[cfg(feature = "bundled")]
     fn test_load_bundled_sdtm() {
         let standard = sdtm_ig_v3_4().expect("Failed to load SDTM-IG v3.4");
         assert_eq!(standard.name, "SDTM-IG");
@@ -143,6 +191,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "bundled")]
     fn test_load_bundled_send() {
         let standard = send_ig_v3_1_1().expect("Failed to load SEND-IG v3.1.1");
         assert_eq!(standard.name, "SEND-IG");
@@ -151,6 +200,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "bundled")]
     fn test_load_bundled_adam() {
         let standard = adam_ig_v1_3().expect("Failed to load ADaM-IG v1.3");
         assert_eq!(standard.name, "ADaM-IG");
@@ -5,10 +5,10 @@ use std::path::Path;
 
 use serde::Deserialize;
 
-use crate::adam::load_adam;
+use crate::adam::{load_adam, parse_adam};
 use crate::error::{Error, Result};
-use crate::sdtm::load_sdtm;
-use crate::send::load_send;
+use crate::sdtm::{load_sdtm, parse_sdtm};
+use crate::send::{load_send, parse_send};
 use crate::types::Standard;
 
 /// Metadata.toml structure.
@@ -56,23 +56,87 @@ pub fn load_standard(dir: &Path) -> Result<Standard> {
     let metadata: MetadataToml = toml::from_str(&content)?;
 
     // Load the appropriate standard based on publishing_set
-    let mut standard = match metadata.standard.publishing_set.to_uppercase().as_str() {
+    let standard = match metadata.standard.publishing_set.to_uppercase().as_str() {
         "SDTM" => load_sdtm(dir)?,
         "SEND" => load_send(dir)?,
         "ADAM" => load_adam(dir)?,
         other => return Err(Error::UnknownStandard(other.to_string())),
     };
 
-    // Populate version info from metadata.toml
-    standard.name = metadata.standard.name;
-    standard.version = metadata.standard.version;
-    if let Some(full_name) = metadata.standard.full_name {
+    Ok(with_metadata(standard, metadata.standard))
+}
+
+/// Parse a CDISC standard from the contents of its files.
+///
+/// This is the in-memory counterpart of [`load_standard`], for metadata
+/// that is embedded in the binary or obtained other than from disk.
+/// `datasets` is the content of `Datasets.csv` (`DataStructures.csv` for
+/// ADaM) and `variables` that of `Variables.csv`.
+///
+/// # Example
+///
+/// ```
+/// use cdisc_metadata::parse_standard;
+///
+/// let metadata = r#"
+/// [standard]
+/// name = "SDTM-IG"
+/// version = "3.4"
+/// publishing_set = "SDTM"
+/// "#;
+/// let datasets = "\
+/// Version,Class,Dataset Name,Dataset Label,Structure
+/// SDTMIG v3.4,Special-Purpose,DM,Demographics,One record per subject
+/// ";
+/// let variables = "\
+/// Version,Variable Order,Class,Dataset Name,Variable Name,Variable Label,Type,Role,Core
+/// SDTMIG v3.4,1,Special-Purpose,DM,STUDYID,Study Identifier,Char,Identifier,Req
+/// ";
+///
+/// let standard = parse_standard(metadata, datasets, variables)?;
+/// assert_eq!(standard.dataset("DM").unwrap().label, "Demographics");
+/// assert_eq!(standard.variables_for_dataset("DM").len(), 1);
+/// # Ok::<(), cdisc_metadata::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The metadata is not valid TOML
+/// - The CSV content is malformed
+/// - The standard type is unknown
+pub fn parse_standard(metadata: &str, datasets: &str, variables: &str) -> Result<Standard> {
+    let metadata: MetadataToml = toml::from_str(metadata)?;
+
+    let standard = match metadata.standard.publishing_set.to_uppercase().as_str() {
+        "SDTM" => parse_sdtm(datasets, variables)?,
+        "SEND" => parse_send(datasets, variables)?,
+        "ADAM" => parse_adam(datasets, variables)?,
+        other => return Err(Error::UnknownStandard(other.to_string())),
+    };
+
+    Ok(with_metadata(standard, metadata.standard))
+}
+
+/// Populate version info from metadata.toml.
+fn with_metadata(mut standard: Standard, info: StandardInfo) -> Standard {
+    standard.name = info.name;
+    standard.version = info.version;
+    if let Some(full_name) = info.full_name {
         standard.full_name = full_name;
     }
-    standard.publishing_set = metadata.standard.publishing_set;
-    standard.effective_date = metadata.standard.effective_date;
+    standard.publishing_set = info.publishing_set;
+    standard.effective_date = info.effective_date;
+    standard
+}
 
-    Ok(standard)
+/// Splits a `;`-separated list of codelist codes or submission values.
+pub(crate) fn split_list(s: &str) -> Vec<String> {
+    s.split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
 }
 
 #[cfg(test)]
@@ -106,4 +170,13 @@ mod tests {
         assert_eq!(standard.version, "1.3");
         assert_eq!(standard.publishing_set, "ADaM");
     }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list("C66727; C114118;C150811"),
+            ["C66727", "C114118", "C150811"]
+        );
+        assert!(split_list("").is_empty());
+    }
 }
@@ -1,8 +1,10 @@
 //! SEND-IG (Standard for Exchange of Nonclinical Data) metadata parser.
 
+use std::io::Read;
 use std::path::Path;
 
 use crate::error::{Error, Result};
+use crate::loader::split_list;
 use crate::types::{DatasetDef, Standard, VarType, Variable};
 
 /// Load SEND-IG metadata from a directory.
@@ -18,7 +20,19 @@ pub fn load_send(dir: &Path) -> Result<Standard> {
     let datasets = load_datasets(dir)?;
     let variables = load_variables(dir)?;
 
-    Ok(Standard {
+    Ok(standard(datasets, variables))
+}
+
+/// Parse SEND-IG metadata from the contents of `Datasets.csv` and `Variables.csv`.
+pub(crate) fn parse_send(datasets: &str, variables: &str) -> Result<Standard> {
+    let datasets = read_datasets(csv::Reader::from_reader(datasets.as_bytes()))?;
+    let variables = read_variables(csv::Reader::from_reader(variables.as_bytes()))?;
+    Ok(standard(datasets, variables))
+}
+
+/// Build the SEND-IG standard from its definitions.
+fn standard(datasets: Vec<DatasetDef>, variables: Vec<Variable>) -> Standard {
+    Standard {
         name: "SEND-IG".to_string(),
         version: String::new(),
         full_name: "Standard for Exchange of Nonclinical Data Implementation Guide".to_string(),
@@ -26,7 +40,7 @@ pub fn load_send(dir: &Path) -> Result<Standard> {
         effective_date: None,
         datasets,
         variables,
-    })
+    }
 }
 
 /// Load dataset definitions from Datasets.csv.
@@ -36,7 +50,11 @@ fn load_datasets(dir: &Path) -> Result<Vec<DatasetDef>> {
         return Err(Error::MissingFile(path));
     }
 
-    let mut reader = csv::Reader::from_path(&path)?;
+    read_datasets(csv::Reader::from_path(&path)?)
+}
+
+/// Read dataset definitions from the contents of Datasets.csv.
+fn read_datasets<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<DatasetDef>> {
     let mut datasets = Vec::new();
 
     for result in reader.records() {
@@ -68,7 +86,11 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
         return Err(Error::MissingFile(path));
     }
 
-    let mut reader = csv::Reader::from_path(&path)?;
+    read_variables(csv::Reader::from_path(&path)?)
+}
+
+/// Read variable definitions from the contents of Variables.csv.
+fn read_variables<R: Read>(mut reader: csv::Reader<R>) -> Result<Vec<Variable>> {
     let mut variables = Vec::new();
 
     for result in reader.records() {
@@ -93,6 +115,9 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
             .map(|s| s.to_string())
             .filter(|s| !s.is_empty());
 
+        let codelist_codes = split_list(record.get(7).unwrap_or(""));
+        let codelist_values = split_list(record.get(8).unwrap_or(""));
+
         let var_type = if type_str.eq_ignore_ascii_case("Num") {
             VarType::Num
         } else {
@@ -108,6 +133,8 @@ fn load_variables(dir: &Path) -> Result<Vec<Variable>> {
                 dataset,
                 role,
                 core,
+                codelist_codes,
+                codelist_values,
                 notes,
             });
         }
@@ -42,6 +42,10 @@ pub struct Variable {
     pub role: Option<String>,
     /// Core status (Req, Exp, Perm).
     pub core: Option<String>,
+    /// CDISC Controlled Terminology codelist codes (e.g. "C66769").
+    pub codelist_codes: Vec<String>,
+    /// CDISC Controlled Terminology codelist submission values (e.g. "AESEV").
+    pub codelist_values: Vec<String>,
     /// CDISC notes about this variable.
     pub notes: Option<String>,
 }
@@ -59,6 +63,9 @@ pub struct DatasetDef {
     pub structure: Option<String>,
 }
 
+/// ADaM datasets of occurrence data, which follow OCCDS rather than BDS.
+const ADAM_OCCURRENCE_DATASETS: &[&str] = &["ADAE", "ADCE", "ADCM", "ADDV", "ADHO", "ADMH", "ADPR"];
+
 /// A loaded CDISC standard with its metadata.
 #[derive(Debug, Clone)]
 pub struct Standard {
@@ -80,14 +87,65 @@ pub struct Standard {
 
 impl Standard {
     /// Get all variables for a specific dataset/domain.
+    ///
+    /// ADaM datasets get the variables of their data structure, see
+    /// [`structure_name`](Self::structure_name).
     #[must_use]
     pub fn variables_for_dataset(&self, dataset_name: &str) -> Vec<&Variable> {
+        let name = self.structure_name(dataset_name);
+        // ADaM Variables.csv names a structure by the start of its description
+        let description = self
+            .is_adam()
+            .then(|| self.dataset(name))
+            .flatten()
+            .map(|d| d.label.to_ascii_lowercase());
         self.variables
             .iter()
-            .filter(|v| v.dataset.eq_ignore_ascii_case(dataset_name))
+            .filter(|v| {
+                v.dataset.eq_ignore_ascii_case(name)
+                    || description.as_deref().is_some_and(|d| {
+                        !v.dataset.is_empty() && d.starts_with(&v.dataset.to_ascii_lowercase())
+                    })
+            })
             .collect()
     }
 
+    /// Get the name of the definition a dataset follows.
+    ///
+    /// SDTM and SEND define each dataset by name. ADaM defines data
+    /// structures instead: `ADSL` is its own structure, occurrence datasets
+    /// (`ADAE`, `ADCM`, `ADMH`, ...) follow `OCCDS`, and other analysis
+    /// datasets (`ADLB`, `ADVS`, `ADTTE`, ...) follow the Basic Data
+    /// Structure, `BDS`. Names the standard defines are returned as they are.
+    #[must_use]
+    pub fn structure_name<'a>(&self, dataset_name: &'a str) -> &'a str {
+        let is_analysis = dataset_name
+            .get(..2)
+            .is_some_and(|p| p.eq_ignore_ascii_case("AD"));
+        if !self.is_adam() || !is_analysis || self.dataset(dataset_name).is_some() {
+            dataset_name
+        } else if ADAM_OCCURRENCE_DATASETS
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(dataset_name))
+        {
+            "OCCDS"
+        } else {
+            "BDS"
+        }
+    }
+
+    /// Get the definition a dataset follows: its own definition, or for
+    /// ADaM its data structure, see [`structure_name`](Self::structure_name).
+    #[must_use]
+    pub fn structure(&self, dataset_name: &str) -> Option<&DatasetDef> {
+        self.dataset(self.structure_name(dataset_name))
+    }
+
+    /// Returns true if this is an ADaM standard.
+    fn is_adam(&self) -> bool {
+        self.publishing_set.eq_ignore_ascii_case("ADaM")
+    }
+
     /// Get a dataset definition by name.
     #[must_use]
     pub fn dataset(&self, name: &str) -> Option<&DatasetDef> {
@@ -102,3 +160,79 @@ impl Standard {
         self.datasets.iter().map(|d| d.name.as_str()).collect()
     }
 }
+
+/// A term of a controlled terminology codelist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Term {
+    /// NCI code of the term (e.g. "C41338").
+    pub code: String,
+    /// CDISC submission value (e.g. "MILD").
+    pub submission_value: String,
+    /// CDISC synonyms.
+    pub synonyms: Vec<String>,
+    /// CDISC definition.
+    pub definition: Option<String>,
+    /// NCI preferred term.
+    pub preferred_term: Option<String>,
+}
+
+/// A controlled terminology codelist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Codelist {
+    /// NCI code of the codelist (e.g. "C66769").
+    pub code: String,
+    /// Codelist name (e.g. "Severity/Intensity Scale for Adverse Events").
+    pub name: String,
+    /// CDISC submission value of the codelist (e.g. "AESEV").
+    pub submission_value: String,
+    /// Whether sponsors may add terms to the codelist.
+    pub extensible: bool,
+    /// The terms of the codelist.
+    pub terms: Vec<Term>,
+}
+
+impl Codelist {
+    /// Returns true if `value` is the submission value of a term.
+    #[must_use]
+    pub fn contains(&self, value: &str) -> bool {
+        self.term(value).is_some()
+    }
+
+    /// Get a term by submission value.
+    #[must_use]
+    pub fn term(&self, value: &str) -> Option<&Term> {
+        self.terms.iter().find(|t| t.submission_value == value)
+    }
+}
+
+/// A loaded controlled terminology package.
+#[derive(Debug, Clone, Default)]
+pub struct Terminology {
+    /// All codelists, in file order.
+    pub codelists: Vec<Codelist>,
+}
+
+impl Terminology {
+    /// Get a codelist by NCI code or submission value.
+    #[must_use]
+    pub fn codelist(&self, key: &str) -> Option<&Codelist> {
+        self.codelists
+            .iter()
+            .find(|c| c.code.eq_ignore_ascii_case(key) || c.submission_value == key)
+    }
+
+    /// Get the codelists a variable refers to.
+    ///
+    /// Codelists are looked up by NCI code, falling back to the submission
+    /// values when the variable lists no codes. Returns `None` if any of them
+    /// is not in this package.
+    #[must_use]
+    pub fn codelists_for(&self, variable: &Variable) -> Option<Vec<&Codelist>> {
+        let keys = if variable.codelist_codes.is_empty() {
+            &variable.codelist_values
+        } else {
+            &variable.codelist_codes
+        };
+        keys.iter().map(|key| self.codelist(key)).collect()
+    }
+}
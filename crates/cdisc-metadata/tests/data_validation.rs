@@ -1,5 +1,7 @@
 //! Tests for validating the bundled CDISC metadata content.
 
+#![cfg(feature = "bundled")]
+
 use cdisc_metadata::{adam_ig_v1_3, sdtm_ig_v3_4, send_ig_v3_1_1};
 
 // =============================================================================
@@ -126,6 +128,17 @@ fn sdtm_datasets_have_non_empty_names() {
     }
 }
 
+#[test]
+fn sdtm_variables_have_codelists() {
+    let standard = sdtm_ig_v3_4().unwrap();
+    let ae_vars = standard.variables_for_dataset("AE");
+    let aesev = ae_vars.iter().find(|v| v.name == "AESEV").unwrap();
+    assert_eq!(aesev.codelist_codes, ["C66769"]);
+
+    let aeterm = ae_vars.iter().find(|v| v.name == "AETERM").unwrap();
+    assert!(aeterm.codelist_codes.is_empty());
+}
+
 // =============================================================================
 // SEND Data Validation Tests
 // =============================================================================
@@ -195,6 +208,15 @@ fn send_dm_has_required_variables() {
     );
 }
 
+#[test]
+fn send_variables_have_codelist_values() {
+    let standard = send_ig_v3_1_1().unwrap();
+    let ex_vars = standard.variables_for_dataset("EX");
+    let exroute = ex_vars.iter().find(|v| v.name == "EXROUTE").unwrap();
+    assert_eq!(exroute.codelist_codes, ["C66729"]);
+    assert_eq!(exroute.codelist_values, ["ROUTE"]);
+}
+
 // =============================================================================
 // ADaM Data Validation Tests
 // =============================================================================
@@ -247,6 +269,32 @@ fn adam_adsl_has_required_variables() {
     assert!(var_names.contains(&"USUBJID"), "ADSL should have USUBJID");
 }
 
+#[test]
+fn adam_datasets_follow_their_structure() {
+    let standard = adam_ig_v1_3().unwrap();
+    let names = |dataset: &str| -> Vec<String> {
+        standard
+            .variables_for_dataset(dataset)
+            .iter()
+            .map(|v| v.name.clone())
+            .collect()
+    };
+
+    assert_eq!(standard.structure_name("ADSL"), "ADSL");
+    assert!(names("ADSL").contains(&"AGE".to_string()));
+
+    assert_eq!(standard.structure_name("ADLB"), "BDS");
+    assert_eq!(names("ADLB"), names("BDS"));
+    assert!(names("ADLB").contains(&"PARAMCD".to_string()));
+    assert_eq!(
+        standard.structure("ADLB").unwrap().class,
+        "BASIC DATA STRUCTURE"
+    );
+
+    assert_eq!(standard.structure_name("ADAE"), "OCCDS");
+    assert_eq!(standard.structure_name("DM"), "DM");
+}
+
 #[test]
 fn adam_variables_have_valid_types() {
     let standard = adam_ig_v1_3().unwrap();
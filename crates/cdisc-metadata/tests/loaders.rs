@@ -1,9 +1,8 @@
 //! Tests for loading functions and bundled standards.
 
-use cdisc_metadata::{
-    adam_ig_v1_3, data_dir, load_adam, load_sdtm, load_send, load_standard, sdtm_ig_v3_4,
-    send_ig_v3_1_1, standard_path,
-};
+#[cfg(feature = "bundled")]
+use cdisc_metadata::{adam_ig_v1_3, sdtm_ig_v3_4, send_ig_v3_1_1};
+use cdisc_metadata::{data_dir, load_adam, load_sdtm, load_send, load_standard, standard_path};
 
 // =============================================================================
 // Path Functions Tests
@@ -60,6 +59,7 @@ fn standard_path_adam() {
 // =============================================================================
 
 #[test]
+#[cfg(feature = "bundled")]
 fn sdtm_ig_v3_4_loads_successfully() {
     let result = sdtm_ig_v3_4();
     assert!(
@@ -70,6 +70,7 @@ fn sdtm_ig_v3_4_loads_successfully() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn sdtm_ig_v3_4_has_correct_metadata() {
     let standard = sdtm_ig_v3_4().unwrap();
 
@@ -80,6 +81,7 @@ fn sdtm_ig_v3_4_has_correct_metadata() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn send_ig_v3_1_1_loads_successfully() {
     let result = send_ig_v3_1_1();
     assert!(
@@ -90,6 +92,7 @@ fn send_ig_v3_1_1_loads_successfully() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn send_ig_v3_1_1_has_correct_metadata() {
     let standard = send_ig_v3_1_1().unwrap();
 
@@ -100,6 +103,7 @@ fn send_ig_v3_1_1_has_correct_metadata() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn adam_ig_v1_3_loads_successfully() {
     let result = adam_ig_v1_3();
     assert!(
@@ -110,6 +114,7 @@ fn adam_ig_v1_3_loads_successfully() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn adam_ig_v1_3_has_correct_metadata() {
     let standard = adam_ig_v1_3().unwrap();
 
@@ -192,6 +197,7 @@ fn load_adam_direct() {
 // =============================================================================
 
 #[test]
+#[cfg(feature = "bundled")]
 fn bundled_and_direct_loaders_produce_same_result_sdtm() {
     let bundled = sdtm_ig_v3_4().unwrap();
     let direct = load_sdtm(&standard_path("sdtm", "v3.4")).unwrap();
@@ -201,6 +207,7 @@ fn bundled_and_direct_loaders_produce_same_result_sdtm() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn bundled_and_direct_loaders_produce_same_result_send() {
     let bundled = send_ig_v3_1_1().unwrap();
     let direct = load_send(&standard_path("send", "v3.1.1")).unwrap();
@@ -210,6 +217,7 @@ fn bundled_and_direct_loaders_produce_same_result_send() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn bundled_and_direct_loaders_produce_same_result_adam() {
     let bundled = adam_ig_v1_3().unwrap();
     let direct = load_adam(&standard_path("adam", "v1.3")).unwrap();
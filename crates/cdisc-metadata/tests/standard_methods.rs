@@ -1,5 +1,7 @@
 //! Tests for Standard struct methods.
 
+#![cfg(feature = "bundled")]
+
 use cdisc_metadata::{adam_ig_v1_3, sdtm_ig_v3_4, send_ig_v3_1_1};
 
 // =============================================================================
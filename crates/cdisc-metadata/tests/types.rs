@@ -1,6 +1,8 @@
 //! Tests for core types: VarType, Variable, DatasetDef, Standard.
 
-use cdisc_metadata::{VarType, sdtm_ig_v3_4};
+use cdisc_metadata::VarType;
+#[cfg(feature = "bundled")]
+use cdisc_metadata::sdtm_ig_v3_4;
 
 // =============================================================================
 // VarType Tests
@@ -47,6 +49,7 @@ fn var_type_debug() {
 // =============================================================================
 
 #[test]
+#[cfg(feature = "bundled")]
 fn variable_from_standard_has_required_fields() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let var = standard
@@ -62,6 +65,7 @@ fn variable_from_standard_has_required_fields() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn variable_types_are_correct() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
 
@@ -81,6 +85,7 @@ fn variable_types_are_correct() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn variable_clone_preserves_all_fields() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let var = &standard.variables[0];
@@ -101,6 +106,7 @@ fn variable_clone_preserves_all_fields() {
 // =============================================================================
 
 #[test]
+#[cfg(feature = "bundled")]
 fn dataset_def_from_standard_has_required_fields() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let dm = standard.dataset("DM").expect("DM dataset should exist");
@@ -111,6 +117,7 @@ fn dataset_def_from_standard_has_required_fields() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn dataset_def_clone_preserves_all_fields() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let dataset = &standard.datasets[0];
@@ -127,6 +134,7 @@ fn dataset_def_clone_preserves_all_fields() {
 // =============================================================================
 
 #[test]
+#[cfg(feature = "bundled")]
 fn standard_has_metadata() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
 
@@ -137,6 +145,7 @@ fn standard_has_metadata() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn standard_clone_preserves_all_data() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let cloned = standard.clone();
@@ -151,6 +160,7 @@ fn standard_clone_preserves_all_data() {
 }
 
 #[test]
+#[cfg(feature = "bundled")]
 fn standard_debug_format() {
     let standard = sdtm_ig_v3_4().expect("Failed to load SDTM");
     let debug = format!("{:?}", standard);
@@ -25,6 +25,7 @@ use std::path::Path;
 
 pub use rules::Rule;
 
+use crate::dataset::Dataset;
 use crate::schema::DatasetSchema;
 use crate::validate::Issue;
 use crate::xpt::XptVersion;
@@ -227,16 +228,48 @@ impl Agency {
     ///
     /// Applies all [`Rule`] items for this agency and returns any [`Issue`] items found.
     #[must_use]
-    pub(crate) fn validate(self, plan: &DatasetSchema, file_path: Option<&Path>) -> Vec<Issue> {
+    pub(crate) fn validate(self, plan: &DatasetSchema) -> Vec<Issue> {
         let mut issues = Vec::new();
         let agency_name = self.name();
 
         for rule in self.rules() {
-            issues.extend(rule.validate(plan, file_path, agency_name));
+            issues.extend(rule.validate(plan, agency_name));
         }
 
         issues
     }
+
+    /// Validates the output file of a schema plan against this agency's
+    /// requirements.
+    ///
+    /// Applies the [`Rule`] items that concern the file, such as
+    /// [`Rule::DatasetNameMatchesFileStem`] and [`Rule::MaxFileSizeGb`].
+    /// `file_size` is the estimated size in bytes of the largest file that
+    /// will be written.
+    #[must_use]
+    pub(crate) fn validate_path(
+        self,
+        plan: &DatasetSchema,
+        path: &Path,
+        file_size: usize,
+    ) -> Vec<Issue> {
+        self.rules()
+            .iter()
+            .flat_map(|rule| rule.validate_path(plan, path, file_size, self.name()))
+            .collect()
+    }
+
+    /// Validates the data of a dataset against this agency's requirements.
+    ///
+    /// Applies the [`Rule`] items that concern character values, such as
+    /// [`Rule::RequireAsciiCharacterValues`].
+    #[must_use]
+    pub(crate) fn validate_data(self, dataset: &Dataset, plan: &DatasetSchema) -> Vec<Issue> {
+        self.rules()
+            .iter()
+            .flat_map(|rule| rule.validate_data(dataset, plan))
+            .collect()
+    }
 }
 
 impl std::fmt::Display for Agency {
@@ -279,7 +312,7 @@ mod tests {
         ];
         plan.recalculate_positions();
 
-        let issues = Agency::FDA.validate(&plan, None);
+        let issues = Agency::FDA.validate(&plan);
         assert!(!issues.iter().any(|i| i.severity() == Severity::Error));
     }
 
@@ -289,7 +322,7 @@ mod tests {
         plan.variables = vec![VariableSpec::numeric("AESEQ")];
         plan.recalculate_positions();
 
-        let issues = Agency::FDA.validate(&plan, None);
+        let issues = Agency::FDA.validate(&plan);
         assert!(
             issues
                 .iter()
@@ -349,7 +382,7 @@ mod tests {
         plan.variables = vec![VariableSpec::numeric("AESEQ")];
         plan.recalculate_positions();
 
-        let issues = Agency::PMDA.validate(&plan, None);
+        let issues = Agency::PMDA.validate(&plan);
         // Should NOT have any errors (warnings are OK)
         assert!(!issues.iter().any(|i| i.severity() == Severity::Error));
     }
@@ -361,7 +394,7 @@ mod tests {
         plan.variables = vec![VariableSpec::numeric("AESEQ")];
         plan.recalculate_positions();
 
-        let issues = Agency::NMPA.validate(&plan, None);
+        let issues = Agency::NMPA.validate(&plan);
         // Should NOT have any errors (warnings are OK)
         assert!(!issues.iter().any(|i| i.severity() == Severity::Error));
     }
@@ -373,7 +406,7 @@ mod tests {
         plan.variables = vec![VariableSpec::numeric("AESEQ")];
         plan.recalculate_positions();
 
-        let issues = Agency::FDA.validate(&plan, None);
+        let issues = Agency::FDA.validate(&plan);
         // Should have NonAsciiDatasetLabel error
         assert!(
             issues
@@ -382,6 +415,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fda_rejects_non_ascii_values() {
+        use crate::dataset::{Column, ColumnData};
+
+        let dataset = Dataset::new(
+            "AE",
+            vec![Column::new(
+                "AETERM",
+                ColumnData::String(vec![
+                    Some("HEADACHE".into()),
+                    None,
+                    Some("Céphalée".into()),
+                    Some("Übelkeit".into()),
+                ]),
+            )],
+        )
+        .unwrap();
+        let mut plan = DatasetSchema::new("AE");
+        plan.variables = vec![VariableSpec::character("AETERM", 20).with_source_index(0)];
+        plan.recalculate_positions();
+
+        assert_eq!(
+            Agency::FDA.validate_data(&dataset, &plan),
+            [Issue::NonAsciiValue {
+                variable: "AETERM".into(),
+                row: 2,
+                count: 2,
+            }]
+        );
+        assert!(Agency::PMDA.validate_data(&dataset, &plan).is_empty());
+    }
+
+    #[test]
+    fn test_agency_validate_path() {
+        let mut plan = DatasetSchema::new("AE");
+        plan.variables = vec![VariableSpec::numeric("AESEQ")];
+        plan.recalculate_positions();
+
+        assert!(
+            Agency::FDA
+                .validate_path(&plan, Path::new("out/ae.xpt"), 1024)
+                .is_empty()
+        );
+        assert_eq!(
+            Agency::FDA.validate_path(&plan, Path::new("out/adae.xpt"), 6 << 30),
+            [
+                Issue::DatasetNameFileStemMismatch {
+                    dataset: "AE".into(),
+                    stem: "adae".into(),
+                },
+                Issue::FileSizeExceeded {
+                    dataset: "AE".into(),
+                    agency: "FDA",
+                    size: 6 << 30,
+                    max: 5 << 30,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_multibyte_label_warning() {
         let mut plan = DatasetSchema::new("AE");
@@ -391,7 +484,7 @@ mod tests {
         plan.variables = vec![VariableSpec::numeric("AESEQ")];
         plan.recalculate_positions();
 
-        let issues = Agency::PMDA.validate(&plan, None);
+        let issues = Agency::PMDA.validate(&plan);
         // Should have a warning about approaching byte limit
         assert!(
             issues
@@ -7,8 +7,10 @@ use std::path::Path;
 
 use regex::Regex;
 
+use crate::dataset::Dataset;
 use crate::schema::DatasetSchema;
-use crate::validate::Issue;
+use crate::validate::{Issue, character_values, merge_value_issues};
+use crate::xpt::v5::write::gb_to_bytes;
 
 /// A single validation rule within an agency's requirements.
 ///
@@ -83,14 +85,10 @@ impl Rule {
 
     /// Validates a schema plan against this rule.
     ///
-    /// Returns a list of issues found during validation.
+    /// Returns a list of issues found during validation. Rules on the output
+    /// file are checked by [`validate_path`](Self::validate_path).
     #[must_use]
-    pub(crate) fn validate(
-        &self,
-        plan: &DatasetSchema,
-        file_path: Option<&Path>,
-        agency_name: &'static str,
-    ) -> Vec<Issue> {
+    pub(crate) fn validate(&self, plan: &DatasetSchema, agency_name: &'static str) -> Vec<Issue> {
         let mut issues = Vec::new();
 
         match self {
@@ -116,18 +114,6 @@ impl Rule {
                 }
             }
 
-            Self::DatasetNameMatchesFileStem => {
-                if let Some(path) = file_path
-                    && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
-                    && !stem.eq_ignore_ascii_case(&plan.domain_code)
-                {
-                    issues.push(Issue::DatasetNameFileStemMismatch {
-                        dataset: plan.domain_code.clone(),
-                        stem: stem.to_string(),
-                    });
-                }
-            }
-
             Self::RequireAsciiNames => {
                 if !plan.domain_code.is_ascii() {
                     issues.push(Issue::NonAsciiDatasetName {
@@ -162,27 +148,30 @@ impl Rule {
                 }
             }
 
-            Self::RequireAsciiCharacterValues => {
-                // Note: This is checked during data encoding, not schema validation
+            Self::RequireAsciiCharacterValues
+            | Self::DatasetNameMatchesFileStem
+            | Self::MaxFileSizeGb(_) => {
+                // Checked against the data by `validate_data`, and against the
+                // output file by `validate_path`
             }
 
             Self::DatasetNameMaxBytes(max) => {
-                if plan.domain_code.len() > *max {
+                if plan.byte_len(&plan.domain_code) > *max {
                     issues.push(Issue::AgencyDatasetNameTooLong {
                         dataset: plan.domain_code.clone(),
                         max: *max,
-                        actual: plan.domain_code.len(),
+                        actual: plan.byte_len(&plan.domain_code),
                     });
                 }
             }
 
             Self::VariableNameMaxBytes(max) => {
                 for var in &plan.variables {
-                    if var.name.len() > *max {
+                    if plan.byte_len(&var.name) > *max {
                         issues.push(Issue::AgencyVariableNameTooLong {
                             variable: var.name.clone(),
                             max: *max,
-                            actual: var.name.len(),
+                            actual: plan.byte_len(&var.name),
                         });
                     }
                 }
@@ -191,7 +180,7 @@ impl Rule {
             Self::LabelMaxBytes(max) => {
                 // Check dataset label
                 if let Some(ref label) = plan.dataset_label {
-                    let byte_len = label.len();
+                    let byte_len = plan.byte_len(label);
                     if byte_len > *max {
                         issues.push(Issue::AgencyLabelTooLong {
                             name: plan.domain_code.clone(),
@@ -213,7 +202,7 @@ impl Rule {
 
                 // Check variable labels
                 for var in &plan.variables {
-                    let byte_len = var.label.len();
+                    let byte_len = plan.byte_len(&var.label);
                     if byte_len > *max {
                         issues.push(Issue::AgencyLabelTooLong {
                             name: var.name.clone(),
@@ -246,9 +235,74 @@ impl Rule {
                     }
                 }
             }
+        }
+
+        issues
+    }
 
-            Self::MaxFileSizeGb(_max) => {
-                // File size check is done during write, not schema validation
+    /// Validates the output file of a schema plan against this rule.
+    ///
+    /// `file_size` is the estimated size in bytes of the largest file that
+    /// will be written, after any splitting. Only rules on the output file
+    /// apply; all other rules are checked by [`validate`](Self::validate).
+    #[must_use]
+    pub(crate) fn validate_path(
+        &self,
+        plan: &DatasetSchema,
+        path: &Path,
+        file_size: usize,
+        agency_name: &'static str,
+    ) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        match self {
+            Self::DatasetNameMatchesFileStem => {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+                    && !stem.eq_ignore_ascii_case(&plan.domain_code)
+                {
+                    issues.push(Issue::DatasetNameFileStemMismatch {
+                        dataset: plan.domain_code.clone(),
+                        stem: stem.to_string(),
+                    });
+                }
+            }
+
+            Self::MaxFileSizeGb(max) => {
+                let max_bytes = gb_to_bytes(*max);
+                if file_size > max_bytes {
+                    issues.push(Issue::FileSizeExceeded {
+                        dataset: plan.domain_code.clone(),
+                        agency: agency_name,
+                        size: file_size,
+                        max: max_bytes,
+                    });
+                }
+            }
+
+            _ => {}
+        }
+
+        issues
+    }
+
+    /// Validates the data of a dataset against this rule.
+    ///
+    /// Only rules on character values look at the data; all other rules
+    /// are checked against the schema by [`validate`](Self::validate).
+    #[must_use]
+    pub(crate) fn validate_data(&self, dataset: &Dataset, plan: &DatasetSchema) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        if let Self::RequireAsciiCharacterValues = self {
+            for var in plan.variables.iter().filter(|v| v.xpt_type.is_character()) {
+                let non_ascii = character_values(dataset, var)
+                    .filter(|(_, value)| !value.is_ascii())
+                    .map(|(row, _)| Issue::NonAsciiValue {
+                        variable: var.name.clone(),
+                        row,
+                        count: 1,
+                    });
+                merge_value_issues(&mut issues, non_ascii, 0);
             }
         }
 
@@ -30,11 +30,13 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
-use crate::config::ReadOptions;
+use crate::agency::Agency;
+use crate::config::{ReadOptions, TextMode};
 use crate::dataset::Dataset;
 use crate::error::{Error, Result};
-use crate::write_plan::XptWriterBuilder;
-use crate::xpt::v5::read::{XptInfo, XptReader as V5Reader};
+use crate::validate::{MemberIssues, validate_file};
+use crate::write_plan::{XptLibraryWriter, XptWriterBuilder};
+use crate::xpt::v5::read::{XptBatchReader, XptInfo, XptReader as V5Reader};
 
 /// Unified entry point for XPT file operations.
 ///
@@ -72,13 +74,16 @@ use crate::xpt::v5::read::{XptInfo, XptReader as V5Reader};
 ///     .write_path("ae.xpt")?;
 /// # Ok::<(), xportrs::Error>(())
 /// ```
+///
+/// Use [`Xpt::library_writer`] to write several datasets into one file.
 pub struct Xpt;
 
 impl Xpt {
     /// Reads the first dataset from an XPT file with default options.
     ///
     /// This is the simplest way to read an XPT file. For files with a single
-    /// dataset (the common case), this is all you need.
+    /// dataset (the common case), this is all you need. Both V5 and V8 files
+    /// are supported; the version is detected from the file header.
     ///
     /// # Errors
     ///
@@ -168,10 +173,44 @@ impl Xpt {
         XptWriterBuilder::new(dataset)
     }
 
+    /// Creates a writer for a transport file with several members.
+    ///
+    /// Datasets are written as consecutive members of one file, in the order
+    /// they are added. The members can be read back with
+    /// [`XptReaderBuilder::read_all`] or [`XptReaderBuilder::read_member`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Agency, Dataset};
+    ///
+    /// # let dm = Dataset::new("DM", vec![]).unwrap();
+    /// # let ae = Dataset::new("AE", vec![]).unwrap();
+    /// let mut library = Xpt::library_writer();
+    /// library.add_dataset(dm)?;
+    ///
+    /// let mut builder = Xpt::writer(ae);
+    /// builder.agency(Agency::FDA);
+    /// library.add(builder.finalize()?);
+    ///
+    /// library.write_path("study.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn library_writer() -> XptLibraryWriter {
+        XptLibraryWriter::new()
+    }
+
     /// Inspects an XPT file without reading all data.
     ///
-    /// Returns an [`XptInfo`] containing file metadata. This is useful for
-    /// checking what's in an XPT file without loading all observations into memory.
+    /// Returns an [`XptInfo`] containing file metadata, including the detected
+    /// [`XptVersion`](crate::XptVersion). This is useful for checking what's in
+    /// an XPT file without loading all observations into memory.
+    ///
+    /// Only the headers up to the first member's observation data are read,
+    /// so only the first member is listed. Use
+    /// [`XptReaderBuilder::find_members`] to list all members of a file
+    /// with several members.
     ///
     /// # Errors
     ///
@@ -183,17 +222,113 @@ impl Xpt {
     /// use xportrs::Xpt;
     ///
     /// let info = Xpt::inspect("data.xpt")?;
-    /// for name in info.member_names() {
-    ///     println!("Member: {}", name);
+    /// println!("Version: {}", info.version);
+    /// for member in &info.members {
+    ///     println!("Member: {} ({} variables)", member.name, member.variables.len());
     /// }
     /// # Ok::<(), xportrs::Error>(())
     /// ```
     #[must_use = "this returns a Result that should be handled"]
     pub fn inspect(path: impl AsRef<Path>) -> Result<XptInfo> {
+        Self::inspect_with(path, TextMode::default())
+    }
+
+    /// Inspects an XPT file, decoding dataset and variable names and labels
+    /// with `mode`.
+    ///
+    /// See [`inspect`](Self::inspect).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or parsed, or with
+    /// [`TextMode::StrictUtf8`] if a name or label is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{TextMode, Xpt};
+    ///
+    /// let info = Xpt::inspect_with("ae.xpt", TextMode::ShiftJis)?;
+    /// if let Some(member) = info.members.first() {
+    ///     println!("{}: {}", member.name, member.label.as_deref().unwrap_or(""));
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn inspect_with(path: impl AsRef<Path>, mode: TextMode) -> Result<XptInfo> {
         let file = File::open(path.as_ref()).map_err(Error::Io)?;
-        let reader = V5Reader::new(BufReader::new(file))?;
+        let reader = V5Reader::with_text_mode(BufReader::new(file), mode)?;
         Ok(reader.file_info().clone())
     }
+
+    /// Validates an existing XPT file, returning the [`Issue`](crate::Issue)
+    /// items found in each member.
+    ///
+    /// Each member is checked as it is stored in the file:
+    ///
+    /// - The NAMESTR records against the structural requirements of the
+    ///   file's [`XptVersion`](crate::XptVersion)
+    /// - Variable positions that do not follow the preceding variables
+    /// - Header records within the observation data, incomplete records and
+    ///   padding that is not blank
+    /// - The [`Agency`] rules (if an agency is given), including the
+    ///   character values and the file name and size; the file name is only
+    ///   checked for files with a single member
+    ///
+    /// Problems are reported as issues rather than errors, so the whole file
+    /// is checked. Every observation is read, but values are not checked
+    /// against their variable lengths: a stored value always fits. Text is decoded with [`TextMode::LossyUtf8`]; use
+    /// [`validate_with`](Self::validate_with) for files in a legacy code page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or its headers cannot
+    /// be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Agency, Xpt};
+    ///
+    /// for member in Xpt::validate("ae.xpt", Some(Agency::FDA))? {
+    ///     for issue in &member.issues {
+    ///         println!("{}: {issue}", member.name);
+    ///     }
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn validate(path: impl AsRef<Path>, agency: Option<Agency>) -> Result<Vec<MemberIssues>> {
+        Self::validate_with(path, agency, TextMode::default())
+    }
+
+    /// Validates an existing XPT file, decoding names, labels and character
+    /// values with `mode`.
+    ///
+    /// See [`validate`](Self::validate).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or its headers cannot
+    /// be parsed, or with [`TextMode::StrictUtf8`] if text is not valid
+    /// UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Agency, TextMode, Xpt};
+    ///
+    /// let members = Xpt::validate_with("ae.xpt", Some(Agency::PMDA), TextMode::ShiftJis)?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn validate_with(
+        path: impl AsRef<Path>,
+        agency: Option<Agency>,
+        mode: TextMode,
+    ) -> Result<Vec<MemberIssues>> {
+        validate_file(path.as_ref(), agency, mode)
+    }
 }
 
 /// Builder for reading XPT files with custom options.
@@ -221,18 +356,149 @@ impl XptReaderBuilder {
         self
     }
 
+    /// Sets how character values are decoded.
+    ///
+    /// Defaults to [`TextMode::LossyUtf8`]. With [`TextMode::StrictUtf8`],
+    /// reading fails with [`Error::Encoding`] on the
+    /// first value that is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{TextMode, Xpt};
+    ///
+    /// let dm = Xpt::reader("dm.xpt")?
+    ///     .text_mode(TextMode::StrictUtf8)
+    ///     .read()?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn text_mode(mut self, mode: TextMode) -> Self {
+        self.options.text_mode = mode;
+        self
+    }
+
+    /// Keeps trailing blanks in character values.
+    ///
+    /// By default, the space padding of fixed-width character values is
+    /// trimmed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let dm = Xpt::reader("dm.xpt")?.preserve_blanks(true).read()?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn preserve_blanks(mut self, preserve: bool) -> Self {
+        self.options.preserve_blanks = preserve;
+        self
+    }
+
+    /// Reads at most `limit` observations of each member.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let preview = Xpt::reader("lb.xpt")?.row_limit(10).read()?;
+    /// assert!(preview.nrows() <= 10);
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn row_limit(mut self, limit: usize) -> Self {
+        self.options.row_limit = Some(limit);
+        self
+    }
+
+    /// Restricts reading to the named variables.
+    ///
+    /// Names are matched case-insensitively and the columns are returned in
+    /// file order. The other variables are skipped using their NAMESTR
+    /// positions rather than decoded. Applies to [`read`](Self::read),
+    /// [`read_member`](Self::read_member), [`read_all`](Self::read_all) and
+    /// the batch readers; reading fails if a member lacks one of the names.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let ae = Xpt::reader("ae.xpt")?
+    ///     .columns(["USUBJID", "AESTDTC"])
+    ///     .read()?;
+    /// assert_eq!(ae.ncols(), 2);
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Skips the first `rows` observations of each member read.
+    ///
+    /// Observations are fixed-width, so the reader seeks straight to the
+    /// first requested row instead of decoding the skipped ones. Skipping
+    /// past the end yields an empty dataset.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let rest = Xpt::reader("lb.xpt")?.skip_rows(1_000).read()?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn skip_rows(mut self, rows: usize) -> Self {
+        self.options.skip_rows = rows;
+        self
+    }
+
+    /// Reads only the observations in `range` (0-based, end exclusive).
+    ///
+    /// Equivalent to [`skip_rows`](Self::skip_rows) with `range.start` plus
+    /// a row limit of `range.len()`. Useful for paginated previews, or for
+    /// parallel readers that each take a slice of a large file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// // Third page of 50 rows
+    /// let page = Xpt::reader("lb.xpt")?.row_range(100..150).read()?;
+    /// assert!(page.nrows() <= 50);
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use]
+    pub fn row_range(mut self, range: std::ops::Range<usize>) -> Self {
+        self.options.skip_rows = range.start;
+        self.options.row_limit = Some(range.len());
+        self
+    }
+
     /// Returns file metadata without reading observation data.
     ///
+    /// Only the first member is included until
+    /// [`find_members`](Self::find_members) has been called.
+    ///
     /// # Example
     ///
     /// ```no_run
     /// use xportrs::Xpt;
     ///
-    /// let reader = Xpt::reader("study.xpt")?;
+    /// let reader = Xpt::reader("dm.xpt")?;
     /// let info = reader.info();
-    /// for name in info.member_names() {
-    ///     println!("Member: {}", name);
-    /// }
+    /// println!("Version: {}", info.version);
     /// # Ok::<(), xportrs::Error>(())
     /// ```
     #[must_use]
@@ -240,6 +506,32 @@ impl XptReaderBuilder {
         self.reader.file_info()
     }
 
+    /// Finds all members in the file and returns the file metadata.
+    ///
+    /// Members after the first are found by scanning the observation data
+    /// before them, so this reads the whole file up to the last member's
+    /// data. Reading a member after the first finds the members itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if reading fails or a member header is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let mut reader = Xpt::reader("study.xpt")?;
+    /// for name in reader.find_members()?.member_names() {
+    ///     println!("Member: {}", name);
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn find_members(&mut self) -> Result<&XptInfo> {
+        self.reader.decode_headers_with(self.options.text_mode)?;
+        self.reader.find_members()
+    }
+
     /// Reads the first [`Dataset`] from the file.
     ///
     /// # Errors
@@ -256,6 +548,7 @@ impl XptReaderBuilder {
     /// ```
     #[must_use = "this returns a Result that should be handled"]
     pub fn read(mut self) -> Result<Dataset> {
+        self.reader.decode_headers_with(self.options.text_mode)?;
         let first_member = self
             .reader
             .file_info()
@@ -311,4 +604,64 @@ impl XptReaderBuilder {
     pub fn read_all(mut self) -> Result<Vec<Dataset>> {
         self.reader.read_all(&self.options)
     }
+
+    /// Reads the first member in batches of at most `batch_size` rows.
+    ///
+    /// Returns an iterator of [`Dataset`] batches, so files larger than
+    /// memory can be filtered or aggregated one batch at a time. A
+    /// `batch_size` of zero is treated as one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the file has no members.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// for batch in Xpt::reader("lb.xpt")?.batches(100_000)? {
+    ///     let batch = batch?;
+    ///     println!("{} rows", batch.nrows());
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn batches(mut self, batch_size: usize) -> Result<XptBatchReader> {
+        self.reader.decode_headers_with(self.options.text_mode)?;
+        let first_member = self
+            .reader
+            .file_info()
+            .members
+            .first()
+            .ok_or_else(|| Error::corrupt("XPT file contains no members"))?
+            .name
+            .clone();
+
+        self.member_batches(&first_member, batch_size)
+    }
+
+    /// Reads a specific member by name in batches of at most `batch_size` rows.
+    ///
+    /// The name matching is case-insensitive. See [`batches`](Self::batches).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the member is not found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// let batches = Xpt::reader("study.xpt")?.member_batches("LB", 50_000)?;
+    /// for batch in batches {
+    ///     println!("{} rows", batch?.nrows());
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn member_batches(self, name: &str, batch_size: usize) -> Result<XptBatchReader> {
+        self.reader.member_batches(name, &self.options, batch_size)
+    }
 }
@@ -0,0 +1,411 @@
+//! Conformance of a dataset with the variable definitions of a standard.
+
+use cdisc_metadata::{Standard, Terminology, Variable};
+
+use crate::dataset::{Column, ColumnData, Dataset};
+use crate::metadata::XptVarType;
+use crate::schema::DatasetSchema;
+use crate::validate::{Issue, merge_value_issues};
+use crate::xpt::v5::write::get_character_value;
+
+/// A variable as seen by the conformance checks.
+struct Candidate<'a> {
+    name: &'a str,
+    label: Option<&'a str>,
+    /// The written type, or `None` if the variable can be written as either.
+    xpt_type: Option<XptVarType>,
+}
+
+/// Checks the columns of a dataset against a standard.
+pub(super) fn check_dataset(dataset: &Dataset, standard: &Standard) -> Vec<Issue> {
+    let candidates: Vec<_> = dataset
+        .columns()
+        .iter()
+        .map(|col| Candidate {
+            name: col.name(),
+            label: col.label(),
+            xpt_type: match col.data() {
+                ColumnData::Date(_) | ColumnData::DateTime(_) | ColumnData::Time(_) => None,
+                data if data.is_numeric() => Some(XptVarType::Numeric),
+                _ => Some(XptVarType::Character),
+            },
+        })
+        .collect();
+    check(dataset.domain_code(), &candidates, standard)
+}
+
+/// Checks the planned variables of a schema against a standard.
+pub(crate) fn check_schema(schema: &DatasetSchema, standard: &Standard) -> Vec<Issue> {
+    let candidates: Vec<_> = schema
+        .variables
+        .iter()
+        .map(|var| Candidate {
+            name: &var.name,
+            label: Some(var.label.as_str()),
+            xpt_type: Some(var.xpt_type),
+        })
+        .collect();
+    check(&schema.domain_code, &candidates, standard)
+}
+
+fn check(dataset: &str, candidates: &[Candidate<'_>], standard: &Standard) -> Vec<Issue> {
+    let definitions = standard.variables_for_dataset(dataset);
+    if definitions.is_empty() {
+        return vec![Issue::DatasetNotInStandard {
+            dataset: dataset.to_string(),
+            standard: format!("{} {}", standard.name, standard.version),
+        }];
+    }
+    let find = |name: &str| -> Option<&Variable> {
+        definitions
+            .iter()
+            .copied()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+    };
+
+    let mut issues = Vec::new();
+
+    for var in &definitions {
+        if candidates
+            .iter()
+            .any(|c| c.name.eq_ignore_ascii_case(&var.name))
+        {
+            continue;
+        }
+        let core = var.core.as_deref().unwrap_or_default();
+        let required = core.eq_ignore_ascii_case("Req");
+        if required || core.eq_ignore_ascii_case("Exp") {
+            issues.push(Issue::StandardVariableMissing {
+                variable: var.name.clone(),
+                required,
+            });
+        }
+    }
+
+    // The IG variable with the highest order so far
+    let mut last: Option<(&str, u32)> = None;
+    for candidate in candidates {
+        let Some(var) = find(candidate.name) else {
+            continue;
+        };
+
+        let expected = XptVarType::from(var.var_type);
+        if candidate.xpt_type.is_some_and(|t| t != expected) {
+            issues.push(Issue::StandardTypeMismatch {
+                variable: candidate.name.to_string(),
+                expected,
+            });
+        }
+
+        if let Some(label) = candidate.label
+            && !label.trim().is_empty()
+            && label.trim() != var.label.trim()
+        {
+            issues.push(Issue::StandardLabelMismatch {
+                variable: candidate.name.to_string(),
+                label: label.to_string(),
+                expected: var.label.clone(),
+            });
+        }
+
+        match last {
+            Some((after, order)) if var.order < order => {
+                issues.push(Issue::StandardOrderMismatch {
+                    variable: candidate.name.to_string(),
+                    after: after.to_string(),
+                });
+            }
+            _ => last = Some((candidate.name, var.order)),
+        }
+    }
+
+    issues
+}
+
+/// Checks the character columns of a dataset against the codelists of the
+/// standard.
+pub(super) fn check_dataset_codelists(
+    dataset: &Dataset,
+    standard: &Standard,
+    terminology: &Terminology,
+) -> Vec<Issue> {
+    let names = dataset
+        .columns()
+        .iter()
+        .filter(|col| col.is_character())
+        .map(Column::name);
+    check_codelists(dataset, dataset.domain_code(), names, standard, terminology)
+}
+
+/// Checks the planned character variables of a schema against the codelists
+/// of the standard.
+pub(crate) fn check_schema_codelists(
+    dataset: &Dataset,
+    schema: &DatasetSchema,
+    standard: &Standard,
+    terminology: &Terminology,
+) -> Vec<Issue> {
+    let names = schema.character_variables().map(|var| var.name.as_str());
+    check_codelists(dataset, &schema.domain_code, names, standard, terminology)
+}
+
+/// Reports the values of a variable outside its codelists, once per
+/// variable.
+///
+/// Only variables whose codelists are all found in the terminology and
+/// non-extensible are checked; a value is accepted if any of the codelists
+/// contains it.
+fn check_codelists<'a>(
+    dataset: &Dataset,
+    name: &str,
+    variables: impl Iterator<Item = &'a str>,
+    standard: &Standard,
+    terminology: &Terminology,
+) -> Vec<Issue> {
+    let definitions = standard.variables_for_dataset(name);
+    let mut issues = Vec::new();
+
+    for variable in variables {
+        let Some(var) = definitions
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(variable))
+        else {
+            continue;
+        };
+        let Some(codelists) = terminology.codelists_for(var) else {
+            continue;
+        };
+        if codelists.is_empty() || codelists.iter().any(|c| c.extensible) {
+            continue;
+        }
+        let Some(col) = dataset.column(variable) else {
+            continue;
+        };
+
+        let codelist = codelists
+            .iter()
+            .map(|c| c.submission_value.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let outside = (0..dataset.nrows()).filter_map(|row| {
+            let Ok(Some(value)) = get_character_value(col.data(), row) else {
+                return None;
+            };
+            let value = value.trim_end();
+            (!value.is_empty() && !codelists.iter().any(|c| c.contains(value))).then(|| {
+                Issue::ValueNotInCodelist {
+                    variable: variable.to_string(),
+                    row,
+                    count: 1,
+                    value: value.to_string(),
+                    codelist: codelist.clone(),
+                }
+            })
+        });
+        merge_value_issues(&mut issues, outside, 0);
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdisc_metadata::{Codelist, Term, VarType};
+
+    fn standard() -> Standard {
+        let var = |order, name: &str, label: &str, var_type, core: &str| Variable {
+            order,
+            name: name.into(),
+            label: label.into(),
+            var_type,
+            dataset: "AE".into(),
+            role: None,
+            core: Some(core.into()),
+            codelist_codes: Vec::new(),
+            codelist_values: Vec::new(),
+            notes: None,
+        };
+        Standard {
+            name: "SDTM-IG".into(),
+            version: "3.4".into(),
+            full_name: String::new(),
+            publishing_set: "SDTM".into(),
+            effective_date: None,
+            datasets: Vec::new(),
+            variables: vec![
+                var(1, "STUDYID", "Study Identifier", VarType::Char, "Req"),
+                var(
+                    2,
+                    "USUBJID",
+                    "Unique Subject Identifier",
+                    VarType::Char,
+                    "Req",
+                ),
+                var(3, "AESEQ", "Sequence Number", VarType::Num, "Req"),
+                var(
+                    4,
+                    "AETERM",
+                    "Reported Term for the Adverse Event",
+                    VarType::Char,
+                    "Req",
+                ),
+                Variable {
+                    codelist_codes: vec!["C66769".into()],
+                    ..var(5, "AESEV", "Severity/Intensity", VarType::Char, "Perm")
+                },
+                var(
+                    6,
+                    "AESTDTC",
+                    "Start Date/Time of Adverse Event",
+                    VarType::Char,
+                    "Exp",
+                ),
+                var(
+                    7,
+                    "AEENDTC",
+                    "End Date/Time of Adverse Event",
+                    VarType::Char,
+                    "Exp",
+                ),
+            ],
+        }
+    }
+
+    fn text(name: &str) -> Column {
+        Column::new(name, ColumnData::String(vec![Some("X".into())]))
+    }
+
+    #[test]
+    fn test_conformance_reports_deviations() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![
+                text("USUBJID"),
+                text("AETERM").with_label("Term"),
+                text("AESEQ"),
+                text("EXTRA"),
+                Column::new(
+                    "AESTDTC",
+                    ColumnData::Date(vec![chrono::NaiveDate::from_ymd_opt(2024, 1, 1)]),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_dataset(&dataset, &standard()),
+            [
+                Issue::StandardVariableMissing {
+                    variable: "STUDYID".into(),
+                    required: true,
+                },
+                Issue::StandardVariableMissing {
+                    variable: "AEENDTC".into(),
+                    required: false,
+                },
+                Issue::StandardLabelMismatch {
+                    variable: "AETERM".into(),
+                    label: "Term".into(),
+                    expected: "Reported Term for the Adverse Event".into(),
+                },
+                Issue::StandardTypeMismatch {
+                    variable: "AESEQ".into(),
+                    expected: XptVarType::Numeric,
+                },
+                Issue::StandardOrderMismatch {
+                    variable: "AESEQ".into(),
+                    after: "AETERM".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conformance_unknown_dataset() {
+        let dataset = Dataset::new("XX", vec![text("USUBJID")]).unwrap();
+        assert_eq!(
+            check_dataset(&dataset, &standard()),
+            [Issue::DatasetNotInStandard {
+                dataset: "XX".into(),
+                standard: "SDTM-IG 3.4".into(),
+            }]
+        );
+    }
+
+    fn terminology(extensible: bool) -> Terminology {
+        let term = |code: &str, value: &str| Term {
+            code: code.into(),
+            submission_value: value.into(),
+            synonyms: Vec::new(),
+            definition: None,
+            preferred_term: None,
+        };
+        Terminology {
+            codelists: vec![Codelist {
+                code: "C66769".into(),
+                name: "Severity/Intensity Scale for Adverse Events".into(),
+                submission_value: "AESEV".into(),
+                extensible,
+                terms: vec![
+                    term("C41338", "MILD"),
+                    term("C41339", "MODERATE"),
+                    term("C41340", "SEVERE"),
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_codelist_values() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![Column::new(
+                "AESEV",
+                ColumnData::String(vec![
+                    Some("MILD".into()),
+                    Some("Severe".into()),
+                    None,
+                    Some("SEVERE  ".into()),
+                    Some("mild".into()),
+                ]),
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_dataset_codelists(&dataset, &standard(), &terminology(false)),
+            [Issue::ValueNotInCodelist {
+                variable: "AESEV".into(),
+                row: 1,
+                count: 2,
+                value: "Severe".into(),
+                codelist: "AESEV".into(),
+            }]
+        );
+        assert!(check_dataset_codelists(&dataset, &standard(), &terminology(true)).is_empty());
+    }
+
+    #[test]
+    fn test_codelist_missing_from_terminology() {
+        // AESEV refers to one codelist in the package and one that is not
+        let mut standard = standard();
+        let aesev = standard
+            .variables
+            .iter_mut()
+            .find(|v| v.name == "AESEV")
+            .unwrap();
+        aesev.codelist_codes.push("C99999".into());
+
+        let dataset = Dataset::new(
+            "AE",
+            vec![Column::new(
+                "AESEV",
+                ColumnData::String(vec![Some("GRADE 4".into())]),
+            )],
+        )
+        .unwrap();
+
+        assert!(check_dataset_codelists(&dataset, &standard, &terminology(false)).is_empty());
+    }
+}
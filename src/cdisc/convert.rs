@@ -0,0 +1,101 @@
+//! Conversions from standard definitions to xportrs metadata.
+
+use cdisc_metadata::{DatasetDef, VarType, Variable};
+
+use crate::dataset::VariableRole;
+#[cfg(feature = "define-xml")]
+use crate::define::DefineStandard;
+use crate::metadata::{DatasetMetadata, VariableMetadata, XptVarType};
+
+impl From<VarType> for XptVarType {
+    fn from(var_type: VarType) -> Self {
+        if var_type.is_numeric() {
+            Self::Numeric
+        } else {
+            Self::Character
+        }
+    }
+}
+
+/// Takes the dataset, name, label, type, role and order of an IG variable.
+///
+/// All qualifier roles map to [`VariableRole::Qualifier`]; an IG order that
+/// does not fit an `i32` is left unset.
+impl From<&Variable> for VariableMetadata {
+    fn from(var: &Variable) -> Self {
+        let mut meta =
+            Self::new(var.dataset.as_str(), var.name.as_str()).with_xpt_type(var.var_type.into());
+        if !var.label.is_empty() {
+            meta.label = Some(var.label.clone());
+        }
+        meta.role = var.role.as_deref().and_then(VariableRole::from_cdisc);
+        meta.order = i32::try_from(var.order).ok();
+        meta
+    }
+}
+
+/// Takes the name and label of an IG dataset.
+impl From<&DatasetDef> for DatasetMetadata {
+    fn from(def: &DatasetDef) -> Self {
+        let meta = Self::new(def.name.as_str());
+        if def.label.is_empty() {
+            meta
+        } else {
+            meta.with_label(def.label.as_str())
+        }
+    }
+}
+
+/// Takes the name and version of an IG, with the name as Define-XML spells
+/// it (`SDTM-IG` is `SDTMIG`).
+#[cfg(feature = "define-xml")]
+impl From<&cdisc_metadata::Standard> for DefineStandard {
+    fn from(standard: &cdisc_metadata::Standard) -> Self {
+        Self::new(standard.name.replace('-', ""), standard.version.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_to_metadata() {
+        let var = Variable {
+            order: 3,
+            name: "AESEQ".into(),
+            label: "Sequence Number".into(),
+            var_type: VarType::Num,
+            dataset: "AE".into(),
+            role: Some("Identifier".into()),
+            core: Some("Req".into()),
+            codelist_codes: Vec::new(),
+            codelist_values: Vec::new(),
+            notes: None,
+        };
+
+        assert_eq!(
+            VariableMetadata::from(&var),
+            VariableMetadata::new("AE", "AESEQ")
+                .with_xpt_type(XptVarType::Numeric)
+                .with_label("Sequence Number")
+                .with_role(VariableRole::Identifier)
+                .with_order(3)
+        );
+    }
+
+    #[test]
+    fn test_dataset_to_metadata() {
+        let def = DatasetDef {
+            name: "AE".into(),
+            label: "Adverse Events".into(),
+            class: "Events".into(),
+            structure: None,
+        };
+
+        assert_eq!(
+            DatasetMetadata::from(&def),
+            DatasetMetadata::new("AE").with_label("Adverse Events")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! CDISC Implementation Guide conformance.
+//!
+//! This module checks datasets against the variable definitions of a CDISC
+//! Implementation Guide (SDTM-IG, SEND-IG or ADaM-IG), either directly with
+//! [`conformance`] or while writing through
+//! [`XptWriterBuilder::standard`](crate::XptWriterBuilder::standard).
+//!
+//! | Check                                   | Issue                     | Severity |
+//! |-----------------------------------------|---------------------------|----------|
+//! | Dataset has no IG variables             | `DatasetNotInStandard`    | Warning  |
+//! | Required (`Req`) variable is missing    | `StandardVariableMissing` | Error    |
+//! | Expected (`Exp`) variable is missing    | `StandardVariableMissing` | Warning  |
+//! | Char/Num type differs from the IG       | `StandardTypeMismatch`    | Error    |
+//! | Label differs from the IG label         | `StandardLabelMismatch`   | Warning  |
+//! | Variables are not in IG order           | `StandardOrderMismatch`   | Warning  |
+//! | Value outside a non-extensible codelist | `ValueNotInCodelist`      | Error    |
+//!
+//! Variables that the IG does not define for the dataset are not reported.
+//! [`skeleton`] arranges a dataset to the IG before it is written.
+//! Codelist values are checked with [`codelist_conformance`] or
+//! [`XptWriterBuilder::terminology`](crate::XptWriterBuilder::terminology),
+//! against a controlled terminology package loaded with [`load_terminology`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xportrs::Dataset;
+//! use xportrs::cdisc::{conformance, sdtm_ig_v3_4};
+//!
+//! # let ae = Dataset::new("AE", vec![]).unwrap();
+//! let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+//! for issue in conformance(&ae, &standard) {
+//!     eprintln!("{issue}");
+//! }
+//! ```
+
+mod check;
+mod convert;
+mod skeleton;
+
+use crate::dataset::Dataset;
+use crate::validate::Issue;
+
+pub use cdisc_metadata::{
+    Codelist, DatasetDef, Standard, Term, Terminology, VarType, Variable, adam_ig_v1_3,
+    load_standard, load_terminology, parse_standard, sdtm_ig_v3_4, send_ig_v3_1_1,
+};
+
+pub(crate) use check::{check_schema, check_schema_codelists};
+pub use skeleton::AddMissing;
+pub(crate) use skeleton::apply_defaults;
+
+/// Checks a dataset against the variable definitions of a standard.
+///
+/// The dataset is matched to the IG by its domain code. Temporal columns
+/// satisfy either IG type, since they can be written as SAS date values or
+/// ISO 8601 text.
+///
+/// # Example
+///
+/// ```no_run
+/// use xportrs::{Column, ColumnData, Dataset};
+/// use xportrs::cdisc::{conformance, sdtm_ig_v3_4};
+///
+/// let ae = Dataset::new(
+///     "AE",
+///     vec![Column::new("AESEQ", ColumnData::String(vec![Some("1".into())]))],
+/// )?;
+/// let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+///
+/// // AESEQ is numeric in SDTM-IG, and STUDYID, USUBJID, ... are missing
+/// assert!(conformance(&ae, &standard).iter().any(|i| i.is_error()));
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+#[must_use]
+pub fn conformance(dataset: &Dataset, standard: &Standard) -> Vec<Issue> {
+    check::check_dataset(dataset, standard)
+}
+
+/// Checks the character values of a dataset against controlled terminology.
+///
+/// Each character column that the standard links to codelists is checked
+/// against those codelists in `terminology`. Extensible codelists accept any
+/// value, so only columns whose codelists are all in `terminology` and
+/// non-extensible are checked. Values outside the codelists are reported as
+/// one [`Issue::ValueNotInCodelist`] per variable, with their count and the
+/// first of them.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use xportrs::{Column, ColumnData, Dataset};
+/// use xportrs::cdisc::{codelist_conformance, load_terminology, sdtm_ig_v3_4};
+///
+/// let ae = Dataset::new(
+///     "AE",
+///     vec![Column::new("AESEV", ColumnData::String(vec![Some("VERY BAD".into())]))],
+/// )?;
+/// let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+/// let ct = load_terminology(Path::new("SDTM Terminology.txt")).expect("CT package");
+///
+/// // AESEV must be one of MILD, MODERATE or SEVERE
+/// assert_eq!(codelist_conformance(&ae, &standard, &ct).len(), 1);
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+#[must_use]
+pub fn codelist_conformance(
+    dataset: &Dataset,
+    standard: &Standard,
+    terminology: &Terminology,
+) -> Vec<Issue> {
+    check::check_dataset_codelists(dataset, standard, terminology)
+}
+
+/// Arranges a dataset by the variable definitions of a standard.
+///
+/// - Columns are reordered to IG order; columns the IG does not define for
+///   the dataset follow in their original order
+/// - Columns without a label get the IG label, and the dataset gets the IG
+///   dataset label if it has none
+/// - Columns without a role get the IG role (all qualifier roles map to
+///   [`VariableRole::Qualifier`](crate::VariableRole::Qualifier))
+/// - Missing variables are added as columns of missing values, as selected
+///   by `missing`
+///
+/// Missing Required variables are never added, since they must have values;
+/// [`conformance`] reports them.
+///
+/// # Example
+///
+/// ```no_run
+/// use xportrs::{Column, ColumnData, Dataset};
+/// use xportrs::cdisc::{AddMissing, sdtm_ig_v3_4, skeleton};
+///
+/// let ae = Dataset::new(
+///     "AE",
+///     vec![
+///         Column::new("AETERM", ColumnData::String(vec![Some("HEADACHE".into())])),
+///         Column::new("USUBJID", ColumnData::String(vec![Some("01-001".into())])),
+///     ],
+/// )?;
+/// let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+///
+/// let ae = skeleton(ae, &standard, AddMissing::Expected);
+/// assert_eq!(ae.columns()[0].name(), "USUBJID");
+/// assert_eq!(ae.columns()[0].label(), Some("Unique Subject Identifier"));
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+#[must_use]
+pub fn skeleton(dataset: Dataset, standard: &Standard, missing: AddMissing) -> Dataset {
+    skeleton::skeleton(dataset, standard, missing)
+}
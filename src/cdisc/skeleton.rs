@@ -0,0 +1,121 @@
+//! Arrangement of a dataset by the variable definitions of a standard.
+
+use cdisc_metadata::{Standard, Variable};
+
+use crate::dataset::{Column, ColumnData, Dataset, VariableRole};
+
+/// Which IG variables missing from the data [`skeleton`](super::skeleton)
+/// adds as empty columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddMissing {
+    /// Add no columns.
+    #[default]
+    None,
+    /// Add Expected (`Exp`) variables.
+    Expected,
+    /// Add Expected (`Exp`) and Permissible (`Perm`) variables.
+    ExpectedAndPermissible,
+}
+
+impl AddMissing {
+    /// Returns `true` if a variable with the given core status is added.
+    fn includes(self, core: Option<&str>) -> bool {
+        let core = core.unwrap_or_default();
+        match self {
+            Self::None => false,
+            Self::Expected => core.eq_ignore_ascii_case("Exp"),
+            Self::ExpectedAndPermissible => {
+                core.eq_ignore_ascii_case("Exp") || core.eq_ignore_ascii_case("Perm")
+            }
+        }
+    }
+}
+
+pub(super) fn skeleton(mut dataset: Dataset, standard: &Standard, missing: AddMissing) -> Dataset {
+    let mut definitions = standard.variables_for_dataset(dataset.domain_code());
+    definitions.sort_by_key(|v| v.order);
+
+    let name = dataset.domain_code().to_string();
+    fill_dataset_label(&mut dataset, &name, standard);
+
+    let nrows = dataset.nrows();
+    let mut columns: Vec<Option<Column>> = dataset.columns_mut().drain(..).map(Some).collect();
+    let mut ordered = Vec::with_capacity(columns.len());
+
+    for var in definitions {
+        let existing = columns
+            .iter_mut()
+            .find(|c| {
+                c.as_ref()
+                    .is_some_and(|c| c.name().eq_ignore_ascii_case(&var.name))
+            })
+            .and_then(Option::take);
+        match existing {
+            Some(mut col) => {
+                fill_ig_attributes(&mut col, var);
+                ordered.push(col);
+            }
+            None if missing.includes(var.core.as_deref()) => {
+                let mut col = empty_column(var, nrows);
+                fill_ig_attributes(&mut col, var);
+                ordered.push(col);
+            }
+            None => {}
+        }
+    }
+
+    // Variables the IG does not define keep their order after the IG ones
+    ordered.extend(columns.into_iter().flatten());
+    *dataset.columns_mut() = ordered;
+    dataset
+}
+
+/// Fills in the IG dataset label, and the IG label and role of each column,
+/// where the data has none; the columns keep their order.
+///
+/// `name` is the dataset name the IG definitions are looked up by.
+pub(crate) fn apply_defaults(dataset: &mut Dataset, name: &str, standard: &Standard) {
+    fill_dataset_label(dataset, name, standard);
+
+    let definitions = standard.variables_for_dataset(name);
+    for col in dataset.columns_mut() {
+        if let Some(var) = definitions
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(col.name()))
+        {
+            fill_ig_attributes(col, var);
+        }
+    }
+}
+
+/// Fills in the IG dataset label if the dataset has none.
+fn fill_dataset_label(dataset: &mut Dataset, name: &str, standard: &Standard) {
+    if dataset.dataset_label().is_none()
+        && let Some(def) = standard.dataset(name)
+        && !def.label.is_empty()
+    {
+        dataset.set_label(def.label.as_str());
+    }
+}
+
+/// Fills in the IG label and role where the column has none.
+fn fill_ig_attributes(col: &mut Column, var: &Variable) {
+    if col.role().is_none()
+        && let Some(role) = var.role.as_deref().and_then(VariableRole::from_cdisc)
+    {
+        col.set_role(role);
+    }
+    if col.label().is_none() && !var.label.is_empty() {
+        col.set_label(var.label.as_str());
+    }
+}
+
+/// Creates a column of missing values of the IG type.
+fn empty_column(var: &Variable, nrows: usize) -> Column {
+    let data = if var.var_type.is_numeric() {
+        ColumnData::F64(vec![None; nrows])
+    } else {
+        ColumnData::String(vec![None; nrows])
+    };
+    Column::new(var.name.as_str(), data)
+}
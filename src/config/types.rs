@@ -4,6 +4,8 @@
 
 use chrono::{DateTime, Utc};
 
+use crate::xpt::XptVersion;
+
 /// Main configuration for xportrs operations.
 ///
 /// This struct controls the behavior of reading and writing operations,
@@ -13,7 +15,8 @@ pub(crate) struct Config {
     /// Whether to use strict checks (errors abort writes).
     pub strict_checks: bool,
 
-    /// Whether to allow safe automatic fixes (e.g., name normalization).
+    /// Whether to repair common problems automatically, recording each fix
+    /// as an issue.
     pub auto_fix: bool,
 
     /// Verbosity level for logging/diagnostics.
@@ -115,6 +118,20 @@ pub(crate) struct WriteOptions {
     ///
     /// If not set, the current time will be used.
     pub modified: Option<DateTime<Utc>>,
+
+    /// XPT format version to write.
+    pub version: XptVersion,
+
+    /// Character set for character values, names and labels.
+    ///
+    /// Both UTF-8 modes write UTF-8.
+    pub text_mode: TextMode,
+
+    /// Whether character values and labels may be truncated to fit.
+    ///
+    /// Truncation is always reported; when allowed, it is a warning instead
+    /// of an error.
+    pub allow_truncation: bool,
 }
 
 #[allow(dead_code)]
@@ -145,6 +162,27 @@ impl WriteOptions {
         self.modified = Some(modified);
         self
     }
+
+    /// Sets the XPT format version.
+    #[must_use]
+    pub(crate) fn with_version(mut self, version: XptVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the character set to write.
+    #[must_use]
+    pub(crate) fn with_text_mode(mut self, mode: TextMode) -> Self {
+        self.text_mode = mode;
+        self
+    }
+
+    /// Sets whether truncation is allowed.
+    #[must_use]
+    pub(crate) fn with_allow_truncation(mut self, allow: bool) -> Self {
+        self.allow_truncation = allow;
+        self
+    }
 }
 
 /// Options for reading XPT files.
@@ -158,10 +196,20 @@ pub(crate) struct ReadOptions {
     /// If `None`, all rows are read.
     pub row_limit: Option<usize>,
 
+    /// Number of rows to skip before reading.
+    ///
+    /// Rows are fixed-width, so the reader seeks past them directly.
+    pub skip_rows: usize,
+
     /// Whether to preserve trailing blanks in character values.
     ///
     /// By default, trailing spaces are trimmed.
     pub preserve_blanks: bool,
+
+    /// Variables to read, matched case-insensitively.
+    ///
+    /// If `None`, all variables are read. Variables are returned in file order.
+    pub columns: Option<Vec<String>>,
 }
 
 impl Default for ReadOptions {
@@ -169,7 +217,9 @@ impl Default for ReadOptions {
         Self {
             text_mode: TextMode::LossyUtf8,
             row_limit: None,
+            skip_rows: 0,
             preserve_blanks: false,
+            columns: None,
         }
     }
 }
@@ -202,20 +252,37 @@ impl ReadOptions {
         self.preserve_blanks = preserve;
         self
     }
+
+    /// Sets the variables to read.
+    #[must_use]
+    pub(crate) fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
 }
 
-/// Text decoding mode for character variables.
+/// Text encoding mode for character data.
 ///
 /// XPT files can contain character data in various encodings.
-/// This enum controls how that data is decoded into Rust strings.
+/// This enum controls how that data is decoded into Rust strings when
+/// reading, and which character set is written when writing. It applies to
+/// character values, variable names and labels, and the dataset label.
 ///
 /// # Variants
 ///
 /// - [`TextMode::StrictUtf8`] - Errors on invalid UTF-8 sequences
 /// - [`TextMode::LossyUtf8`] - Replaces invalid sequences with U+FFFD (default)
 /// - [`TextMode::Latin1`] - Interprets bytes as ISO-8859-1 code points
+/// - [`TextMode::Windows1252`] - Windows-1252 (older US and European files)
+/// - [`TextMode::ShiftJis`] - Shift-JIS (Japanese, e.g. PMDA submissions)
+/// - [`TextMode::Gb18030`] - GB18030 (Chinese, e.g. NMPA submissions)
+///
+/// Both UTF-8 modes write UTF-8. The other modes write their code page, and
+/// writing fails for characters the code page cannot represent. Byte-length
+/// limits are checked against the encoded form.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum TextMode {
     /// Strict UTF-8: invalid sequences cause an error.
     StrictUtf8,
@@ -226,6 +293,15 @@ pub enum TextMode {
 
     /// Latin-1 (ISO-8859-1): bytes are interpreted as Latin-1 code points.
     Latin1,
+
+    /// Windows-1252: the Western European Windows code page.
+    Windows1252,
+
+    /// Shift-JIS: the Japanese double-byte code page.
+    ShiftJis,
+
+    /// GB18030: the Chinese national standard code page.
+    Gb18030,
 }
 
 #[cfg(test)]
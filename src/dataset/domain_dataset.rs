@@ -9,6 +9,7 @@ use std::ops::Index;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
 use crate::error::{Error, Result};
+use crate::xpt::v5::encoding::SasMissingValue;
 
 use super::format::{Format, FormatParseError};
 use super::iter::{ColumnNames, IntoIter, Iter, IterMut};
@@ -154,6 +155,14 @@ impl Dataset {
         &self.columns
     }
 
+    /// Returns a mutable reference to the columns.
+    ///
+    /// Every column must keep the dataset's number of rows.
+    #[cfg(feature = "cdisc")]
+    pub(crate) fn columns_mut(&mut self) -> &mut Vec<Column> {
+        &mut self.columns
+    }
+
     /// Returns the number of rows (observations) in the dataset.
     #[must_use]
     pub fn nrows(&self) -> usize {
@@ -349,6 +358,15 @@ pub struct Column {
     /// If not set, length is derived from the maximum value length in the data.
     /// Only applicable to character columns.
     length: Option<usize>,
+
+    /// Explicit numeric byte length, shorter than the default 8 bytes.
+    numeric_length: Option<usize>,
+
+    /// SAS special missing codes (`.A`-`.Z`, `._`) for missing numeric values.
+    ///
+    /// `None` when every missing value is a standard `.`; otherwise one
+    /// entry per row.
+    special_missing: Option<Vec<Option<SasMissingValue>>>,
 }
 
 impl Column {
@@ -363,6 +381,8 @@ impl Column {
             format: None,
             informat: None,
             length: None,
+            numeric_length: None,
+            special_missing: None,
         }
     }
 
@@ -377,6 +397,8 @@ impl Column {
             format: None,
             informat: None,
             length: None,
+            numeric_length: None,
+            special_missing: None,
         }
     }
 
@@ -480,6 +502,81 @@ impl Column {
         self
     }
 
+    /// Sets the byte length of a numeric column.
+    ///
+    /// Numeric columns are 8 bytes by default. A length of 3 to 7 stores
+    /// the values with reduced precision and is reported as
+    /// [`Issue::NumericPrecisionReduced`](crate::Issue::NumericPrecisionReduced).
+    /// Ignored for character columns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use xportrs::{Column, ColumnData};
+    ///
+    /// // Integers up to 8,192 are stored exactly in 3 bytes
+    /// let col = Column::new("AESEQ", ColumnData::I64(vec![Some(1), Some(2)]))
+    ///     .with_numeric_length(3);
+    ///
+    /// assert_eq!(col.numeric_length(), Some(3));
+    /// ```
+    #[must_use]
+    pub fn with_numeric_length(mut self, length: usize) -> Self {
+        self.numeric_length = Some(length);
+        self
+    }
+
+    /// Sets the SAS special missing codes of a numeric column, one per row.
+    ///
+    /// SAS distinguishes the standard missing value `.` from the special
+    /// missing values `.A` through `.Z` and `._`. In [`ColumnData`] all of
+    /// them are `None`; this records which code each missing row carries so
+    /// it is written back exactly. Entries for rows that hold a value are
+    /// ignored, and the vector is padded with `None` (or truncated) to the
+    /// column length. Columns read from an XPT file carry these codes
+    /// automatically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use xportrs::{Column, ColumnData, SasMissingValue};
+    ///
+    /// let col = Column::new("LBSTRESN", ColumnData::F64(vec![Some(5.2), None, None]))
+    ///     .with_special_missing(vec![None, Some(SasMissingValue::Special('U'))]);
+    ///
+    /// assert_eq!(col.special_missing_at(1), Some(SasMissingValue::Special('U')));
+    /// assert_eq!(col.special_missing_at(2), None);
+    /// ```
+    #[must_use]
+    pub fn with_special_missing(mut self, mut codes: Vec<Option<SasMissingValue>>) -> Self {
+        codes.resize(self.data.len(), None);
+        for code in &mut codes {
+            if !code.is_some_and(SasMissingValue::is_special) {
+                *code = None;
+            }
+        }
+        self.special_missing = codes.iter().any(Option::is_some).then_some(codes);
+        self
+    }
+
+    /// Returns the SAS special missing codes, one per row, if any are set.
+    ///
+    /// Returns `None` when every missing value is a standard `.`.
+    #[must_use]
+    pub fn special_missing(&self) -> Option<&[Option<SasMissingValue>]> {
+        self.special_missing.as_deref()
+    }
+
+    /// Returns the SAS special missing code recorded for `row`, if any.
+    ///
+    /// Only meaningful for rows whose value is missing.
+    #[must_use]
+    pub fn special_missing_at(&self, row: usize) -> Option<SasMissingValue> {
+        self.special_missing
+            .as_ref()
+            .and_then(|codes| codes.get(row).copied().flatten())
+    }
+
     /// Returns the number of elements in the column.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -531,12 +628,43 @@ impl Column {
         self.length
     }
 
+    /// Returns the numeric byte length set via
+    /// [`Column::with_numeric_length`], if any.
+    #[must_use]
+    pub fn numeric_length(&self) -> Option<usize> {
+        self.numeric_length
+    }
+
     /// Returns a reference to the [`ColumnData`].
     #[must_use]
     pub fn data(&self) -> &ColumnData {
         &self.data
     }
 
+    /// Renames the column.
+    pub(crate) fn set_name(&mut self, name: impl Into<VariableName>) {
+        self.name = name.into();
+    }
+
+    /// Sets the label.
+    #[cfg(feature = "cdisc")]
+    pub(crate) fn set_label(&mut self, label: impl Into<Label>) {
+        self.label = Some(label.into());
+    }
+
+    /// Sets the role.
+    #[cfg(feature = "cdisc")]
+    pub(crate) fn set_role(&mut self, role: VariableRole) {
+        self.role = Some(role);
+    }
+
+    /// Returns a mutable reference to the [`ColumnData`].
+    ///
+    /// The number of elements must not change.
+    pub(crate) fn data_mut(&mut self) -> &mut ColumnData {
+        &mut self.data
+    }
+
     /// Returns `true` if the column contains numeric data (for XPT purposes).
     #[must_use]
     pub fn is_numeric(&self) -> bool {
@@ -814,6 +942,22 @@ impl VariableRole {
             Self::Rule => "Rule",
         }
     }
+
+    /// Maps a CDISC role name (as used in Define-XML and the IGs) to a role.
+    ///
+    /// All qualifier roles (grouping, result, synonym, record and variable
+    /// qualifiers) map to [`VariableRole::Qualifier`].
+    #[cfg(any(feature = "define-xml", feature = "cdisc"))]
+    pub(crate) fn from_cdisc(role: &str) -> Option<Self> {
+        match role.trim() {
+            "Identifier" => Some(Self::Identifier),
+            "Topic" => Some(Self::Topic),
+            "Timing" => Some(Self::Timing),
+            "Rule" => Some(Self::Rule),
+            r if r.ends_with("Qualifier") => Some(Self::Qualifier),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for VariableRole {
@@ -897,4 +1041,27 @@ mod tests {
         assert_eq!(data.len(), 3);
         assert!(data.is_character());
     }
+
+    #[test]
+    fn test_column_special_missing() {
+        let data = ColumnData::F64(vec![None, None, Some(1.0)]);
+
+        // Standard missing codes alone are not recorded
+        let col = Column::new("X", data.clone())
+            .with_special_missing(vec![Some(SasMissingValue::Standard)]);
+        assert_eq!(col.special_missing(), None);
+
+        let col = Column::new("X", data).with_special_missing(vec![
+            Some(SasMissingValue::Underscore),
+            None,
+            None,
+            Some(SasMissingValue::Special('A')),
+        ]);
+        assert_eq!(
+            col.special_missing(),
+            Some(&[Some(SasMissingValue::Underscore), None, None][..])
+        );
+        assert_eq!(col.special_missing_at(0), Some(SasMissingValue::Underscore));
+        assert_eq!(col.special_missing_at(5), None);
+    }
 }
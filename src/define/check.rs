@@ -0,0 +1,167 @@
+//! Reconciliation of a dataset with its Define-XML definition.
+
+use crate::config::TextMode;
+use crate::dataset::{Dataset, Format};
+use crate::metadata::{DatasetMetadata, VariableMetadata};
+use crate::schema::{compute_max_string_length, is_writable_as};
+use crate::validate::Issue;
+
+use super::DefineDataset;
+
+/// Converts a Define-XML dataset definition into writer metadata, reporting
+/// every mismatch with the data as an [`Issue`].
+///
+/// Definitions that the data cannot satisfy are left out of the metadata so
+/// that the mismatch is reported instead of failing schema derivation: a
+/// data type the column cannot be written as, a length shorter than the
+/// longest value, or an unparseable display format.
+pub(crate) fn reconcile(
+    dataset: &Dataset,
+    define: &DefineDataset,
+    mode: TextMode,
+) -> (DatasetMetadata, Vec<VariableMetadata>, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let mut variable_meta = Vec::with_capacity(define.variables.len());
+
+    for var in &define.variables {
+        let Some(col) = dataset.column(&var.name) else {
+            issues.push(Issue::DefineVariableMissing {
+                variable: var.name.clone(),
+                mandatory: var.mandatory,
+            });
+            continue;
+        };
+
+        let mut meta = var.to_metadata(&define.name);
+
+        let xpt_type = var.xpt_type();
+        if !is_writable_as(col.data(), xpt_type) {
+            issues.push(Issue::DefineTypeMismatch {
+                variable: var.name.clone(),
+                data_type: var.data_type.clone(),
+            });
+            meta.xpt_type = None;
+            meta.length = None;
+        }
+
+        if let Some(length) = meta.length {
+            let actual = compute_max_string_length(col.data(), mode);
+            if actual > length {
+                issues.push(Issue::DefineLengthExceeded {
+                    variable: var.name.clone(),
+                    length,
+                    actual,
+                });
+                meta.length = None;
+            }
+        }
+
+        if let Some(format) = &meta.format
+            && let Err(e) = Format::parse(format)
+        {
+            issues.push(Issue::InvalidFormatSyntax {
+                variable: var.name.clone(),
+                format: format.clone(),
+                reason: e.to_string(),
+            });
+            meta.format = None;
+        }
+
+        variable_meta.push(meta);
+    }
+
+    for col in dataset.columns() {
+        if define.variable(col.name()).is_none() {
+            issues.push(Issue::VariableNotInDefine {
+                variable: col.name().to_string(),
+            });
+        }
+    }
+
+    (define.dataset_metadata(), variable_meta, issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{Column, ColumnData};
+    use crate::define::DefineVariable;
+
+    fn define() -> DefineDataset {
+        let text = |name: &str, length| DefineVariable {
+            name: name.into(),
+            data_type: "text".into(),
+            length: Some(length),
+            mandatory: true,
+            ..DefineVariable::default()
+        };
+        DefineDataset {
+            name: "AE".into(),
+            label: Some("Adverse Events".into()),
+            variables: vec![
+                text("USUBJID", 20),
+                text("AETERM", 4),
+                DefineVariable {
+                    name: "AESEQ".into(),
+                    data_type: "integer".into(),
+                    display_format: Some("not a format".into()),
+                    ..DefineVariable::default()
+                },
+                text("AEDECOD", 200),
+            ],
+            ..DefineDataset::default()
+        }
+    }
+
+    #[test]
+    fn test_reconcile_reports_mismatches() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![
+                Column::new("USUBJID", ColumnData::F64(vec![Some(1.0)])),
+                Column::new("AETERM", ColumnData::String(vec![Some("HEADACHE".into())])),
+                Column::new("AESEQ", ColumnData::I64(vec![Some(1)])),
+                Column::new("EXTRA", ColumnData::I64(vec![Some(1)])),
+            ],
+        )
+        .unwrap();
+
+        let (dataset_meta, variable_meta, issues) =
+            reconcile(&dataset, &define(), TextMode::default());
+
+        assert_eq!(
+            dataset_meta.dataset_label.as_deref(),
+            Some("Adverse Events")
+        );
+        assert_eq!(variable_meta.len(), 3);
+        assert!(variable_meta.iter().all(|m| m.length.is_none()));
+        assert!(variable_meta[2].format.is_none());
+
+        assert_eq!(
+            issues,
+            [
+                Issue::DefineTypeMismatch {
+                    variable: "USUBJID".into(),
+                    data_type: "text".into(),
+                },
+                Issue::DefineLengthExceeded {
+                    variable: "AETERM".into(),
+                    length: 4,
+                    actual: 8,
+                },
+                Issue::InvalidFormatSyntax {
+                    variable: "AESEQ".into(),
+                    format: "not a format".into(),
+                    reason: Format::parse("not a format").unwrap_err().to_string(),
+                },
+                Issue::DefineVariableMissing {
+                    variable: "AEDECOD".into(),
+                    mandatory: true,
+                },
+                Issue::VariableNotInDefine {
+                    variable: "EXTRA".into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,523 @@
+//! Define-XML 2.1 generation.
+
+use quick_xml::escape::escape;
+
+use crate::dataset::{Column, ColumnData};
+use crate::error::{Error, Result};
+use crate::write_plan::ValidatedWrite;
+use crate::xpt::v5::write::get_numeric_value;
+
+use super::{DefineDataset, DefineVariable, DefineXml};
+
+/// Creates the definition of a dataset from the schema that will be written.
+///
+/// Every field reflects the transport file:
+///
+/// - Variables are listed in the written order, with their written labels,
+///   display formats and roles; `Mandatory` is `Yes` for variables with no
+///   missing values.
+/// - Character variables are `text` (or `date`, `datetime` and `time` for
+///   temporal columns written as ISO 8601 text) with their written length.
+/// - Numeric variables are `integer` unless a value has a fractional part,
+///   in which case they are `float` with the largest number of decimal
+///   places as `SignificantDigits`. Their length is the largest number of
+///   digits, counting up to 15 significant digits so that binary rounding
+///   (`0.1 + 0.2`) does not add decimals.
+/// - The dataset is repeating unless `USUBJID` is unique.
+///
+/// The standard, class, structure and archive location are not known from
+/// the data: set them with [`DefineDataset::with_standard`],
+/// [`DefineDataset::with_class`] and [`DefineDataset::with_structure`], and
+/// [`DefineDataset::for_files`] with the files written.
+impl From<&ValidatedWrite> for DefineDataset {
+    fn from(validated: &ValidatedWrite) -> Self {
+        let schema = validated.schema();
+        let dataset = validated.dataset();
+
+        let variables = schema
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(i, var)| {
+                let col = &dataset.columns()[var.source_index];
+                let (data_type, length, significant_digits) = if var.xpt_type.is_character() {
+                    (character_type(col.data()), Some(var.length), None)
+                } else {
+                    numeric_type(col.data())
+                };
+                DefineVariable {
+                    name: var.name.clone(),
+                    label: Some(var.label.clone()).filter(|l| !l.is_empty()),
+                    data_type: data_type.to_string(),
+                    length,
+                    significant_digits,
+                    display_format: var.format.as_ref().map(ToString::to_string),
+                    order: i32::try_from(i + 1).ok(),
+                    mandatory: !dataset.is_empty() && !has_missing(col),
+                    role: var.role,
+                }
+            })
+            .collect();
+
+        Self {
+            name: schema.domain_code.clone(),
+            label: schema.dataset_label.clone(),
+            repeating: !dataset.column("USUBJID").is_some_and(has_unique_values),
+            variables,
+            ..Self::default()
+        }
+    }
+}
+
+/// Returns the Define-XML data type of a character variable.
+fn character_type(data: &ColumnData) -> &'static str {
+    match data {
+        ColumnData::Date(_) => "date",
+        ColumnData::DateTime(_) => "datetime",
+        ColumnData::Time(_) => "time",
+        _ => "text",
+    }
+}
+
+/// Significant digits to which numeric values are counted.
+const SIGNIFICANT_DIGITS: usize = 15;
+
+/// Returns the Define-XML data type, length and significant digits of a
+/// numeric variable.
+///
+/// The length is at least 1, for variables with no values.
+fn numeric_type(data: &ColumnData) -> (&'static str, Option<usize>, Option<usize>) {
+    let (mut integer, mut decimals) = (1, 0);
+    for row in 0..data.len() {
+        if let Ok(Some(value)) = get_numeric_value(data, row)
+            && value.is_finite()
+        {
+            let (i, d) = digits(value);
+            integer = integer.max(i);
+            decimals = decimals.max(d);
+        }
+    }
+    if decimals == 0 {
+        ("integer", Some(integer), None)
+    } else {
+        ("float", Some(integer + decimals), Some(decimals))
+    }
+}
+
+/// Returns the number of integer digits and decimal places of a value, to
+/// [`SIGNIFICANT_DIGITS`].
+fn digits(value: f64) -> (usize, usize) {
+    let value = value.abs();
+    let integer = format!("{:.0}", value.trunc()).len();
+    let precision = if value >= 1.0 {
+        SIGNIFICANT_DIGITS.saturating_sub(integer)
+    } else {
+        SIGNIFICANT_DIGITS
+    };
+    let text = format!("{value:.precision$}");
+    let decimals = text
+        .split_once('.')
+        .map_or(0, |(_, d)| d.trim_end_matches('0').len());
+    (integer, decimals)
+}
+
+/// Returns `true` if any value of the column is missing.
+fn has_missing(col: &Column) -> bool {
+    match col.data() {
+        ColumnData::F64(v) => v.iter().any(|x| x.is_none_or(f64::is_nan)),
+        ColumnData::String(v) => v
+            .iter()
+            .any(|x| x.as_deref().is_none_or(|s| s.trim().is_empty())),
+        ColumnData::I64(v) => v.iter().any(Option::is_none),
+        ColumnData::Bool(v) => v.iter().any(Option::is_none),
+        ColumnData::Bytes(v) => v.iter().any(Option::is_none),
+        ColumnData::Date(v) => v.iter().any(Option::is_none),
+        ColumnData::DateTime(v) => v.iter().any(Option::is_none),
+        ColumnData::Time(v) => v.iter().any(Option::is_none),
+    }
+}
+
+/// Returns `true` if the character values of the column are all distinct.
+fn has_unique_values(col: &Column) -> bool {
+    let ColumnData::String(values) = col.data() else {
+        return false;
+    };
+    let mut seen = std::collections::HashSet::with_capacity(values.len());
+    values.iter().all(|v| seen.insert(v.as_deref()))
+}
+
+/// Renders a Define-XML 2.1 document.
+pub(super) fn write_define(define: &DefineXml) -> Result<String> {
+    check_complete(define)?;
+
+    let study = define.study_name().unwrap_or("STUDY");
+    let created = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S");
+    let standards = define.standards();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<ODM xmlns=\"http://www.cdisc.org/ns/odm/v1.3\" \
+         xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         xmlns:def=\"http://www.cdisc.org/ns/def/v2.1\" \
+         ODMVersion=\"1.3.2\" FileType=\"Snapshot\" FileOID=\"DEF.{oid}\" \
+         CreationDateTime=\"{created}\" SourceSystem=\"xportrs\" \
+         SourceSystemVersion=\"{version}\" def:Context=\"Submission\">\n",
+        oid = escape(study),
+        version = env!("CARGO_PKG_VERSION"),
+    ));
+    xml.push_str(&format!("  <Study OID=\"STUDY.{}\">\n", escape(study)));
+    xml.push_str("    <GlobalVariables>\n");
+    for element in ["StudyName", "StudyDescription", "ProtocolName"] {
+        xml.push_str(&format!("      <{element}>{}</{element}>\n", escape(study)));
+    }
+    xml.push_str("    </GlobalVariables>\n");
+    xml.push_str(&format!(
+        "    <MetaDataVersion OID=\"MDV.{study}\" Name=\"{study}\" def:DefineVersion=\"2.1.0\">\n",
+        study = escape(study),
+    ));
+
+    xml.push_str("      <def:Standards>\n");
+    for (i, standard) in standards.iter().enumerate() {
+        xml.push_str(&format!(
+            "        <def:Standard OID=\"STD.{}\" Name=\"{}\" Type=\"IG\" Version=\"{}\"/>\n",
+            i + 1,
+            escape(&standard.name),
+            escape(&standard.version),
+        ));
+    }
+    xml.push_str("      </def:Standards>\n");
+
+    for dataset in define.datasets() {
+        let standard = dataset
+            .standard
+            .as_ref()
+            .and_then(|s| standards.iter().position(|&t| t == s));
+        write_item_group(&mut xml, dataset, standard);
+    }
+    for dataset in define.datasets() {
+        for var in &dataset.variables {
+            write_item(&mut xml, dataset, var);
+        }
+    }
+
+    xml.push_str("    </MetaDataVersion>\n");
+    xml.push_str("  </Study>\n");
+    xml.push_str("</ODM>\n");
+    Ok(xml)
+}
+
+/// Checks that the document has what Define-XML 2.1 requires and cannot
+/// be derived.
+fn check_complete(define: &DefineXml) -> Result<()> {
+    let missing = |dataset: &DefineDataset, what: &str| {
+        Err(Error::metadata(format!(
+            "Define-XML 2.1 requires the {what} of dataset '{}'",
+            dataset.name
+        )))
+    };
+
+    if define.standards().is_empty() {
+        return Err(Error::metadata(
+            "Define-XML 2.1 requires the standard of at least one dataset",
+        ));
+    }
+    for dataset in define.datasets() {
+        if dataset.class.is_none() {
+            return missing(dataset, "class");
+        }
+        if dataset.structure.is_none() {
+            return missing(dataset, "structure");
+        }
+        if dataset.archive_location.is_none() {
+            return missing(dataset, "archive location");
+        }
+        if let Some(var) = dataset.variables.iter().find(|v| {
+            v.length.is_none() && matches!(v.data_type.as_str(), "text" | "integer" | "float")
+        }) {
+            return missing(dataset, &format!("length of variable '{}'", var.name));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a dataset is an analysis dataset: one that follows the ADaM-IG,
+/// or, without a standard, one whose name starts with `AD`.
+fn is_analysis(dataset: &DefineDataset) -> bool {
+    match &dataset.standard {
+        Some(standard) => standard
+            .name
+            .replace('-', "")
+            .eq_ignore_ascii_case("ADaMIG"),
+        None => dataset.name.to_ascii_uppercase().starts_with("AD"),
+    }
+}
+
+/// Renders an `ItemGroupDef` with its `ItemRef`s, class and leaf.
+///
+/// `standard` is the position of the dataset's standard in the document.
+fn write_item_group(xml: &mut String, dataset: &DefineDataset, standard: Option<usize>) {
+    let name = escape(&dataset.name);
+    let sas_name = escape(dataset.sas_name.as_deref().unwrap_or(&dataset.name));
+    let is_analysis = is_analysis(dataset);
+
+    xml.push_str(&format!(
+        "      <ItemGroupDef OID=\"IG.{name}\" Name=\"{name}\" SASDatasetName=\"{sas_name}\""
+    ));
+    if !is_analysis {
+        xml.push_str(&format!(" Domain=\"{sas_name}\""));
+    }
+    xml.push_str(&format!(
+        " Repeating=\"{}\" IsReferenceData=\"No\" Purpose=\"{}\"",
+        yes_no(dataset.repeating),
+        if is_analysis {
+            "Analysis"
+        } else {
+            "Tabulation"
+        },
+    ));
+    if let Some(structure) = &dataset.structure {
+        xml.push_str(&format!(" def:Structure=\"{}\"", escape(structure)));
+    }
+    if dataset.archive_location.is_some() {
+        xml.push_str(&format!(" def:ArchiveLocationID=\"LF.{name}\""));
+    }
+    match standard {
+        Some(i) => xml.push_str(&format!(" def:StandardOID=\"STD.{}\"", i + 1)),
+        None => xml.push_str(" def:IsNonStandard=\"Yes\""),
+    }
+    xml.push_str(">\n");
+
+    write_description(xml, "        ", dataset.label.as_deref());
+    for var in &dataset.variables {
+        xml.push_str(&format!(
+            "        <ItemRef ItemOID=\"IT.{name}.{}\"",
+            escape(&var.name)
+        ));
+        if let Some(order) = var.order {
+            xml.push_str(&format!(" OrderNumber=\"{order}\""));
+        }
+        xml.push_str(&format!(" Mandatory=\"{}\"", yes_no(var.mandatory)));
+        if let Some(role) = var.role {
+            xml.push_str(&format!(" Role=\"{}\"", role.as_str()));
+        }
+        xml.push_str("/>\n");
+    }
+    if let Some(class) = &dataset.class {
+        xml.push_str(&format!(
+            "        <def:Class Name=\"{}\"/>\n",
+            escape(class)
+        ));
+    }
+    if let Some(location) = &dataset.archive_location {
+        let location = escape(location);
+        xml.push_str(&format!(
+            "        <def:leaf ID=\"LF.{name}\" xlink:href=\"{location}\">\n"
+        ));
+        xml.push_str(&format!("          <def:title>{location}</def:title>\n"));
+        xml.push_str("        </def:leaf>\n");
+    }
+    xml.push_str("      </ItemGroupDef>\n");
+}
+
+/// Renders the `ItemDef` of a variable.
+fn write_item(xml: &mut String, dataset: &DefineDataset, var: &DefineVariable) {
+    let name = escape(&var.name);
+    xml.push_str(&format!(
+        "      <ItemDef OID=\"IT.{}.{name}\" Name=\"{name}\" SASFieldName=\"{name}\" DataType=\"{}\"",
+        escape(&dataset.name),
+        escape(&var.data_type),
+    ));
+    if let Some(length) = var.length {
+        xml.push_str(&format!(" Length=\"{length}\""));
+    }
+    if let Some(digits) = var.significant_digits {
+        xml.push_str(&format!(" SignificantDigits=\"{digits}\""));
+    }
+    if let Some(format) = &var.display_format {
+        xml.push_str(&format!(" def:DisplayFormat=\"{}\"", escape(format)));
+    }
+    xml.push_str(">\n");
+    write_description(xml, "        ", var.label.as_deref());
+    xml.push_str("      </ItemDef>\n");
+}
+
+/// Renders a `Description` element, if there is a label.
+fn write_description(xml: &mut String, indent: &str, label: Option<&str>) {
+    if let Some(label) = label {
+        xml.push_str(&format!("{indent}<Description>\n"));
+        xml.push_str(&format!(
+            "{indent}  <TranslatedText xml:lang=\"en\">{}</TranslatedText>\n",
+            escape(label)
+        ));
+        xml.push_str(&format!("{indent}</Description>\n"));
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "Yes" } else { "No" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::VariableRole;
+    use crate::define::DefineStandard;
+
+    fn lb() -> DefineDataset {
+        DefineDataset {
+            name: "LB".into(),
+            label: Some("Laboratory Test Results".into()),
+            standard: Some(DefineStandard::new("SDTMIG", "3.4")),
+            class: Some("FINDINGS".into()),
+            structure: Some("One record per lab test per time point per subject".into()),
+            archive_location: Some("lb.xpt".into()),
+            repeating: true,
+            variables: vec![
+                DefineVariable {
+                    name: "USUBJID".into(),
+                    label: Some("Unique Subject Identifier".into()),
+                    data_type: "text".into(),
+                    length: Some(20),
+                    order: Some(1),
+                    mandatory: true,
+                    role: Some(VariableRole::Identifier),
+                    ..DefineVariable::default()
+                },
+                DefineVariable {
+                    name: "LBSTRESN".into(),
+                    label: Some("Numeric Result/Finding in Std Units & Scale".into()),
+                    data_type: "float".into(),
+                    length: Some(8),
+                    significant_digits: Some(2),
+                    display_format: Some("8.2".into()),
+                    order: Some(2),
+                    ..DefineVariable::default()
+                },
+            ],
+            ..DefineDataset::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_roundtrip() {
+        let mut define = DefineXml::new("STUDY <1>");
+        define.push(lb());
+        define.push(DefineDataset {
+            name: "LB_002".into(),
+            sas_name: Some("LB".into()),
+            standard: None,
+            archive_location: Some("lb_002.xpt".into()),
+            ..lb()
+        });
+
+        let xml = define.to_xml().unwrap();
+        assert!(xml.contains("def:DefineVersion=\"2.1.0\""));
+        assert!(xml.contains("STUDY &lt;1&gt;"));
+
+        let parsed = DefineXml::parse(&xml).unwrap();
+        assert_eq!(parsed.version(), Some("2.1.0"));
+        assert_eq!(parsed.study_name(), Some("STUDY <1>"));
+        assert_eq!(parsed.datasets(), define.datasets());
+    }
+
+    #[test]
+    fn test_generate_required_elements() {
+        let mut define = DefineXml::new("STUDY1");
+        define.push(lb());
+        let xml = define.to_xml().unwrap();
+
+        for required in [
+            "def:Context=\"Submission\"",
+            "<def:Standards>",
+            "<def:Standard OID=\"STD.1\" Name=\"SDTMIG\" Type=\"IG\" Version=\"3.4\"/>",
+            "def:StandardOID=\"STD.1\"",
+            "def:Structure=\"One record per lab test per time point per subject\"",
+            "<def:Class Name=\"FINDINGS\"/>",
+            "def:ArchiveLocationID=\"LF.LB\"",
+            "<def:leaf ID=\"LF.LB\" xlink:href=\"lb.xpt\">",
+            "SASDatasetName=\"LB\" Domain=\"LB\"",
+            "DataType=\"float\" Length=\"8\" SignificantDigits=\"2\"",
+        ] {
+            assert!(xml.contains(required), "missing {required}");
+        }
+        let standards = xml.find("<def:Standards>").unwrap();
+        assert!(standards < xml.find("<ItemGroupDef").unwrap());
+
+        // Each required part that cannot be derived is reported
+        for incomplete in [
+            DefineDataset {
+                standard: None,
+                ..lb()
+            },
+            DefineDataset {
+                class: None,
+                ..lb()
+            },
+            DefineDataset {
+                structure: None,
+                ..lb()
+            },
+            DefineDataset {
+                archive_location: None,
+                ..lb()
+            },
+        ] {
+            let mut define = DefineXml::new("STUDY1");
+            define.push(incomplete);
+            assert!(define.to_xml().is_err());
+        }
+    }
+
+    #[test]
+    fn test_analysis_purpose() {
+        let dataset = |name: &str, standard: Option<DefineStandard>| DefineDataset {
+            name: name.into(),
+            standard,
+            ..DefineDataset::default()
+        };
+
+        // The standard decides over the name
+        assert!(is_analysis(&dataset(
+            "ADSL",
+            Some(DefineStandard::new("ADaMIG", "1.3"))
+        )));
+        assert!(!is_analysis(&dataset(
+            "ADX",
+            Some(DefineStandard::new("SDTMIG", "3.4"))
+        )));
+        assert!(is_analysis(&dataset("ADLB", None)));
+        assert!(!is_analysis(&dataset("LB", None)));
+
+        let mut define = DefineXml::new("STUDY1");
+        define.push(DefineDataset {
+            name: "ADX".into(),
+            ..lb()
+        });
+        let xml = define.to_xml().unwrap();
+        assert!(xml.contains("Domain=\"ADX\""));
+        assert!(xml.contains("Purpose=\"Tabulation\""));
+    }
+
+    #[test]
+    fn test_numeric_type() {
+        let integral = ColumnData::F64(vec![Some(-120.0), None, Some(f64::NAN)]);
+        assert_eq!(numeric_type(&integral), ("integer", Some(3), None));
+
+        let fractional = ColumnData::F64(vec![Some(1.5), Some(12.25)]);
+        assert_eq!(numeric_type(&fractional), ("float", Some(4), Some(2)));
+
+        // Binary rounding does not count as decimals
+        let rounded = ColumnData::F64(vec![Some(0.1 + 0.2)]);
+        assert_eq!(numeric_type(&rounded), ("float", Some(2), Some(1)));
+
+        assert_eq!(
+            numeric_type(&ColumnData::I64(vec![Some(12345)])),
+            ("integer", Some(5), None)
+        );
+        assert_eq!(
+            numeric_type(&ColumnData::F64(vec![None])),
+            ("integer", Some(1), None)
+        );
+    }
+}
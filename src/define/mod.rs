@@ -0,0 +1,455 @@
+//! Define-XML import and generation.
+//!
+//! This module reads the dataset and variable definitions of a Define-XML 2.0
+//! or 2.1 document so that they can drive XPT generation through
+//! [`XptWriterBuilder::define_xml`](crate::XptWriterBuilder::define_xml), and
+//! generates Define-XML 2.1 from the datasets actually written.
+//!
+//! Only the parts of the document that describe the transport files are
+//! read and written:
+//!
+//! | Define-XML                       | Used for                          |
+//! |----------------------------------|-----------------------------------|
+//! | `ItemGroupDef` `Name`            | Dataset name                      |
+//! | `ItemGroupDef` `Description`     | Dataset label                     |
+//! | `def:Standard`, `def:StandardOID`| Implementation guide and version  |
+//! | `def:Class`, `def:Structure`     | Dataset class and structure       |
+//! | `def:leaf` `xlink:href`          | Transport file name               |
+//! | `ItemRef` `OrderNumber`          | Variable order                    |
+//! | `ItemRef` `Mandatory`, `Role`    | Missing-variable checks, role     |
+//! | `ItemDef` `Name`, `Description`  | Variable name and label           |
+//! | `ItemDef` `DataType`, `Length`   | XPT type and character length     |
+//! | `ItemDef` `SignificantDigits`    | Decimal places of `float` values  |
+//! | `ItemDef` `def:DisplayFormat`    | SAS display format                |
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xportrs::define::DefineXml;
+//!
+//! let define = DefineXml::from_path("define.xml")?;
+//! for dataset in define.datasets() {
+//!     println!("{}: {} variables", dataset.name, dataset.variables.len());
+//! }
+//! # Ok::<(), xportrs::Error>(())
+//! ```
+//!
+//! Generating a define.xml for the files written. Define-XML 2.1 requires
+//! the standard, class and structure of every dataset, and the file it is
+//! archived in:
+//!
+//! ```no_run
+//! use xportrs::define::{DefineDataset, DefineStandard, DefineXml};
+//! use xportrs::{Dataset, Xpt};
+//!
+//! # let ae = Dataset::new("AE", vec![]).unwrap();
+//! let validated = Xpt::writer(ae).finalize()?;
+//! let ae_def = DefineDataset::from(&validated)
+//!     .with_standard(DefineStandard::new("SDTMIG", "3.4"))
+//!     .with_class("EVENTS")
+//!     .with_structure("One record per adverse event per subject");
+//! let files = validated.write_path("ae.xpt")?;
+//!
+//! let mut define = DefineXml::new("STUDY1");
+//! for dataset in ae_def.for_files(&files) {
+//!     define.push(dataset);
+//! }
+//! define.write_path("define.xml")?;
+//! # Ok::<(), xportrs::Error>(())
+//! ```
+
+mod check;
+mod generate;
+mod parse;
+
+use std::path::Path;
+
+#[cfg(feature = "cdisc")]
+use crate::cdisc::Standard;
+use crate::dataset::VariableRole;
+use crate::error::{Error, Result};
+use crate::metadata::{DatasetMetadata, VariableMetadata, XptVarType};
+
+pub(crate) use check::reconcile;
+
+/// A Define-XML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefineXml {
+    version: Option<String>,
+    study_name: Option<String>,
+    datasets: Vec<DefineDataset>,
+}
+
+impl DefineXml {
+    /// Creates an empty document for the given study.
+    #[must_use]
+    pub fn new(study_name: impl Into<String>) -> Self {
+        Self {
+            version: None,
+            study_name: Some(study_name.into()),
+            datasets: Vec::new(),
+        }
+    }
+
+    /// Reads and parses a Define-XML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid
+    /// Define-XML document.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let xml = std::fs::read_to_string(path.as_ref()).map_err(Error::Io)?;
+        Self::parse(&xml)
+    }
+
+    /// Parses a Define-XML document from a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Metadata`] if the document is not well-formed XML, or
+    /// an `ItemRef` points to an `ItemDef` that does not exist.
+    pub fn parse(xml: &str) -> Result<Self> {
+        parse::parse_define(xml)
+    }
+
+    /// Returns the Define-XML version (e.g. `"2.1.0"`), if declared.
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the study name, if declared.
+    #[must_use]
+    pub fn study_name(&self) -> Option<&str> {
+        self.study_name.as_deref()
+    }
+
+    /// Returns the datasets defined in the document, in document order.
+    #[must_use]
+    pub fn datasets(&self) -> &[DefineDataset] {
+        &self.datasets
+    }
+
+    /// Returns the dataset with the given name (case-insensitive).
+    #[must_use]
+    pub fn dataset(&self, name: &str) -> Option<&DefineDataset> {
+        self.datasets
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds a dataset, replacing any dataset with the same name.
+    pub fn push(&mut self, dataset: DefineDataset) {
+        match self
+            .datasets
+            .iter_mut()
+            .find(|d| d.name.eq_ignore_ascii_case(&dataset.name))
+        {
+            Some(existing) => *existing = dataset,
+            None => self.datasets.push(dataset),
+        }
+    }
+
+    /// Returns the standards of the datasets, in order of first use.
+    #[must_use]
+    pub fn standards(&self) -> Vec<&DefineStandard> {
+        let mut standards: Vec<&DefineStandard> = Vec::new();
+        for standard in self.datasets.iter().filter_map(|d| d.standard.as_ref()) {
+            if !standards.contains(&standard) {
+                standards.push(standard);
+            }
+        }
+        standards
+    }
+
+    /// Renders the document as Define-XML 2.1.
+    ///
+    /// Documents read from Define-XML 2.0 are written as 2.1 as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Metadata`] if the document lacks something that
+    /// Define-XML 2.1 requires: a dataset with a standard, and the class,
+    /// structure and archive location of every dataset.
+    pub fn to_xml(&self) -> Result<String> {
+        generate::write_define(self)
+    }
+
+    /// Writes the document as Define-XML 2.1 to the specified path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is incomplete (see
+    /// [`to_xml`](Self::to_xml)) or the file cannot be written.
+    pub fn write_path(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path.as_ref(), self.to_xml()?).map_err(Error::Io)
+    }
+}
+
+/// An implementation guide (`def:Standard`) in a Define-XML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefineStandard {
+    /// The standard name (e.g., `SDTMIG`, `SENDIG`, `ADaMIG`).
+    pub name: String,
+
+    /// The standard version (e.g., "3.4").
+    pub version: String,
+}
+
+impl DefineStandard {
+    /// Creates a standard with the given name and version.
+    #[must_use]
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+}
+
+/// A dataset (`ItemGroupDef`) in a Define-XML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefineDataset {
+    /// The dataset name (e.g., "AE").
+    pub name: String,
+
+    /// The SAS dataset name, if it differs from the name (e.g., "AE" for
+    /// the parts of a split dataset named `AE_001`, `AE_002`, ...).
+    pub sas_name: Option<String>,
+
+    /// The dataset label.
+    pub label: Option<String>,
+
+    /// The implementation guide the dataset follows; `None` for a
+    /// non-standard dataset.
+    pub standard: Option<DefineStandard>,
+
+    /// The dataset class (e.g., "EVENTS", "FINDINGS").
+    pub class: Option<String>,
+
+    /// The dataset structure (e.g., "One record per adverse event per subject").
+    pub structure: Option<String>,
+
+    /// The transport file name (e.g., "ae.xpt").
+    pub archive_location: Option<String>,
+
+    /// Whether a subject can have more than one record.
+    pub repeating: bool,
+
+    /// The variables, in `ItemRef` order.
+    pub variables: Vec<DefineVariable>,
+}
+
+impl DefineDataset {
+    /// Creates the definition of a dataset from its specification.
+    ///
+    /// The variables are listed in specification order (see
+    /// [`VariableMetadata::order`]), with their labels, display formats and
+    /// roles. Character variables are `text` with the specified length.
+    /// Numeric variables are `float`, as their values are not known, with
+    /// the specified length as their number of digits. Every variable is
+    /// optional and the dataset is repeating.
+    #[must_use]
+    pub fn from_metadata(dataset: &DatasetMetadata, variables: &[VariableMetadata]) -> Self {
+        let mut variables: Vec<&VariableMetadata> = variables
+            .iter()
+            .filter(|v| v.domain_code.eq_ignore_ascii_case(&dataset.domain_code))
+            .collect();
+        variables.sort_by_key(|v| v.order.unwrap_or(i32::MAX));
+
+        let variables = variables
+            .into_iter()
+            .enumerate()
+            .map(|(i, var)| {
+                let is_character = var.xpt_type.is_some_and(|t| t.is_character());
+                DefineVariable {
+                    name: var.variable_name.clone(),
+                    label: var.label.clone(),
+                    data_type: if is_character { "text" } else { "float" }.to_string(),
+                    length: var.length,
+                    significant_digits: None,
+                    display_format: var.format.clone(),
+                    order: i32::try_from(i + 1).ok(),
+                    mandatory: false,
+                    role: var.role,
+                }
+            })
+            .collect();
+
+        Self {
+            name: dataset.domain_code.clone(),
+            label: dataset.dataset_label.clone(),
+            repeating: true,
+            variables,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the implementation guide the dataset follows.
+    #[must_use]
+    pub fn with_standard(mut self, standard: DefineStandard) -> Self {
+        self.standard = Some(standard);
+        self
+    }
+
+    /// Sets the standard, class and structure from an implementation guide.
+    ///
+    /// The class and structure are those of the IG dataset of the same
+    /// name, if the IG defines one; an `ADaM` dataset takes those of its data
+    /// structure (`ADSL`, `BDS` or `OCCDS`). The class is spelled as in the
+    /// Define-XML terminology (`Special-Purpose` is `SPECIAL PURPOSE`).
+    #[cfg(feature = "cdisc")]
+    #[must_use]
+    pub fn with_ig(mut self, standard: &Standard) -> Self {
+        if let Some(def) = standard.structure(&self.name) {
+            self.class = Some(def.class.to_uppercase().replace('-', " "));
+            self.structure = def.structure.clone().or(self.structure);
+        }
+        self.standard = Some(DefineStandard::from(standard));
+        self
+    }
+
+    /// Sets the dataset class.
+    #[must_use]
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// Sets the dataset structure.
+    #[must_use]
+    pub fn with_structure(mut self, structure: impl Into<String>) -> Self {
+        self.structure = Some(structure.into());
+        self
+    }
+
+    /// Sets the transport file name.
+    #[must_use]
+    pub fn with_archive_location(mut self, file_name: impl Into<String>) -> Self {
+        self.archive_location = Some(file_name.into());
+        self
+    }
+
+    /// Returns the definitions of the files a dataset was written to, as
+    /// returned by [`ValidatedWrite::write_path`](crate::ValidatedWrite::write_path).
+    ///
+    /// A dataset written to one file is archived in that file. The parts of
+    /// a split dataset are defined as datasets of their own, named after
+    /// their files (`ae_001.xpt` is `AE_001`) with the dataset's name as
+    /// their SAS dataset name.
+    #[must_use]
+    pub fn for_files(self, files: &[impl AsRef<Path>]) -> Vec<Self> {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        };
+        match files {
+            [file] => vec![Self {
+                archive_location: file_name(file.as_ref()),
+                ..self
+            }],
+            _ => files
+                .iter()
+                .map(|file| {
+                    let file = file.as_ref();
+                    let stem = file.file_stem().map(|stem| stem.to_string_lossy());
+                    Self {
+                        name: stem.map_or_else(|| self.name.clone(), |s| s.to_uppercase()),
+                        sas_name: Some(self.sas_name.clone().unwrap_or_else(|| self.name.clone())),
+                        archive_location: file_name(file),
+                        ..self.clone()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the variable with the given name, if defined.
+    #[must_use]
+    pub fn variable(&self, name: &str) -> Option<&DefineVariable> {
+        self.variables.iter().find(|v| v.name == name)
+    }
+
+    /// Converts the dataset definition into [`DatasetMetadata`].
+    #[must_use]
+    pub fn dataset_metadata(&self) -> DatasetMetadata {
+        let meta = DatasetMetadata::new(&self.name);
+        match &self.label {
+            Some(label) => meta.with_label(label),
+            None => meta,
+        }
+    }
+
+    /// Converts the variable definitions into [`VariableMetadata`].
+    #[must_use]
+    pub fn variable_metadata(&self) -> Vec<VariableMetadata> {
+        self.variables
+            .iter()
+            .map(|v| v.to_metadata(&self.name))
+            .collect()
+    }
+}
+
+/// A variable (`ItemRef` and its `ItemDef`) in a Define-XML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefineVariable {
+    /// The variable name.
+    pub name: String,
+
+    /// The variable label.
+    pub label: Option<String>,
+
+    /// The Define-XML data type (e.g., "text", "integer", "datetime").
+    pub data_type: String,
+
+    /// The maximum length of the values.
+    pub length: Option<usize>,
+
+    /// The number of decimal places of `float` values.
+    pub significant_digits: Option<usize>,
+
+    /// The SAS display format (e.g., "DATE9.").
+    pub display_format: Option<String>,
+
+    /// The position of the variable in the dataset.
+    pub order: Option<i32>,
+
+    /// Whether the variable is mandatory.
+    pub mandatory: bool,
+
+    /// The CDISC variable role.
+    pub role: Option<VariableRole>,
+}
+
+impl DefineVariable {
+    /// Returns the XPT type for the Define-XML data type.
+    ///
+    /// `integer` and `float` are numeric; every other data type, including
+    /// the ISO 8601 date and time types, is character.
+    #[must_use]
+    pub fn xpt_type(&self) -> XptVarType {
+        match self.data_type.as_str() {
+            "integer" | "float" => XptVarType::Numeric,
+            _ => XptVarType::Character,
+        }
+    }
+
+    /// Converts the variable definition into [`VariableMetadata`].
+    ///
+    /// The length is only carried over for character variables.
+    #[must_use]
+    pub fn to_metadata(&self, domain_code: &str) -> VariableMetadata {
+        let xpt_type = self.xpt_type();
+        VariableMetadata {
+            domain_code: domain_code.to_string(),
+            variable_name: self.name.clone(),
+            xpt_type: Some(xpt_type),
+            length: self.length.filter(|_| xpt_type.is_character()),
+            numeric_length: None,
+            label: self.label.clone(),
+            format: self.display_format.clone(),
+            order: self.order,
+            role: self.role,
+        }
+    }
+}
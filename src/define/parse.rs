@@ -0,0 +1,374 @@
+//! Define-XML document parsing.
+
+use std::collections::HashMap;
+
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+
+use crate::dataset::VariableRole;
+use crate::error::{Error, Result};
+
+use super::{DefineDataset, DefineStandard, DefineVariable, DefineXml};
+
+/// An `ItemGroupDef` before its `ItemRef`s are resolved.
+#[derive(Default)]
+struct RawGroup {
+    name: String,
+    sas_name: Option<String>,
+    label: Option<String>,
+    standard: Option<DefineStandard>,
+    class: Option<String>,
+    structure: Option<String>,
+    archive_location: Option<String>,
+    repeating: bool,
+    refs: Vec<RawRef>,
+}
+
+/// An `ItemRef` within an `ItemGroupDef`.
+struct RawRef {
+    item_oid: String,
+    order: Option<i32>,
+    mandatory: bool,
+    role: Option<VariableRole>,
+}
+
+/// An `ItemDef`, keyed by OID.
+#[derive(Default)]
+struct RawItem {
+    name: String,
+    label: Option<String>,
+    data_type: String,
+    length: Option<usize>,
+    significant_digits: Option<usize>,
+    display_format: Option<String>,
+}
+
+/// The element whose text content is being read.
+enum TextTarget {
+    StudyName,
+    Label,
+}
+
+/// Parses a Define-XML 2.0 or 2.1 document.
+pub(super) fn parse_define(xml: &str) -> Result<DefineXml> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut version = None;
+    let mut study_name = None;
+    let mut standards: HashMap<String, DefineStandard> = HashMap::new();
+    let mut groups = Vec::new();
+    let mut items: HashMap<String, RawItem> = HashMap::new();
+
+    // Local names of the open elements, and the item being read
+    let mut stack: Vec<String> = Vec::new();
+    let mut group: Option<RawGroup> = None;
+    let mut item: Option<(String, RawItem)> = None;
+    let mut text: Option<(TextTarget, String)> = None;
+
+    loop {
+        let event = reader.read_event().map_err(xml_error)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = local_name(e);
+                let parent = stack.last().map(String::as_str);
+                let is_start = matches!(event, Event::Start(_));
+                match (name.as_str(), parent) {
+                    ("MetaDataVersion", _) => {
+                        version = attributes(e)?.remove("DefineVersion");
+                    }
+                    ("StudyName", Some("GlobalVariables")) if is_start => {
+                        text = Some((TextTarget::StudyName, String::new()));
+                    }
+                    ("Standard", Some("Standards")) => {
+                        let mut attrs = attributes(e)?;
+                        standards.insert(
+                            attrs.remove("OID").unwrap_or_default(),
+                            DefineStandard {
+                                name: attrs.remove("Name").unwrap_or_default(),
+                                version: attrs.remove("Version").unwrap_or_default(),
+                            },
+                        );
+                    }
+                    ("ItemGroupDef", Some("MetaDataVersion")) => {
+                        let mut attrs = attributes(e)?;
+                        let name = attrs.remove("Name").unwrap_or_default();
+                        group = Some(RawGroup {
+                            sas_name: attrs.remove("SASDatasetName").filter(|n| *n != name),
+                            name,
+                            // Define-XML 2.1 refers to the standards, which
+                            // come before the datasets
+                            standard: attrs
+                                .get("StandardOID")
+                                .and_then(|oid| standards.get(oid))
+                                .cloned(),
+                            // Define-XML 2.0 has the class as an attribute
+                            class: attrs.remove("Class"),
+                            structure: attrs.remove("Structure"),
+                            repeating: attrs.get("Repeating").is_some_and(|v| v == "Yes"),
+                            ..RawGroup::default()
+                        });
+                    }
+                    ("Class", Some("ItemGroupDef")) => {
+                        if let Some(group) = group.as_mut() {
+                            group.class = attributes(e)?.remove("Name");
+                        }
+                    }
+                    ("leaf", Some("ItemGroupDef")) => {
+                        if let Some(group) = group.as_mut() {
+                            group.archive_location = attributes(e)?.remove("href");
+                        }
+                    }
+                    ("ItemRef", Some("ItemGroupDef")) => {
+                        let mut attrs = attributes(e)?;
+                        if let Some(group) = group.as_mut() {
+                            group.refs.push(RawRef {
+                                item_oid: attrs.remove("ItemOID").unwrap_or_default(),
+                                order: attrs.get("OrderNumber").and_then(|v| v.parse().ok()),
+                                mandatory: attrs.get("Mandatory").is_some_and(|v| v == "Yes"),
+                                role: attrs.get("Role").and_then(|v| VariableRole::from_cdisc(v)),
+                            });
+                        }
+                    }
+                    ("ItemDef", Some("MetaDataVersion")) => {
+                        let mut attrs = attributes(e)?;
+                        item = Some((
+                            attrs.remove("OID").unwrap_or_default(),
+                            RawItem {
+                                name: attrs.remove("Name").unwrap_or_default(),
+                                data_type: attrs.remove("DataType").unwrap_or_default(),
+                                length: attrs.get("Length").and_then(|v| v.parse().ok()),
+                                significant_digits: attrs
+                                    .get("SignificantDigits")
+                                    .and_then(|v| v.parse().ok()),
+                                display_format: attrs.remove("DisplayFormat"),
+                                ..RawItem::default()
+                            },
+                        ));
+                    }
+                    // Only the description of the group or item itself is a
+                    // label; origins and methods have descriptions too
+                    ("TranslatedText", Some("Description")) => {
+                        let owner = stack.len().checked_sub(2).map(|i| stack[i].as_str());
+                        if is_start && matches!(owner, Some("ItemGroupDef" | "ItemDef")) {
+                            text = Some((TextTarget::Label, String::new()));
+                        }
+                    }
+                    _ => {}
+                }
+
+                if is_start {
+                    stack.push(name);
+                } else {
+                    close(&name, &mut group, &mut item, &mut groups, &mut items);
+                }
+            }
+            Event::Text(e) => {
+                if let Some((_, text)) = text.as_mut() {
+                    text.push_str(&e.decode().map_err(xml_error)?);
+                }
+            }
+            Event::GeneralRef(e) => {
+                if let Some((_, text)) = text.as_mut() {
+                    text.push_str(&resolve_reference(&e)?);
+                }
+            }
+            Event::End(_) => {
+                let name = stack.pop().unwrap_or_default();
+                if matches!(name.as_str(), "TranslatedText" | "StudyName")
+                    && let Some((target, value)) = text.take()
+                {
+                    let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+                    match target {
+                        TextTarget::StudyName => study_name = value,
+                        TextTarget::Label => {
+                            if let Some((_, item)) = item.as_mut() {
+                                item.label = item.label.take().or(value);
+                            } else if let Some(group) = group.as_mut() {
+                                group.label = group.label.take().or(value);
+                            }
+                        }
+                    }
+                }
+                close(&name, &mut group, &mut item, &mut groups, &mut items);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let datasets = groups
+        .into_iter()
+        .map(|group| resolve_group(group, &items))
+        .collect::<Result<_>>()?;
+
+    Ok(DefineXml {
+        version,
+        study_name,
+        datasets,
+    })
+}
+
+/// Stores a finished `ItemGroupDef` or `ItemDef`.
+fn close(
+    name: &str,
+    group: &mut Option<RawGroup>,
+    item: &mut Option<(String, RawItem)>,
+    groups: &mut Vec<RawGroup>,
+    items: &mut HashMap<String, RawItem>,
+) {
+    match name {
+        "ItemGroupDef" => groups.extend(group.take()),
+        "ItemDef" => {
+            if let Some((oid, item)) = item.take() {
+                items.insert(oid, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Joins the `ItemRef`s of a group with their `ItemDef`s.
+fn resolve_group(group: RawGroup, items: &HashMap<String, RawItem>) -> Result<DefineDataset> {
+    let variables = group
+        .refs
+        .into_iter()
+        .map(|r| {
+            let item = items.get(&r.item_oid).ok_or_else(|| {
+                Error::metadata(format!(
+                    "ItemRef '{}' in dataset '{}' has no matching ItemDef",
+                    r.item_oid, group.name
+                ))
+            })?;
+            Ok(DefineVariable {
+                name: item.name.clone(),
+                label: item.label.clone(),
+                data_type: item.data_type.clone(),
+                length: item.length,
+                significant_digits: item.significant_digits,
+                display_format: item.display_format.clone(),
+                order: r.order,
+                mandatory: r.mandatory,
+                role: r.role,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(DefineDataset {
+        name: group.name,
+        sas_name: group.sas_name,
+        label: group.label,
+        standard: group.standard,
+        class: group.class,
+        structure: group.structure,
+        archive_location: group.archive_location,
+        repeating: group.repeating,
+        variables,
+    })
+}
+
+/// Returns the local name (without namespace prefix) of an element.
+fn local_name(e: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// Returns the attributes of an element, keyed by local name.
+fn attributes(e: &BytesStart<'_>) -> Result<HashMap<String, String>> {
+    e.attributes()
+        .map(|attr| {
+            let attr = attr.map_err(xml_error)?;
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+            let value = attr.unescape_value().map_err(xml_error)?.into_owned();
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Resolves a character or predefined entity reference in text content.
+fn resolve_reference(e: &BytesRef<'_>) -> Result<String> {
+    if let Some(ch) = e.resolve_char_ref().map_err(xml_error)? {
+        return Ok(ch.to_string());
+    }
+    let name = e.decode().map_err(xml_error)?;
+    resolve_predefined_entity(&name)
+        .map(String::from)
+        .ok_or_else(|| Error::metadata(format!("invalid Define-XML: unknown entity '&{name};'")))
+}
+
+fn xml_error(e: impl std::fmt::Display) -> Error {
+    Error::metadata(format!("invalid Define-XML: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ODM xmlns="http://www.cdisc.org/ns/odm/v1.3" xmlns:def="http://www.cdisc.org/ns/def/v2.1">
+  <Study OID="S1">
+    <MetaDataVersion OID="MDV1" Name="Study 1" def:DefineVersion="2.1.0">
+      <ItemGroupDef OID="IG.AE" Name="AE" Domain="AE" Repeating="Yes">
+        <Description><TranslatedText xml:lang="en">Adverse Events</TranslatedText></Description>
+        <ItemRef ItemOID="IT.AE.USUBJID" OrderNumber="1" Mandatory="Yes" Role="Identifier"/>
+        <ItemRef ItemOID="IT.AE.AETERM" OrderNumber="2" Mandatory="Yes" Role="Topic"/>
+        <ItemRef ItemOID="IT.AE.AESTDY" OrderNumber="3" Mandatory="No" Role="Timing"/>
+      </ItemGroupDef>
+      <ItemDef OID="IT.AE.USUBJID" Name="USUBJID" DataType="text" Length="20">
+        <Description><TranslatedText xml:lang="en">Unique Subject Identifier</TranslatedText></Description>
+      </ItemDef>
+      <ItemDef OID="IT.AE.AETERM" Name="AETERM" DataType="text" Length="200">
+        <Description><TranslatedText xml:lang="en">Reported Term for the Adverse Event</TranslatedText></Description>
+        <def:Origin Type="Collected"><Description><TranslatedText>CRF page 12</TranslatedText></Description></def:Origin>
+      </ItemDef>
+      <ItemDef OID="IT.AE.AESTDY" Name="AESTDY" DataType="integer" Length="8" def:DisplayFormat="8.">
+        <Description><TranslatedText xml:lang="en">Study Day of Start &amp; End</TranslatedText></Description>
+      </ItemDef>
+    </MetaDataVersion>
+  </Study>
+</ODM>"#;
+
+    #[test]
+    fn test_parse_define() {
+        let define = DefineXml::parse(DEFINE).unwrap();
+        assert_eq!(define.version(), Some("2.1.0"));
+        assert_eq!(define.datasets().len(), 1);
+
+        let ae = define.dataset("ae").unwrap();
+        assert_eq!(ae.name, "AE");
+        assert_eq!(ae.label.as_deref(), Some("Adverse Events"));
+
+        let names: Vec<&str> = ae.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["USUBJID", "AETERM", "AESTDY"]);
+
+        let aeterm = ae.variable("AETERM").unwrap();
+        assert_eq!(
+            aeterm.label.as_deref(),
+            Some("Reported Term for the Adverse Event")
+        );
+        assert_eq!(aeterm.length, Some(200));
+        assert_eq!(aeterm.role, Some(VariableRole::Topic));
+        assert!(aeterm.mandatory);
+
+        let aestdy = ae.variable("AESTDY").unwrap();
+        assert_eq!(aestdy.label.as_deref(), Some("Study Day of Start & End"));
+        assert_eq!(aestdy.display_format.as_deref(), Some("8."));
+        assert_eq!(aestdy.order, Some(3));
+        assert!(aestdy.xpt_type().is_numeric());
+        assert!(!aestdy.mandatory);
+    }
+
+    #[test]
+    fn test_parse_define_errors() {
+        let dangling = DEFINE.replace(r#"ItemDef OID="IT.AE.AETERM""#, r#"ItemDef OID="IT.X""#);
+        assert!(matches!(
+            DefineXml::parse(&dangling),
+            Err(Error::Metadata { .. })
+        ));
+
+        let malformed = DEFINE.replace("</Study>", "</Stud>");
+        assert!(matches!(
+            DefineXml::parse(&malformed),
+            Err(Error::Metadata { .. })
+        ));
+    }
+}
@@ -44,7 +44,7 @@
 //! // Write with FDA agency compliance validation
 //! let mut builder = Xpt::writer(dataset);
 //! builder.agency(Agency::FDA);
-//! builder.finalize()?.write_path("ae_fda.xpt")?;
+//! builder.finalize()?.write_path("fda/ae.xpt")?;
 //! # Ok::<(), xportrs::Error>(())
 //! ```
 //!
@@ -87,6 +87,8 @@
 //! | `serde`   | Serialization/deserialization support              |
 //! | `tracing` | Structured logging with the `tracing` crate       |
 //! | `polars`  | Polars `DataFrame` integration                     |
+//! | `define-xml` | Define-XML import and generation (`define` module) |
+//! | `cdisc`   | CDISC IG conformance checks (`cdisc` module)       |
 //! | `full`    | All optional features                              |
 //!
 //! ## CDISC Terminology
@@ -115,6 +117,10 @@ mod write_plan;
 pub mod xpt;
 
 // Optional integrations
+#[cfg(feature = "cdisc")]
+pub mod cdisc;
+#[cfg(feature = "define-xml")]
+pub mod define;
 #[cfg(feature = "polars")]
 pub mod polars;
 
@@ -137,19 +143,25 @@ pub use dataset::{
 pub use error::{Error, Result};
 
 // Metadata types - for advanced usage
-pub use metadata::XptVarType;
+pub use metadata::{DatasetMetadata, VariableMetadata, XptVarType};
 
 // Validation types
-pub use validate::{Issue, Severity};
+pub use validate::{Fix, Issue, MemberIssues, Severity};
 
 // Write plan types
-pub use write_plan::{ValidatedWrite, XptWriterBuilder};
+pub use write_plan::{ValidatedWrite, XptLibraryWriter, XptWriterBuilder};
 
 // XPT version enum
 pub use xpt::XptVersion;
 
-// XPT file info (for Xpt::inspect)
-pub use xpt::v5::read::XptInfo;
+// Streaming writer (for ValidatedWrite::stream_path)
+pub use xpt::v5::write::XptStreamWriter;
+
+// XPT file info (for Xpt::inspect) and batch reading
+pub use xpt::v5::read::{XptBatchReader, XptInfo};
+
+// SAS missing value codes (for Column::with_special_missing)
+pub use xpt::v5::encoding::SasMissingValue;
 
 /// Temporal conversion utilities.
 ///
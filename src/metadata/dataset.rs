@@ -5,10 +5,11 @@
 /// Metadata describing a dataset.
 ///
 /// This struct provides optional metadata that can override or supplement
-/// the information in a [`Dataset`](crate::Dataset).
+/// the information in a [`Dataset`](crate::Dataset). Set it with
+/// [`XptWriterBuilder::dataset_metadata`](crate::XptWriterBuilder::dataset_metadata).
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub(crate) struct DatasetMetadata {
+pub struct DatasetMetadata {
     /// The domain code (e.g., "AE", "DM", "LB").
     pub domain_code: String,
 
@@ -18,11 +19,10 @@ pub(crate) struct DatasetMetadata {
     pub dataset_label: Option<String>,
 }
 
-#[allow(dead_code)]
 impl DatasetMetadata {
     /// Creates new dataset metadata with the given domain code.
     #[must_use]
-    pub(crate) fn new(domain_code: impl Into<String>) -> Self {
+    pub fn new(domain_code: impl Into<String>) -> Self {
         Self {
             domain_code: domain_code.into(),
             dataset_label: None,
@@ -31,7 +31,7 @@ impl DatasetMetadata {
 
     /// Sets the dataset label.
     #[must_use]
-    pub(crate) fn with_label(mut self, label: impl Into<String>) -> Self {
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
         self.dataset_label = Some(label.into());
         self
     }
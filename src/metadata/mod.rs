@@ -7,6 +7,5 @@
 mod dataset;
 mod variable;
 
-pub(crate) use dataset::DatasetMetadata;
-pub(crate) use variable::VariableMetadata;
-pub use variable::XptVarType;
+pub use dataset::DatasetMetadata;
+pub use variable::{VariableMetadata, XptVarType};
@@ -7,10 +7,14 @@ use crate::dataset::VariableRole;
 /// Metadata describing a single variable.
 ///
 /// This struct provides optional metadata that guides XPT file generation.
-/// When provided, it takes precedence over inferred values from the data.
+/// When provided via [`XptWriterBuilder::variable_metadata`], it takes
+/// precedence over the [`Column`] and over values inferred from the data.
+///
+/// [`XptWriterBuilder::variable_metadata`]: crate::XptWriterBuilder::variable_metadata
+/// [`Column`]: crate::Column
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub(crate) struct VariableMetadata {
+pub struct VariableMetadata {
     /// The domain code this variable belongs to.
     pub domain_code: String,
 
@@ -24,10 +28,17 @@ pub(crate) struct VariableMetadata {
 
     /// The byte length for character variables.
     ///
-    /// For numeric variables, this is always 8 in XPT v5.
+    /// For numeric variables, this is always 8 in XPT v5 (see
+    /// [`numeric_length`](Self::numeric_length)).
     /// For character variables, this specifies the fixed-width length.
     pub length: Option<usize>,
 
+    /// The byte length for numeric variables, if shorter than 8 bytes.
+    ///
+    /// A length of 3 to 7 stores the values with reduced precision and is
+    /// reported as [`Issue::NumericPrecisionReduced`](crate::Issue::NumericPrecisionReduced).
+    pub numeric_length: Option<usize>,
+
     /// The variable label (description).
     ///
     /// Limited to 40 bytes in XPT v5.
@@ -47,11 +58,10 @@ pub(crate) struct VariableMetadata {
     pub role: Option<VariableRole>,
 }
 
-#[allow(dead_code)]
 impl VariableMetadata {
     /// Creates new variable metadata with required fields.
     #[must_use]
-    pub(crate) fn new(domain_code: impl Into<String>, variable_name: impl Into<String>) -> Self {
+    pub fn new(domain_code: impl Into<String>, variable_name: impl Into<String>) -> Self {
         Self {
             domain_code: domain_code.into(),
             variable_name: variable_name.into(),
@@ -61,42 +71,49 @@ impl VariableMetadata {
 
     /// Sets the XPT type.
     #[must_use]
-    pub(crate) fn with_xpt_type(mut self, xpt_type: XptVarType) -> Self {
+    pub fn with_xpt_type(mut self, xpt_type: XptVarType) -> Self {
         self.xpt_type = Some(xpt_type);
         self
     }
 
     /// Sets the byte length.
     #[must_use]
-    pub(crate) fn with_length(mut self, length: usize) -> Self {
+    pub fn with_length(mut self, length: usize) -> Self {
         self.length = Some(length);
         self
     }
 
+    /// Sets the byte length of a numeric variable.
+    #[must_use]
+    pub fn with_numeric_length(mut self, length: usize) -> Self {
+        self.numeric_length = Some(length);
+        self
+    }
+
     /// Sets the label.
     #[must_use]
-    pub(crate) fn with_label(mut self, label: impl Into<String>) -> Self {
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
         self.label = Some(label.into());
         self
     }
 
     /// Sets the format.
     #[must_use]
-    pub(crate) fn with_format(mut self, format: impl Into<String>) -> Self {
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
         self.format = Some(format.into());
         self
     }
 
     /// Sets the ordering key.
     #[must_use]
-    pub(crate) fn with_order(mut self, order: i32) -> Self {
+    pub fn with_order(mut self, order: i32) -> Self {
         self.order = Some(order);
         self
     }
 
     /// Sets the variable role.
     #[must_use]
-    pub(crate) fn with_role(mut self, role: VariableRole) -> Self {
+    pub fn with_role(mut self, role: VariableRole) -> Self {
         self.role = Some(role);
         self
     }
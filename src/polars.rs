@@ -44,12 +44,45 @@
 //! let dataset = Dataset::from_dataframe("AE", df).unwrap();
 //! assert_eq!(dataset.ncols(), 2);
 //! ```
+//!
+//! # Special Missing Values
+//!
+//! Polars has a single null, so SAS special missing values (`.A`-`.Z`, `._`)
+//! of `F64` columns are null like standard missing values. Their codes are
+//! kept in a companion string column named after the column with a
+//! [`SPECIAL_MISSING_SUFFIX`], placed right after it and holding `".A"`,
+//! `"._"` and so on, or null. The suffix cannot occur in SAS variable names.
+//! [`FromDataFrame`] reads the companion columns back into
+//! [`Column::with_special_missing`](crate::Column::with_special_missing), so
+//! a round trip through Polars keeps the codes.
+//!
+//! ```
+//! use xportrs::{Column, ColumnData, Dataset, SasMissingValue, polars::IntoDataFrame};
+//!
+//! let dataset = Dataset::new(
+//!     "LB",
+//!     vec![
+//!         Column::new("LBSTRESN", ColumnData::F64(vec![Some(5.2), None]))
+//!             .with_special_missing(vec![None, Some(SasMissingValue::Special('U'))]),
+//!     ],
+//! ).unwrap();
+//!
+//! let df = dataset.into_dataframe().unwrap();
+//! assert_eq!(df.column("LBSTRESN").unwrap().null_count(), 1);
+//! let codes = df.column("LBSTRESN.special_missing").unwrap();
+//! assert_eq!(codes.str().unwrap().get(1), Some(".U"));
+//! ```
 
 use chrono::Timelike;
 use polars::prelude::*;
 
 use crate::dataset::{Column as XptColumn, ColumnData, Dataset, DomainCode};
 use crate::error::{Error, Result};
+use crate::xpt::v5::encoding::SasMissingValue;
+
+/// Suffix of the companion column holding the special missing codes of a
+/// numeric column.
+pub const SPECIAL_MISSING_SUFFIX: &str = ".special_missing";
 
 /// Extension trait for converting a [`Dataset`] into a Polars [`DataFrame`].
 pub trait IntoDataFrame {
@@ -81,8 +114,10 @@ impl IntoDataFrame for Dataset {
         let mut columns: Vec<Column> = Vec::with_capacity(self.columns().len());
 
         for col in self.columns() {
-            let series = column_data_to_series(col.name(), col.data())?;
-            columns.push(series.into());
+            columns.push(column_data_to_series(col.name(), col.data())?.into());
+            if let Some(codes) = col.special_missing() {
+                columns.push(special_missing_series(col.name(), codes).into());
+            }
         }
 
         DataFrame::new(columns).map_err(|e| Error::Polars {
@@ -96,7 +131,14 @@ impl FromDataFrame for Dataset {
         let mut columns: Vec<XptColumn> = Vec::with_capacity(df.width());
 
         for col in df.get_columns() {
-            let xpt_col = series_to_column(col)?;
+            let name = col.name().as_str();
+            if let Some(base) = name.strip_suffix(SPECIAL_MISSING_SUFFIX)
+                && df.column(base).is_ok()
+            {
+                continue;
+            }
+            let companion = df.column(&format!("{name}{SPECIAL_MISSING_SUFFIX}")).ok();
+            let xpt_col = series_to_column(col, companion)?;
             columns.push(xpt_col);
         }
 
@@ -202,12 +244,55 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
     hex
 }
 
-/// Converts a Polars Column to an xportrs Column.
-fn series_to_column(column: &Column) -> Result<XptColumn> {
+/// Builds the companion column holding the special missing codes of `name`.
+fn special_missing_series(name: &str, codes: &[Option<SasMissingValue>]) -> Series {
+    let ca: StringChunked = codes
+        .iter()
+        .map(|code| code.map(|missing| missing.to_string()))
+        .collect();
+    ca.with_name(format!("{name}{SPECIAL_MISSING_SUFFIX}").into())
+        .into_series()
+}
+
+/// Parses a special missing code (`".A"`-`".Z"` or `"._"`).
+fn parse_special_missing(code: &str) -> Option<SasMissingValue> {
+    let mut chars = code.strip_prefix('.')?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => SasMissingValue::from_code(c).filter(|m| m.is_special()),
+        _ => None,
+    }
+}
+
+/// Converts a Polars Column to an xportrs Column, taking the special
+/// missing codes from its companion column, if any.
+fn series_to_column(column: &Column, companion: Option<&Column>) -> Result<XptColumn> {
     let name = column.name().as_str();
     let series = column.as_materialized_series();
     let data = series_to_column_data(series)?;
-    Ok(XptColumn::new(name, data))
+
+    let Some(companion) = companion else {
+        return Ok(XptColumn::new(name, data));
+    };
+    if !matches!(data, ColumnData::F64(_)) {
+        return Err(Error::Polars {
+            message: format!("special missing codes given for non-float column '{name}'"),
+        });
+    }
+    let ca = companion.str().map_err(|e| Error::Polars {
+        message: e.to_string(),
+    })?;
+    let codes = ca
+        .into_iter()
+        .map(|code| {
+            code.map(|code| {
+                parse_special_missing(code).ok_or_else(|| Error::Polars {
+                    message: format!("invalid special missing code '{code}' for '{name}'"),
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(XptColumn::new(name, data).with_special_missing(codes))
 }
 
 /// Converts a Polars Series to xportrs `ColumnData`.
@@ -402,6 +487,71 @@ mod tests {
         assert_eq!(roundtrip.nrows(), original.nrows());
     }
 
+    #[test]
+    fn test_special_missing_roundtrip() {
+        let codes = vec![
+            None,
+            Some(SasMissingValue::Special('U')),
+            None,
+            Some(SasMissingValue::Underscore),
+        ];
+        let original = Dataset::new(
+            "LB",
+            vec![
+                XptColumn::new(
+                    "LBSTRESN",
+                    ColumnData::F64(vec![Some(1.5), None, None, None]),
+                )
+                .with_special_missing(codes.clone()),
+            ],
+        )
+        .unwrap();
+
+        let df = original.into_dataframe().unwrap();
+        assert_eq!(df.width(), 2);
+        let values = df.column("LBSTRESN").unwrap().f64().unwrap();
+        assert_eq!(values.null_count(), 3);
+        let companion = df.column("LBSTRESN.special_missing").unwrap();
+        let companion = companion.str().unwrap();
+        assert_eq!(
+            companion.into_iter().collect::<Vec<_>>(),
+            vec![None, Some(".U"), None, Some("._")]
+        );
+
+        let roundtrip = Dataset::from_dataframe("LB", df).unwrap();
+        assert_eq!(roundtrip.ncols(), 1);
+        let col = roundtrip.column("LBSTRESN").unwrap();
+        assert_eq!(
+            col.data(),
+            &ColumnData::F64(vec![Some(1.5), None, None, None])
+        );
+        assert_eq!(col.special_missing(), Some(&codes[..]));
+    }
+
+    #[test]
+    fn test_invalid_special_missing_code() {
+        let values = Series::new("LBSTRESN".into(), &[None, Some(1.0)]);
+        let codes = Series::new("LBSTRESN.special_missing".into(), &[Some(".a"), None]);
+        let df = DataFrame::new(vec![values.into(), codes.into()]).unwrap();
+
+        assert!(Dataset::from_dataframe("LB", df).is_err());
+    }
+
+    #[test]
+    fn test_parse_special_missing() {
+        assert_eq!(
+            parse_special_missing(".A"),
+            Some(SasMissingValue::Special('A'))
+        );
+        assert_eq!(
+            parse_special_missing("._"),
+            Some(SasMissingValue::Underscore)
+        );
+        assert_eq!(parse_special_missing("."), None);
+        assert_eq!(parse_special_missing("A"), None);
+        assert_eq!(parse_special_missing(".AB"), None);
+    }
+
     #[test]
     fn test_bytes_to_hex() {
         assert_eq!(bytes_to_hex(&[0x00, 0xff, 0xab]), "00ffab");
@@ -5,14 +5,20 @@
 
 use std::collections::HashMap;
 
-use crate::agency::Agency;
-use crate::config::Config;
+use crate::config::{Config, TextMode};
 use crate::dataset::{ColumnData, Dataset, Format};
 use crate::error::{Error, Result};
 use crate::metadata::{DatasetMetadata, VariableMetadata, XptVarType};
+use crate::validate::Issue;
+use crate::xpt::XptVersion;
+use crate::xpt::v5::encoding::{encoded_len, truncate_encoded};
 
 use super::plan::{DatasetSchema, VariableSpec};
 
+/// Maximum dataset label length; the member header field is 40 bytes in
+/// every XPT version.
+const MAX_DATASET_LABEL_LEN: usize = 40;
+
 /// Derives a schema plan from a dataset and optional metadata.
 ///
 /// This function implements the planning algorithm described in the architecture:
@@ -32,7 +38,6 @@ pub fn derive_schema_plan(
     dataset: &Dataset,
     dataset_meta: Option<&DatasetMetadata>,
     variable_meta: Option<&[VariableMetadata]>,
-    agency: Option<Agency>,
     config: &Config,
 ) -> Result<DatasetSchema> {
     // 1. Resolve domain identity
@@ -44,13 +49,17 @@ pub fn derive_schema_plan(
         .and_then(|m| m.dataset_label.clone())
         .or_else(|| dataset.dataset_label().map(String::from));
 
-    // Build metadata lookup
+    // Build metadata lookup; entries for other domains are ignored so a whole
+    // study specification can be passed for each dataset
     let var_meta_map: HashMap<&str, &VariableMetadata> = variable_meta
         .unwrap_or(&[])
         .iter()
+        .filter(|m| m.domain_code.is_empty() || m.domain_code.eq_ignore_ascii_case(&domain_code))
         .map(|m| (m.variable_name.as_str(), m))
         .collect();
 
+    let text_mode = config.write.text_mode;
+
     // 2-5. Build variable map and determine types/lengths
     let mut planned_vars: Vec<VariableSpec> = Vec::with_capacity(dataset.columns().len());
 
@@ -63,18 +72,33 @@ pub fn derive_schema_plan(
         } else {
             infer_xpt_type(col.data())
         };
+        if !is_writable_as(col.data(), xpt_type) {
+            return Err(Error::metadata(format!(
+                "variable '{}' is specified as {xpt_type} but holds {} data",
+                col.name(),
+                if col.data().is_numeric() {
+                    "numeric"
+                } else {
+                    "character"
+                }
+            )));
+        }
 
-        // 4. Determine length - Column.explicit_length() takes priority, then metadata, then inferred
-        let length_override = col.explicit_length().or(meta.and_then(|m| m.length));
-        let length = determine_length(col.data(), xpt_type, length_override, config)?;
+        // 4. Determine length - metadata takes priority, then the Column's length, then inferred
+        let length_override = match xpt_type {
+            XptVarType::Numeric => meta.and_then(|m| m.numeric_length).or(col.numeric_length()),
+            XptVarType::Character => meta.and_then(|m| m.length).or(col.explicit_length()),
+        };
+        let length = determine_length(col.data(), xpt_type, length_override, text_mode);
 
         // Create planned variable
         let mut planned =
             VariableSpec::new(col.name().to_string(), xpt_type, length).with_source_index(idx);
+        planned.length_specified = length_override.is_some();
 
         // First, apply Column's metadata (label, format, informat, role)
         if let Some(label) = col.label() {
-            planned.label = truncate_to_bytes(label, 40);
+            planned.label = label.to_string();
         }
         if let Some(format) = col.format() {
             planned.format = Some(format.clone());
@@ -89,13 +113,12 @@ pub fn derive_schema_plan(
         // Then, override with VariableMetadata if provided (metadata takes priority)
         if let Some(m) = meta {
             if let Some(ref label) = m.label {
-                planned.label = truncate_to_bytes(label, 40);
+                planned.label.clone_from(label);
             }
             if let Some(ref format_str) = m.format {
-                // Try to parse the format string; ignore invalid formats
-                if let Ok(format) = Format::parse(format_str) {
-                    planned.format = Some(format);
-                }
+                let format = Format::parse(format_str)
+                    .map_err(|e| Error::metadata(format!("variable '{}': {e}", m.variable_name)))?;
+                planned.format = Some(format);
             }
             if let Some(role) = m.role {
                 planned.role = Some(role);
@@ -135,20 +158,10 @@ pub fn derive_schema_plan(
 
     // 7. Compute byte positions and row_len
     let mut plan = DatasetSchema::new(domain_code).with_label(dataset_label);
+    plan.text_mode = text_mode;
     plan.variables = planned_vars;
     plan.recalculate_positions();
 
-    // Apply agency name normalization if auto_fix is enabled
-    if config.auto_fix
-        && let Some(ag) = agency
-        && ag.requires_ascii_names()
-    {
-        plan.domain_code = plan.domain_code.to_ascii_uppercase();
-        for var in &mut plan.variables {
-            var.name = var.name.to_ascii_uppercase();
-        }
-    }
-
     Ok(plan)
 }
 
@@ -161,45 +174,46 @@ fn infer_xpt_type(data: &ColumnData) -> XptVarType {
     }
 }
 
+/// Returns `true` if column data can be written as the given XPT type.
+///
+/// Temporal data can be written either way (SAS date values or ISO 8601 text).
+pub(crate) fn is_writable_as(data: &ColumnData, xpt_type: XptVarType) -> bool {
+    match data {
+        ColumnData::Date(_) | ColumnData::DateTime(_) | ColumnData::Time(_) => true,
+        ColumnData::String(_) | ColumnData::Bytes(_) => xpt_type.is_character(),
+        ColumnData::F64(_) | ColumnData::I64(_) | ColumnData::Bool(_) => xpt_type.is_numeric(),
+    }
+}
+
 /// Determines the byte length for a variable.
+///
+/// A specified length is used as is; values longer than it are reported as
+/// truncated when the data is validated.
 fn determine_length(
     data: &ColumnData,
     xpt_type: XptVarType,
     meta_length: Option<usize>,
-    config: &Config,
-) -> Result<usize> {
+    mode: TextMode,
+) -> usize {
     match xpt_type {
-        XptVarType::Numeric => Ok(8), // Always 8 bytes in XPT v5
+        // 8 bytes unless a shorter length is requested explicitly
+        XptVarType::Numeric => meta_length.unwrap_or(8),
         XptVarType::Character => {
-            // Use metadata length if provided
-            if let Some(len) = meta_length {
-                // Validate against actual data if strict
-                if config.strict_checks {
-                    let max_observed = compute_max_string_length(data);
-                    if max_observed > len {
-                        return Err(Error::invalid_schema(format!(
-                            "character value exceeds specified length: max observed {} > specified {}",
-                            max_observed, len
-                        )));
-                    }
-                }
-                Ok(len)
-            } else {
-                // Compute from data
-                let max_observed = compute_max_string_length(data);
-                Ok(max_observed.max(1)) // Minimum 1 byte for character
-            }
+            // Use metadata length if provided, otherwise compute from data
+            meta_length.unwrap_or_else(|| compute_max_string_length(data, mode).max(1)) // Minimum 1 byte for character
         }
     }
 }
 
 /// Computes the maximum byte length of string values in the data.
-fn compute_max_string_length(data: &ColumnData) -> usize {
+///
+/// String lengths are measured in the character set of `mode`.
+pub(crate) fn compute_max_string_length(data: &ColumnData, mode: TextMode) -> usize {
     match data {
         ColumnData::String(vals) => vals
             .iter()
-            .filter_map(|v| v.as_ref())
-            .map(String::len)
+            .filter_map(|v| v.as_deref())
+            .map(|v| encoded_len(v, mode))
             .max()
             .unwrap_or(0),
         ColumnData::Bytes(vals) => vals
@@ -212,19 +226,45 @@ fn compute_max_string_length(data: &ColumnData) -> usize {
     }
 }
 
-/// Truncates a string to fit within a byte limit.
-fn truncate_to_bytes(s: &str, max_bytes: usize) -> String {
-    if s.len() <= max_bytes {
-        return s.to_string();
-    }
+/// Truncates labels that are longer than the XPT version allows.
+///
+/// Lengths are measured in the schema's character set. Each truncated label
+/// is reported as an [`Issue::LabelTruncated`]; `allowed` marks the
+/// truncations as allowed, which makes them warnings.
+pub(crate) fn truncate_labels(
+    plan: &mut DatasetSchema,
+    version: XptVersion,
+    allowed: bool,
+) -> Vec<Issue> {
+    let mode = plan.text_mode;
+    let mut issues = Vec::new();
+    let mut truncate = |label: &mut String, max: usize, name: &str, is_dataset: bool| {
+        let actual = encoded_len(label, mode);
+        if actual > max {
+            *label = truncate_to_bytes(label, max, mode);
+            issues.push(Issue::LabelTruncated {
+                name: name.to_string(),
+                is_dataset,
+                actual,
+                truncated: encoded_len(label, mode),
+                allowed,
+            });
+        }
+    };
 
-    // Find the last valid UTF-8 boundary within max_bytes
-    let mut end = max_bytes;
-    while end > 0 && !s.is_char_boundary(end) {
-        end -= 1;
+    if let Some(label) = plan.dataset_label.as_mut() {
+        truncate(label, MAX_DATASET_LABEL_LEN, &plan.domain_code, true);
     }
+    for var in &mut plan.variables {
+        truncate(&mut var.label, version.max_label_len(), &var.name, false);
+    }
+
+    issues
+}
 
-    s[..end].to_string()
+/// Truncates a string to fit within a byte limit in the character set of `mode`.
+fn truncate_to_bytes(s: &str, max_bytes: usize, mode: TextMode) -> String {
+    truncate_encoded(s, max_bytes, mode).to_string()
 }
 
 #[cfg(test)]
@@ -244,7 +284,7 @@ mod tests {
         .unwrap();
 
         let config = Config::default();
-        let plan = derive_schema_plan(&dataset, None, None, None, &config).unwrap();
+        let plan = derive_schema_plan(&dataset, None, None, &config).unwrap();
 
         assert_eq!(plan.domain_code, "AE");
         assert_eq!(plan.variables.len(), 2);
@@ -275,7 +315,7 @@ mod tests {
         .unwrap();
 
         let config = Config::default();
-        let plan = derive_schema_plan(&dataset, None, None, None, &config).unwrap();
+        let plan = derive_schema_plan(&dataset, None, None, &config).unwrap();
 
         // Verify labels are wired
         assert_eq!(plan.variables[0].label, "Unique Subject Identifier");
@@ -309,17 +349,141 @@ mod tests {
         .unwrap();
 
         let config = Config::default();
-        let plan = derive_schema_plan(&dataset, None, None, None, &config).unwrap();
+        let plan = derive_schema_plan(&dataset, None, None, &config).unwrap();
 
         // Length should be 100, not 5 (the actual data length)
         assert_eq!(plan.variables[0].length, 100);
     }
 
+    #[test]
+    fn test_numeric_length() {
+        let dataset = Dataset::new(
+            "DM",
+            vec![
+                Column::new("AGE", ColumnData::F64(vec![Some(42.0)])).with_length(3),
+                Column::new("AESEQ", ColumnData::I64(vec![Some(1)])),
+                Column::new("AEDY", ColumnData::I64(vec![Some(1)])).with_numeric_length(5),
+            ],
+        )
+        .unwrap();
+        let config = Config::default();
+
+        // A length in digits from a specification keeps 8 bytes
+        let variable_meta = vec![
+            VariableMetadata::new("DM", "AGE").with_length(3),
+            VariableMetadata::new("DM", "AESEQ").with_numeric_length(4),
+        ];
+        let plan = derive_schema_plan(&dataset, None, Some(&variable_meta), &config).unwrap();
+        let lengths: Vec<usize> = plan.variables.iter().map(|v| v.length).collect();
+        assert_eq!(lengths, [8, 4, 5]);
+    }
+
+    #[test]
+    fn test_variable_metadata_drives_schema() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![
+                Column::new("AETERM", ColumnData::String(vec![Some("HEADACHE".into())]))
+                    .with_length(8),
+                Column::new("AESEQ", ColumnData::I64(vec![Some(1)])),
+                Column::new("EXTRA", ColumnData::F64(vec![Some(1.0)])),
+            ],
+        )
+        .unwrap();
+        let dataset_meta = DatasetMetadata::new("AE").with_label("Adverse Events");
+        let variable_meta = vec![
+            VariableMetadata::new("AE", "AETERM")
+                .with_length(200)
+                .with_label("Reported Term for the Adverse Event")
+                .with_format("$200.")
+                .with_order(2),
+            VariableMetadata::new("AE", "AESEQ")
+                .with_label("Sequence Number")
+                .with_order(1),
+            // Entries for other domains are ignored
+            VariableMetadata::new("DM", "AESEQ").with_order(99),
+        ];
+
+        let plan = derive_schema_plan(
+            &dataset,
+            Some(&dataset_meta),
+            Some(&variable_meta),
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.dataset_label.as_deref(), Some("Adverse Events"));
+        let names: Vec<&str> = plan.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["AESEQ", "AETERM", "EXTRA"]);
+        assert_eq!(plan.variables[0].label, "Sequence Number");
+        assert_eq!(plan.variables[1].length, 200);
+        assert!(plan.variables[1].format.as_ref().unwrap().is_character());
+        assert_eq!(plan.variables[1].format_length(), 200);
+    }
+
+    #[test]
+    fn test_invalid_variable_metadata() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![Column::new("AESEQ", ColumnData::I64(vec![Some(1)]))],
+        )
+        .unwrap();
+        let config = Config::default();
+
+        let bad_format = [VariableMetadata::new("AE", "AESEQ").with_format("not a format")];
+        let result = derive_schema_plan(&dataset, None, Some(&bad_format), &config);
+        assert!(matches!(result, Err(Error::Metadata { .. })));
+
+        let bad_type = [VariableMetadata::new("AE", "AESEQ").with_xpt_type(XptVarType::Character)];
+        let result = derive_schema_plan(&dataset, None, Some(&bad_type), &config);
+        assert!(matches!(result, Err(Error::Metadata { .. })));
+    }
+
+    #[test]
+    fn test_truncate_labels() {
+        let mut plan = DatasetSchema::new("AE").with_label(Some("A".repeat(45)));
+        plan.variables = vec![
+            VariableSpec::numeric("AESEQ").with_label("Sequence Number"),
+            VariableSpec::numeric("AESTDY").with_label(format!("{}é", "x".repeat(39))),
+        ];
+
+        let issues = truncate_labels(&mut plan, XptVersion::V5, false);
+        assert_eq!(
+            issues,
+            [
+                Issue::LabelTruncated {
+                    name: "AE".into(),
+                    is_dataset: true,
+                    actual: 45,
+                    truncated: 40,
+                    allowed: false,
+                },
+                Issue::LabelTruncated {
+                    name: "AESTDY".into(),
+                    is_dataset: false,
+                    actual: 41,
+                    truncated: 39,
+                    allowed: false,
+                },
+            ]
+        );
+        assert_eq!(plan.dataset_label.as_deref().map(str::len), Some(40));
+        assert_eq!(plan.variables[0].label, "Sequence Number");
+        assert_eq!(plan.variables[1].label, "x".repeat(39));
+
+        // V8 allows longer variable labels
+        plan.variables[1].label = "x".repeat(100);
+        assert!(truncate_labels(&mut plan, XptVersion::V8, true).is_empty());
+    }
+
     #[test]
     fn test_truncate_to_bytes() {
-        assert_eq!(truncate_to_bytes("hello", 10), "hello");
-        assert_eq!(truncate_to_bytes("hello world", 5), "hello");
+        let utf8 = TextMode::LossyUtf8;
+        assert_eq!(truncate_to_bytes("hello", 10, utf8), "hello");
+        assert_eq!(truncate_to_bytes("hello world", 5, utf8), "hello");
         // UTF-8 boundary test
-        assert_eq!(truncate_to_bytes("héllo", 2), "h");
+        assert_eq!(truncate_to_bytes("héllo", 2, utf8), "h");
+        // Latin-1 encodes 'é' in one byte
+        assert_eq!(truncate_to_bytes("héllo", 2, TextMode::Latin1), "hé");
     }
 }
@@ -6,5 +6,7 @@
 mod derive;
 pub(crate) mod plan;
 
-pub(crate) use derive::derive_schema_plan;
+#[cfg(feature = "define-xml")]
+pub(crate) use derive::{compute_max_string_length, is_writable_as};
+pub(crate) use derive::{derive_schema_plan, truncate_labels};
 pub(crate) use plan::{DatasetSchema, VariableSpec};
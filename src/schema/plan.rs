@@ -3,8 +3,10 @@
 //! This module defines the [`DatasetSchema`] and [`VariableSpec`] types that
 //! represent the transport schema for an XPT file.
 
+use crate::config::TextMode;
 use crate::dataset::{Format, VariableRole};
 use crate::metadata::XptVarType;
+use crate::xpt::v5::encoding::encoded_len;
 
 /// A planned schema for XPT file generation.
 ///
@@ -23,6 +25,9 @@ pub(crate) struct DatasetSchema {
 
     /// The total row length in bytes.
     pub row_len: usize,
+
+    /// The character set written for values, names and labels.
+    pub text_mode: TextMode,
 }
 
 #[allow(dead_code)]
@@ -35,6 +40,7 @@ impl DatasetSchema {
             dataset_label: None,
             variables: Vec::new(),
             row_len: 0,
+            text_mode: TextMode::default(),
         }
     }
 
@@ -45,6 +51,12 @@ impl DatasetSchema {
         self
     }
 
+    /// Returns the byte length of `s` in the schema's character set.
+    #[must_use]
+    pub fn byte_len(&self, s: &str) -> usize {
+        encoded_len(s, self.text_mode)
+    }
+
     /// Returns the number of variables.
     #[must_use]
     pub fn num_variables(&self) -> usize {
@@ -87,6 +99,10 @@ pub(crate) struct VariableSpec {
     /// The byte length (8 for numeric, variable for character).
     pub length: usize,
 
+    /// Whether the length comes from variable metadata, which auto-fix
+    /// leaves alone.
+    pub length_specified: bool,
+
     /// The variable label (max 40 bytes in v5).
     pub label: String,
 
@@ -120,6 +136,7 @@ impl VariableSpec {
             name,
             xpt_type,
             length,
+            length_specified: false,
             label: String::new(),
             format: None,
             informat: None,
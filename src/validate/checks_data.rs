@@ -0,0 +1,291 @@
+//! Character value validation.
+//!
+//! This module scans the data of a dataset against its schema. It checks
+//! the values themselves, not only the variable attributes, so it runs once
+//! the schema is derived at [`finalize()`](crate::XptWriterBuilder::finalize).
+
+use crate::config::TextMode;
+use crate::dataset::{Column, ColumnData, Dataset};
+use crate::error::{Error, Result};
+use crate::schema::DatasetSchema;
+use crate::schema::plan::VariableSpec;
+use crate::xpt::v5::encoding::{encode_str, truncate_encoded};
+use crate::xpt::v5::write::get_character_value;
+
+use super::issues::Issue;
+
+/// Validates the character values of a dataset against its schema.
+///
+/// Reports the values whose encoded length exceeds the variable length,
+/// i.e. the values that are truncated when written, with one issue per
+/// variable. Trailing blanks are not significant in XPT, so they are
+/// ignored. `allowed` marks the truncations as allowed, which makes them
+/// warnings.
+#[must_use]
+pub(crate) fn validate_data(dataset: &Dataset, plan: &DatasetSchema, allowed: bool) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for var in plan.variables.iter().filter(|v| v.xpt_type.is_character()) {
+        let truncated = character_values(dataset, var).filter_map(|(row, value)| {
+            let value = value.trim_end();
+            let actual = plan.byte_len(value);
+            (actual > var.length).then(|| Issue::ValueTruncated {
+                variable: var.name.clone(),
+                row,
+                count: 1,
+                actual,
+                truncated: plan.byte_len(truncate_encoded(value, var.length, plan.text_mode)),
+                allowed,
+            })
+        });
+        merge_value_issues(&mut issues, truncated, 0);
+    }
+
+    issues
+}
+
+/// Adds value issues to `issues`, keeping one issue per kind and variable.
+///
+/// Rows are offset by `row_offset`, for data checked in chunks. An issue
+/// for a variable that already has one adds to its count; the first row is
+/// kept, and a truncation keeps the lengths of the longest value.
+pub(crate) fn merge_value_issues(
+    issues: &mut Vec<Issue>,
+    new: impl IntoIterator<Item = Issue>,
+    row_offset: usize,
+) {
+    for mut issue in new {
+        if let Issue::NonAsciiValue { row, .. }
+        | Issue::ValueTruncated { row, .. }
+        | Issue::ValueNotInCodelist { row, .. } = &mut issue
+        {
+            *row += row_offset;
+        }
+        if !issues
+            .iter_mut()
+            .any(|existing| merge_into(existing, &issue))
+        {
+            issues.push(issue);
+        }
+    }
+}
+
+/// Merges `issue` into `existing` if both are value issues of the same kind
+/// for the same variable (and codelist).
+fn merge_into(existing: &mut Issue, issue: &Issue) -> bool {
+    match (existing, issue) {
+        (
+            Issue::NonAsciiValue {
+                variable, count, ..
+            },
+            Issue::NonAsciiValue {
+                variable: other,
+                count: more,
+                ..
+            },
+        ) if variable == other => {
+            *count += more;
+            true
+        }
+        (
+            Issue::ValueTruncated {
+                variable,
+                count,
+                actual,
+                truncated,
+                ..
+            },
+            Issue::ValueTruncated {
+                variable: other,
+                count: more,
+                actual: other_actual,
+                truncated: other_truncated,
+                ..
+            },
+        ) if variable == other => {
+            *count += more;
+            if other_actual > actual {
+                *actual = *other_actual;
+                *truncated = *other_truncated;
+            }
+            true
+        }
+        (
+            Issue::ValueNotInCodelist {
+                variable,
+                count,
+                codelist,
+                ..
+            },
+            Issue::ValueNotInCodelist {
+                variable: other,
+                count: more,
+                codelist: other_codelist,
+                ..
+            },
+        ) if variable == other && codelist == other_codelist => {
+            *count += more;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the character values of a dataset can be encoded in `mode`.
+///
+/// `row_offset` is added to the row in the error message, for data checked
+/// in chunks.
+///
+/// # Errors
+///
+/// Returns an encoding error for the first value that cannot be encoded.
+pub(crate) fn check_encodable_values(
+    dataset: &Dataset,
+    mode: TextMode,
+    row_offset: usize,
+) -> Result<()> {
+    if matches!(mode, TextMode::StrictUtf8 | TextMode::LossyUtf8) {
+        return Ok(());
+    }
+    for col in dataset.columns() {
+        if let ColumnData::String(values) = col.data() {
+            for (row, value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    encode_str(value, mode).map_err(|e| {
+                        Error::encoding(format!(
+                            "variable '{}', row {}: {e}",
+                            col.name(),
+                            row + row_offset
+                        ))
+                    })?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the non-missing values of a character variable with their row
+/// indices, as they will be written.
+pub(crate) fn character_values<'a>(
+    dataset: &'a Dataset,
+    var: &VariableSpec,
+) -> impl Iterator<Item = (usize, String)> + 'a {
+    let data = dataset.column(&var.name).map(Column::data);
+    (0..dataset.nrows()).filter_map(move |row| {
+        data.and_then(|data| get_character_value(data, row).ok().flatten())
+            .map(|value| (row, value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(length: usize, mode: TextMode) -> DatasetSchema {
+        let mut plan = DatasetSchema::new("AE");
+        plan.text_mode = mode;
+        plan.variables = vec![
+            VariableSpec::character("AETERM", length).with_source_index(0),
+            VariableSpec::numeric("AESEQ").with_source_index(1),
+        ];
+        plan.recalculate_positions();
+        plan
+    }
+
+    fn dataset(values: &[Option<&str>]) -> Dataset {
+        Dataset::new(
+            "AE",
+            vec![
+                Column::new(
+                    "AETERM",
+                    ColumnData::String(values.iter().map(|v| v.map(String::from)).collect()),
+                ),
+                Column::new("AESEQ", ColumnData::F64(vec![Some(1.0); values.len()])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_value_truncated() {
+        let data = dataset(&[
+            Some("HEADACHE"),
+            None,
+            Some("NAUSEA  "),
+            Some("RASH"),
+            Some("DIZZINESS"),
+        ]);
+        let issues = validate_data(&data, &plan(6, TextMode::default()), false);
+
+        assert_eq!(
+            issues,
+            [Issue::ValueTruncated {
+                variable: "AETERM".into(),
+                row: 0,
+                count: 2,
+                actual: 9,
+                truncated: 6,
+                allowed: false,
+            }]
+        );
+        assert!(issues[0].is_error());
+        assert!(issues[0].to_string().contains(
+            "2 values of variable 'AETERM' are truncated, the first in row 0, \
+             the longest from 9 to 6 bytes"
+        ));
+
+        let issues = validate_data(&data, &plan(6, TextMode::default()), true);
+        assert!(issues[0].is_warning());
+    }
+
+    #[test]
+    fn test_value_truncated_multibyte() {
+        // "頭痛" is 6 bytes in UTF-8; 5 bytes only fit one character
+        let data = dataset(&[Some("頭痛")]);
+        let issues = validate_data(&data, &plan(5, TextMode::StrictUtf8), false);
+
+        assert_eq!(
+            issues,
+            [Issue::ValueTruncated {
+                variable: "AETERM".into(),
+                row: 0,
+                count: 1,
+                actual: 6,
+                truncated: 3,
+                allowed: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_value_issues() {
+        let data = dataset(&[Some("HEADACHE"), Some("RASH")]);
+        let mut issues = validate_data(&data, &plan(6, TextMode::default()), false);
+
+        let chunk = dataset(&[Some("DIZZINESS"), Some("NAUSEA")]);
+        let more = validate_data(&chunk, &plan(6, TextMode::default()), false);
+        merge_value_issues(&mut issues, more, 2);
+
+        assert_eq!(
+            issues,
+            [Issue::ValueTruncated {
+                variable: "AETERM".into(),
+                row: 0,
+                count: 2,
+                actual: 9,
+                truncated: 6,
+                allowed: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_encodable_values() {
+        let data = dataset(&[Some("HEADACHE"), Some("頭痛")]);
+        assert!(check_encodable_values(&data, TextMode::ShiftJis, 0).is_ok());
+
+        let err = check_encodable_values(&data, TextMode::Windows1252, 10).unwrap_err();
+        assert!(err.to_string().contains("row 11"), "{err}");
+    }
+}
@@ -0,0 +1,273 @@
+//! Validation of existing transport files.
+//!
+//! This module checks a file as written, rather than a dataset about to be
+//! written: the NAMESTR records of every member are checked like a derived
+//! schema, and the raw file is scanned for anomalies the reader tolerates.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::agency::Agency;
+use crate::config::{ReadOptions, TextMode};
+use crate::error::{Error, Result};
+use crate::schema::DatasetSchema;
+use crate::schema::plan::VariableSpec;
+use crate::xpt::v5::constants::{PAD_CHAR, RECORD_LEN};
+use crate::xpt::v5::read::{XptMemberInfo, XptReader};
+
+use super::issues::{Issue, IssueCollection};
+use super::{merge_value_issues, validate_schema};
+
+/// Number of observations read at a time for the data checks.
+const BATCH_SIZE: usize = 10_000;
+
+/// Prefix shared by all header records.
+const HEADER_PREFIX: &[u8] = b"HEADER RECORD*******";
+
+/// The [`Issue`] items found in one member of a transport file.
+///
+/// Returned by [`Xpt::validate`](crate::Xpt::validate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberIssues {
+    /// The member (dataset) name.
+    pub name: String,
+    /// The issues found in the member.
+    pub issues: Vec<Issue>,
+}
+
+impl MemberIssues {
+    /// Returns `true` if there are any [`Severity::Error`](super::Severity::Error) issues.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.issues.has_errors()
+    }
+
+    /// Returns `true` if there are any [`Severity::Warning`](super::Severity::Warning) issues.
+    #[must_use]
+    pub fn has_warnings(&self) -> bool {
+        self.issues.has_warnings()
+    }
+}
+
+/// Validates every member of the transport file at `path`, decoding names,
+/// labels and character values with `mode`.
+///
+/// The rules on the file name apply to files with a single member only.
+pub(crate) fn validate_file(
+    path: &Path,
+    agency: Option<Agency>,
+    mode: TextMode,
+) -> Result<Vec<MemberIssues>> {
+    let file = File::open(path).map_err(Error::Io)?;
+    let mut reader = XptReader::with_text_mode(file, mode)?;
+    let info = reader.find_members()?.clone();
+    let file_size = reader.get_mut().seek(SeekFrom::End(0)).map_err(Error::Io)?;
+    let single = info.members.len() == 1;
+
+    let mut results = Vec::with_capacity(info.members.len());
+    for member in &info.members {
+        let schema = member_schema(member, mode);
+
+        let mut issues = validate_schema(&schema, info.version);
+        issues.extend(validate_positions(member));
+        issues.extend(scan_observations(reader.get_mut(), member)?);
+
+        if let Some(agency) = agency {
+            issues.extend(agency.validate(&schema));
+            issues.extend(
+                agency
+                    .validate_path(&schema, path, file_size as usize)
+                    .into_iter()
+                    .filter(|i| single || !matches!(i, Issue::DatasetNameFileStemMismatch { .. })),
+            );
+        }
+
+        // Observations can only be decoded if the variables are laid out as
+        // the reader expects
+        let readable = !issues.iter().any(|i| {
+            matches!(
+                i,
+                Issue::NumericWrongLength { .. } | Issue::PositionInconsistent { .. }
+            )
+        });
+        if readable {
+            issues.extend(validate_observations(&mut reader, member, &schema, agency)?);
+        }
+
+        results.push(MemberIssues {
+            name: member.name.clone(),
+            issues,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Creates the schema described by the NAMESTR records of a member.
+fn member_schema(member: &XptMemberInfo, mode: TextMode) -> DatasetSchema {
+    let mut schema = DatasetSchema::new(member.name.as_str()).with_label(member.label.clone());
+    schema.text_mode = mode;
+    schema.variables = member
+        .variables
+        .iter()
+        .enumerate()
+        .map(|(i, var)| {
+            let mut spec = VariableSpec::new(var.nname.clone(), var.xpt_type(), var.length())
+                .with_label(var.nlabel.as_str())
+                .with_source_index(i);
+            spec.position = var.position();
+            spec
+        })
+        .collect();
+    schema.row_len = member.row_len;
+    schema
+}
+
+/// Checks that every variable starts where the preceding variables end.
+fn validate_positions(member: &XptMemberInfo) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut expected = 0;
+
+    for var in &member.variables {
+        if var.position() != expected {
+            issues.push(Issue::PositionInconsistent {
+                variable: var.nname.clone(),
+                recorded: var.position(),
+                expected,
+            });
+        }
+        expected += var.length();
+    }
+
+    issues
+}
+
+/// Scans the observation data of a member for header records, incomplete
+/// records and padding that is not blank.
+fn scan_observations<R: Read + Seek>(reader: &mut R, member: &XptMemberInfo) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let dataset = || member.name.clone();
+
+    reader
+        .seek(SeekFrom::Start(member.obs_offset))
+        .map_err(Error::Io)?;
+    let mut data = reader.by_ref().take(member.obs_len);
+    let mut record = [0u8; RECORD_LEN];
+    let mut offset = member.obs_offset;
+    for _ in 0..member.obs_len / RECORD_LEN as u64 {
+        data.read_exact(&mut record).map_err(Error::Io)?;
+        if record.starts_with(HEADER_PREFIX) {
+            issues.push(Issue::UnknownRecord {
+                dataset: dataset(),
+                offset,
+            });
+        }
+        offset += RECORD_LEN as u64;
+    }
+
+    let incomplete = (member.obs_len % RECORD_LEN as u64) as usize;
+    if incomplete > 0 {
+        issues.push(Issue::IncompleteRecord {
+            dataset: dataset(),
+            len: incomplete,
+        });
+    }
+
+    if let Some(offset) = find_non_blank_padding(reader, member)? {
+        issues.push(Issue::NonBlankPadding {
+            dataset: dataset(),
+            offset,
+        });
+    }
+
+    Ok(issues)
+}
+
+/// Returns the offset of the first byte of padding that is not blank.
+///
+/// Everything after the last observation is padding.
+fn find_non_blank_padding<R: Read + Seek>(
+    reader: &mut R,
+    member: &XptMemberInfo,
+) -> Result<Option<u64>> {
+    if member.row_len == 0 {
+        return Ok(None);
+    }
+
+    let data_len = (member.obs_count * member.row_len) as u64;
+    let offset = member.obs_offset + data_len;
+    reader.seek(SeekFrom::Start(offset)).map_err(Error::Io)?;
+    let mut padding = Vec::new();
+    reader
+        .by_ref()
+        .take(member.obs_len - data_len)
+        .read_to_end(&mut padding)
+        .map_err(Error::Io)?;
+
+    Ok(padding
+        .iter()
+        .position(|&b| b != PAD_CHAR)
+        .map(|pos| offset + pos as u64))
+}
+
+/// Reads the observations of a member one batch at a time, checking the
+/// character values against the agency's requirements if an agency is given.
+///
+/// Values are not checked against their variable lengths: a value read from
+/// the file always fits, and a decoded value can only be longer when
+/// invalid bytes were replaced.
+fn validate_observations<R: Read + Seek>(
+    reader: &mut XptReader<R>,
+    member: &XptMemberInfo,
+    schema: &DatasetSchema,
+    agency: Option<Agency>,
+) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let mut rows_read = 0;
+
+    let options = ReadOptions::default().with_text_mode(schema.text_mode);
+    reader.for_each_batch(member, &options, BATCH_SIZE, |batch| {
+        if let Some(agency) = agency {
+            merge_value_issues(&mut issues, agency.validate_data(&batch, schema), rows_read);
+        }
+        rows_read += batch.nrows();
+        Ok(())
+    })?;
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xpt::v5::namestr::NamestrV5;
+
+    #[test]
+    fn test_validate_positions() {
+        let var = |name: &str, nlng, npos| NamestrV5 {
+            nname: name.into(),
+            nlng,
+            npos,
+            ..NamestrV5::default()
+        };
+        let member = XptMemberInfo {
+            name: "AE".into(),
+            label: None,
+            variables: vec![var("AESEQ", 8, 0), var("AESTDY", 8, 4)],
+            obs_offset: 0,
+            obs_len: 0,
+            obs_count: 0,
+            row_len: 16,
+        };
+
+        assert_eq!(
+            validate_positions(&member),
+            [Issue::PositionInconsistent {
+                variable: "AESTDY".into(),
+                recorded: 4,
+                expected: 8,
+            }]
+        );
+    }
+}
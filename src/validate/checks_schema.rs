@@ -0,0 +1,324 @@
+//! XPT structural validation.
+//!
+//! This module provides the structural checks of the XPT format, applied with
+//! the limits of the [`XptVersion`] being written.
+
+use crate::schema::DatasetSchema;
+use crate::xpt::XptVersion;
+
+use super::issues::Issue;
+
+/// XPT constraints shared by all versions.
+pub mod constraints {
+    /// Full length for numeric variables.
+    pub const NUMERIC_LENGTH: usize = 8;
+    /// Minimum length for numeric variables.
+    pub const MIN_NUMERIC_LENGTH: usize = 3;
+    /// Minimum length for character variables.
+    pub const MIN_CHARACTER_LENGTH: usize = 1;
+}
+
+/// The byte limits of an XPT version.
+struct Limits {
+    /// Maximum bytes for the dataset name.
+    dataset_name: usize,
+    /// Maximum bytes for the dataset label (the member descriptor field is
+    /// 40 bytes in every version).
+    dataset_label: usize,
+    /// Maximum bytes for a variable name.
+    variable_name: usize,
+    /// Maximum bytes for a variable label.
+    variable_label: usize,
+    /// Maximum length for character variables.
+    character_length: usize,
+}
+
+impl Limits {
+    const fn of(version: XptVersion) -> Self {
+        match version {
+            XptVersion::V5 => Self {
+                dataset_name: 8,
+                dataset_label: 40,
+                variable_name: 8,
+                variable_label: 40,
+                character_length: 200,
+            },
+            // Character lengths must fit the 2-byte NAMESTR length field
+            XptVersion::V8 => Self {
+                dataset_name: 32,
+                dataset_label: 40,
+                variable_name: 32,
+                variable_label: 256,
+                character_length: 32767,
+            },
+        }
+    }
+}
+
+/// Validates a schema plan against the structural requirements of `version`.
+///
+/// Returns a list of issues found during validation.
+#[must_use]
+pub(crate) fn validate_schema(plan: &DatasetSchema, version: XptVersion) -> Vec<Issue> {
+    let limits = Limits::of(version);
+    let mut issues = Vec::new();
+
+    // Check dataset name length
+    if plan.byte_len(&plan.domain_code) > limits.dataset_name {
+        issues.push(Issue::DatasetNameTooLong {
+            dataset: plan.domain_code.clone(),
+            max: limits.dataset_name,
+            actual: plan.byte_len(&plan.domain_code),
+        });
+    }
+
+    // Check dataset label length
+    if let Some(ref label) = plan.dataset_label {
+        if plan.byte_len(label) > limits.dataset_label {
+            issues.push(Issue::DatasetLabelTooLong {
+                dataset: plan.domain_code.clone(),
+                max: limits.dataset_label,
+                actual: plan.byte_len(label),
+            });
+        }
+    } else {
+        // Missing dataset label - warning per Pinnacle 21 SD0063A
+        issues.push(Issue::MissingDatasetLabel {
+            dataset: plan.domain_code.clone(),
+        });
+    }
+
+    // Check each variable
+    for var in &plan.variables {
+        // Variable name length
+        if plan.byte_len(&var.name) > limits.variable_name {
+            issues.push(Issue::VariableNameTooLong {
+                variable: var.name.clone(),
+                max: limits.variable_name,
+                actual: plan.byte_len(&var.name),
+            });
+        }
+
+        // Variable label length and missing label check
+        if var.label.is_empty() {
+            // Missing variable label - warning per Pinnacle 21 SD0063
+            issues.push(Issue::MissingVariableLabel {
+                variable: var.name.clone(),
+            });
+        } else if plan.byte_len(&var.label) > limits.variable_label {
+            issues.push(Issue::VariableLabelTooLong {
+                variable: var.name.clone(),
+                max: limits.variable_label,
+                actual: plan.byte_len(&var.label),
+            });
+        }
+
+        // Numeric length must be 3-8; shorter than 8 loses precision
+        if var.xpt_type.is_numeric() {
+            if !(constraints::MIN_NUMERIC_LENGTH..=constraints::NUMERIC_LENGTH)
+                .contains(&var.length)
+            {
+                issues.push(Issue::NumericWrongLength {
+                    variable: var.name.clone(),
+                    expected: constraints::NUMERIC_LENGTH,
+                    actual: var.length,
+                });
+            } else if var.length < constraints::NUMERIC_LENGTH {
+                issues.push(Issue::NumericPrecisionReduced {
+                    variable: var.name.clone(),
+                    length: var.length,
+                });
+            }
+        }
+
+        // Character length must be >= 1
+        if var.xpt_type.is_character() && var.length < constraints::MIN_CHARACTER_LENGTH {
+            issues.push(Issue::CharacterLengthTooShort {
+                variable: var.name.clone(),
+                min: constraints::MIN_CHARACTER_LENGTH,
+                actual: var.length,
+            });
+        }
+
+        // Character length must not exceed the version's maximum
+        if var.xpt_type.is_character() && var.length > limits.character_length {
+            issues.push(Issue::CharacterLengthTooLong {
+                variable: var.name.clone(),
+                max: limits.character_length,
+                actual: var.length,
+            });
+        }
+    }
+
+    // Verify row_len consistency
+    let expected_row_len: usize = plan.variables.iter().map(|v| v.length).sum();
+    if plan.row_len != expected_row_len {
+        issues.push(Issue::RowLenInconsistent {
+            recorded: plan.row_len,
+            computed: expected_row_len,
+        });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::plan::VariableSpec;
+
+    #[test]
+    fn test_valid_schema_with_labels() {
+        let mut plan = DatasetSchema::new("AE").with_label(Some("Adverse Events".into()));
+        plan.variables = vec![
+            VariableSpec::numeric("AESEQ").with_label("Sequence Number"),
+            VariableSpec::character("USUBJID", 20).with_label("Unique Subject Identifier"),
+        ];
+        plan.recalculate_positions();
+
+        let issues = validate_schema(&plan, XptVersion::V5);
+        // Should have no errors or warnings when labels are provided
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_missing_labels_warnings() {
+        let mut plan = DatasetSchema::new("AE");
+        plan.variables = vec![
+            VariableSpec::numeric("AESEQ"),
+            VariableSpec::character("USUBJID", 20),
+        ];
+        plan.recalculate_positions();
+
+        let issues = validate_schema(&plan, XptVersion::V5);
+        // Should have warnings for missing dataset label and variable labels
+        assert!(!issues.is_empty());
+
+        // All issues should be warnings, not errors
+        for issue in &issues {
+            assert!(
+                issue.is_warning(),
+                "Expected warning, got error: {:?}",
+                issue
+            );
+        }
+
+        // Check for specific warning types
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::MissingDatasetLabel { .. }))
+        );
+        assert!(
+            issues.iter().any(
+                |i| matches!(i, Issue::MissingVariableLabel { variable } if variable == "AESEQ")
+            )
+        );
+        assert!(issues.iter().any(
+            |i| matches!(i, Issue::MissingVariableLabel { variable } if variable == "USUBJID")
+        ));
+    }
+
+    #[test]
+    fn test_name_too_long() {
+        let mut plan = DatasetSchema::new("TOOLONGNAME");
+        plan.variables = vec![VariableSpec::numeric("AESEQ")];
+        plan.recalculate_positions();
+
+        let issues = validate_schema(&plan, XptVersion::V5);
+        assert!(!issues.is_empty());
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::DatasetNameTooLong { .. }))
+        );
+    }
+
+    #[test]
+    fn test_numeric_wrong_length() {
+        let numeric = |length| {
+            VariableSpec::new("AESEQ".into(), crate::metadata::XptVarType::Numeric, length)
+                .with_label("Sequence Number")
+        };
+        let mut plan = DatasetSchema::new("AE").with_label(Some("Adverse Events".into()));
+
+        for length in [2, 9] {
+            plan.variables = vec![numeric(length)];
+            plan.recalculate_positions();
+            assert_eq!(
+                validate_schema(&plan, XptVersion::V5),
+                [Issue::NumericWrongLength {
+                    variable: "AESEQ".into(),
+                    expected: 8,
+                    actual: length,
+                }]
+            );
+        }
+
+        // Shorter numerics are allowed, with a warning
+        plan.variables = vec![numeric(4)];
+        plan.recalculate_positions();
+        let issues = validate_schema(&plan, XptVersion::V5);
+        assert_eq!(
+            issues,
+            [Issue::NumericPrecisionReduced {
+                variable: "AESEQ".into(),
+                length: 4,
+            }]
+        );
+        assert!(issues[0].is_warning());
+        assert!(issues[0].to_string().contains("integers above 2097152"));
+    }
+
+    #[test]
+    fn test_v8_long_names_and_labels_accepted() {
+        let mut plan =
+            DatasetSchema::new("LONG_DATASET_NAME").with_label(Some("Archive Dataset".into()));
+        plan.variables = vec![
+            VariableSpec::numeric("SUBJECT_IDENTIFIER").with_label("x".repeat(200)),
+            VariableSpec::character("FREE_TEXT_COMMENT", 1000).with_label("Comment"),
+        ];
+        plan.recalculate_positions();
+
+        let issues = validate_schema(&plan, XptVersion::V8);
+        assert!(issues.is_empty(), "unexpected issues: {issues:?}");
+        assert!(!validate_schema(&plan, XptVersion::V5).is_empty());
+    }
+
+    #[test]
+    fn test_v8_limits_exceeded() {
+        let mut plan = DatasetSchema::new("D".repeat(33)).with_label(Some("L".repeat(41)));
+        plan.variables = vec![
+            VariableSpec::numeric("V".repeat(33)).with_label("x".repeat(257)),
+            VariableSpec::character("TEXT", 40000).with_label("Text"),
+        ];
+        plan.recalculate_positions();
+
+        let issues = validate_schema(&plan, XptVersion::V8);
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::DatasetNameTooLong { max, .. } if *max == 32))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::DatasetLabelTooLong { max, .. } if *max == 40))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::VariableNameTooLong { max, .. } if *max == 32))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::VariableLabelTooLong { max, .. } if *max == 256))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| matches!(i, Issue::CharacterLengthTooLong { max, .. } if *max == 32767))
+        );
+    }
+}
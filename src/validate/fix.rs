@@ -0,0 +1,461 @@
+//! Automatic remediation.
+//!
+//! This module repairs common problems when auto-fix is enabled with
+//! [`XptWriterBuilder::auto_fix`](crate::XptWriterBuilder::auto_fix). Every
+//! change is reported as an [`Issue::Fixed`] so that the issues double as an
+//! audit trail.
+//!
+//! Fixes are applied in two passes: [`fix_dataset`] repairs the data before
+//! the schema is derived, and [`fix_schema`] repairs the derived schema.
+
+use crate::agency::Agency;
+use crate::dataset::{ColumnData, Dataset};
+use crate::schema::DatasetSchema;
+use crate::xpt::XptVersion;
+use crate::xpt::v5::encoding::truncate_encoded;
+
+use super::checks_data::character_values;
+use super::issues::{Fix, Issue};
+
+/// Repairs the columns of a dataset.
+///
+/// - Variable names are uppercased and sanitized to the naming rules
+///   (letters, digits and underscores, not starting with a digit, within the
+///   name length limit). A name is left alone if the fixed name is taken.
+/// - Trailing whitespace is trimmed from character values.
+/// - Non-ASCII characters in character values are transliterated if the
+///   agency requires ASCII values. A value with a character that has no
+///   ASCII equivalent is left alone, to be reported as an error.
+pub(crate) fn fix_dataset(
+    dataset: &mut Dataset,
+    agency: Option<Agency>,
+    version: XptVersion,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let max_name = max_name_len(agency, version);
+    let ascii = agency.is_some_and(Agency::requires_ascii_values);
+
+    let mut names: Vec<String> = dataset.column_names().map(String::from).collect();
+    for (i, col) in dataset.iter_mut().enumerate() {
+        let fixed = sanitize_name(col.name(), max_name, true);
+        if fixed != col.name() && !fixed.is_empty() && !names.contains(&fixed) {
+            issues.push(Issue::Fixed {
+                name: fixed.clone(),
+                is_dataset: false,
+                fix: Fix::Renamed {
+                    from: col.name().to_string(),
+                },
+            });
+            col.set_name(fixed.clone());
+            names[i] = fixed;
+        }
+
+        let name = col.name().to_string();
+        let ColumnData::String(values) = col.data_mut() else {
+            continue;
+        };
+        let (mut trimmed, mut transliterated) = (0, 0);
+        for value in values.iter_mut().flatten() {
+            let len = value.trim_end().len();
+            if len < value.len() {
+                value.truncate(len);
+                trimmed += 1;
+            }
+            if ascii
+                && !value.is_ascii()
+                && let Some(fixed) = transliterate(value)
+            {
+                *value = fixed;
+                transliterated += 1;
+            }
+        }
+        if trimmed > 0 {
+            issues.push(Issue::Fixed {
+                name: name.clone(),
+                is_dataset: false,
+                fix: Fix::TrailingWhitespaceTrimmed { values: trimmed },
+            });
+        }
+        if transliterated > 0 {
+            issues.push(Issue::Fixed {
+                name,
+                is_dataset: false,
+                fix: Fix::ValuesTransliterated {
+                    values: transliterated,
+                },
+            });
+        }
+    }
+
+    issues
+}
+
+/// Repairs a derived schema.
+///
+/// - The dataset name is uppercased and sanitized to the naming rules.
+/// - Non-ASCII characters in labels are transliterated if the agency
+///   requires ASCII labels. A label with a character that has no ASCII
+///   equivalent is left alone, to be reported as an error.
+/// - Labels longer than the limit are shortened at a word boundary.
+/// - Character lengths are shrunk to the longest value, unless the length
+///   comes from variable metadata or Define-XML.
+pub(crate) fn fix_schema(
+    dataset: &Dataset,
+    plan: &mut DatasetSchema,
+    agency: Option<Agency>,
+    version: XptVersion,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let ascii = agency.is_some_and(Agency::requires_ascii_labels);
+    let max_label = agency.map_or(version.max_label_len(), |a| {
+        a.max_label_bytes().min(version.max_label_len())
+    });
+
+    let fixed = sanitize_name(&plan.domain_code, max_name_len(agency, version), false);
+    if fixed != plan.domain_code && !fixed.is_empty() {
+        issues.push(Issue::Fixed {
+            name: fixed.clone(),
+            is_dataset: true,
+            fix: Fix::Renamed {
+                from: std::mem::replace(&mut plan.domain_code, fixed),
+            },
+        });
+    }
+
+    if let Some(mut label) = plan.dataset_label.take() {
+        let name = plan.domain_code.clone();
+        // The member header label field is 40 bytes in every version
+        issues.extend(fix_label(&mut label, &name, true, ascii, 40, plan));
+        plan.dataset_label = Some(label);
+    }
+
+    for i in 0..plan.variables.len() {
+        let mut label = std::mem::take(&mut plan.variables[i].label);
+        let name = plan.variables[i].name.clone();
+        issues.extend(fix_label(&mut label, &name, false, ascii, max_label, plan));
+        plan.variables[i].label = label;
+
+        let var = &plan.variables[i];
+        if var.xpt_type.is_character() && !var.length_specified {
+            let longest = character_values(dataset, var)
+                .map(|(_, value)| plan.byte_len(value.trim_end()))
+                .max()
+                .unwrap_or(0)
+                .max(1);
+            if longest < var.length {
+                issues.push(Issue::Fixed {
+                    name,
+                    is_dataset: false,
+                    fix: Fix::LengthShrunk {
+                        from: var.length,
+                        to: longest,
+                    },
+                });
+                plan.variables[i].length = longest;
+            }
+        }
+    }
+
+    plan.recalculate_positions();
+    issues
+}
+
+/// Transliterates and shortens a label, returning the fixes made.
+fn fix_label(
+    label: &mut String,
+    name: &str,
+    is_dataset: bool,
+    ascii: bool,
+    max: usize,
+    plan: &DatasetSchema,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let fixed = |fix| Issue::Fixed {
+        name: name.to_string(),
+        is_dataset,
+        fix,
+    };
+
+    if ascii
+        && !label.is_ascii()
+        && let Some(ascii_label) = transliterate(label)
+    {
+        let from = std::mem::replace(label, ascii_label);
+        issues.push(fixed(Fix::LabelTransliterated { from }));
+    }
+    if plan.byte_len(label) > max {
+        let shortened = shorten_at_word(label, max, plan).to_string();
+        let from = std::mem::replace(label, shortened);
+        issues.push(fixed(Fix::LabelShortened { from }));
+    }
+
+    issues
+}
+
+/// Returns the name length limit for the agency and version.
+fn max_name_len(agency: Option<Agency>, version: XptVersion) -> usize {
+    let max = version.max_variable_name_len();
+    agency.map_or(max, |a| a.max_variable_name_bytes().min(max))
+}
+
+/// Uppercases a name and removes characters that SAS names cannot contain.
+///
+/// Spaces, hyphens and periods become underscores for variable names;
+/// dataset names keep letters and digits only. A leading digit is prefixed
+/// with an underscore (variables) or removed (datasets). A name with a
+/// character that has no ASCII equivalent gives an empty string.
+fn sanitize_name(name: &str, max: usize, is_variable: bool) -> String {
+    let Some(ascii) = transliterate(name) else {
+        return String::new();
+    };
+    let mut fixed: String = ascii
+        .chars()
+        .filter_map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => Some(c.to_ascii_uppercase()),
+            '_' | ' ' | '-' | '.' if is_variable => Some('_'),
+            _ => None,
+        })
+        .collect();
+
+    if fixed.starts_with(|c: char| c.is_ascii_digit()) {
+        if is_variable {
+            fixed.insert(0, '_');
+        } else {
+            fixed = fixed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .to_string();
+        }
+    }
+    fixed.truncate(max);
+    fixed
+}
+
+/// Shortens text to fit `max` bytes, cutting at the last word boundary.
+///
+/// Text without a word boundary within the limit is cut at the limit.
+fn shorten_at_word<'a>(text: &'a str, max: usize, plan: &DatasetSchema) -> &'a str {
+    let cut = truncate_encoded(text, max, plan.text_mode);
+    let at_boundary = text[cut.len()..].starts_with(char::is_whitespace);
+    if !at_boundary && let Some(space) = cut.rfind(char::is_whitespace) {
+        return cut[..space].trim_end();
+    }
+    cut.trim_end()
+}
+
+/// Replaces non-ASCII characters with ASCII equivalents.
+///
+/// Accented Latin letters lose their accents and typographic punctuation and
+/// common symbols are spelled out. Returns `None` if a character has no
+/// equivalent.
+pub(crate) fn transliterate(s: &str) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            out.push_str(ascii_equivalent(c)?);
+        }
+    }
+    Some(out)
+}
+
+/// Returns the ASCII equivalent of a character, if it has one.
+fn ascii_equivalent(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => "A",
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' => "C",
+        'ç' => "c",
+        'È' | 'É' | 'Ê' | 'Ë' => "E",
+        'è' | 'é' | 'ê' | 'ë' => "e",
+        'Ì' | 'Í' | 'Î' | 'Ï' => "I",
+        'ì' | 'í' | 'î' | 'ï' => "i",
+        'Ð' => "D",
+        'ð' => "d",
+        'Ñ' => "N",
+        'ñ' => "n",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => "O",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ù' | 'Ú' | 'Û' | 'Ü' => "U",
+        'ù' | 'ú' | 'û' | 'ü' | 'µ' | 'μ' => "u",
+        'Ý' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Þ' => "TH",
+        'þ' => "th",
+        'ß' => "ss",
+        '\u{a0}' => " ",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '•' | '·' => "*",
+        '°' => "deg",
+        '±' => "+/-",
+        '×' => "x",
+        '÷' => "/",
+        '≤' => "<=",
+        '≥' => ">=",
+        '©' => "(C)",
+        '®' => "(R)",
+        '™' => "(TM)",
+        '¹' => "1",
+        '²' => "2",
+        '³' => "3",
+        '¼' => "1/4",
+        '½' => "1/2",
+        '¾' => "3/4",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::Column;
+    use crate::schema::plan::VariableSpec;
+
+    #[test]
+    fn test_fix_dataset() {
+        let mut dataset = Dataset::new(
+            "AE",
+            vec![
+                Column::new(
+                    "ae term",
+                    ColumnData::String(vec![Some("Céphalée  ".into()), Some("RASH".into())]),
+                ),
+                Column::new("AESEQ", ColumnData::F64(vec![Some(1.0), Some(2.0)])),
+            ],
+        )
+        .unwrap();
+
+        let issues = fix_dataset(&mut dataset, Some(Agency::FDA), XptVersion::V5);
+        let fixed = |fix| Issue::Fixed {
+            name: "AE_TERM".into(),
+            is_dataset: false,
+            fix,
+        };
+        assert_eq!(
+            issues,
+            [
+                fixed(Fix::Renamed {
+                    from: "ae term".into()
+                }),
+                fixed(Fix::TrailingWhitespaceTrimmed { values: 1 }),
+                fixed(Fix::ValuesTransliterated { values: 1 }),
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity() == crate::Severity::Info));
+        assert_eq!(
+            dataset.column("AE_TERM").unwrap().data(),
+            &ColumnData::String(vec![Some("Cephalee".into()), Some("RASH".into())])
+        );
+
+        // Without an ASCII requirement, values are kept
+        let mut dataset = Dataset::new(
+            "AE",
+            vec![Column::new(
+                "AETERM",
+                ColumnData::String(vec![Some("頭痛".into())]),
+            )],
+        )
+        .unwrap();
+        assert!(fix_dataset(&mut dataset, Some(Agency::PMDA), XptVersion::V5).is_empty());
+
+        // Values without an ASCII equivalent are left for validation to reject
+        assert!(fix_dataset(&mut dataset, Some(Agency::FDA), XptVersion::V5).is_empty());
+        let mut plan = DatasetSchema::new("AE");
+        plan.variables = vec![VariableSpec::character("AETERM", 6).with_source_index(0)];
+        plan.recalculate_positions();
+        assert_eq!(
+            Agency::FDA.validate_data(&dataset, &plan),
+            [Issue::NonAsciiValue {
+                variable: "AETERM".into(),
+                row: 0,
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fix_schema() {
+        let dataset = Dataset::new(
+            "AE",
+            vec![Column::new(
+                "AETERM",
+                ColumnData::String(vec![Some("HEADACHE".into()), None]),
+            )],
+        )
+        .unwrap();
+        let mut plan = DatasetSchema::new("ae").with_label(Some("Événements indésirables".into()));
+        plan.variables = vec![
+            VariableSpec::character("AETERM", 200)
+                .with_label("Reported Term for the Adverse Event (Verbatim Text)")
+                .with_source_index(0),
+        ];
+        plan.recalculate_positions();
+
+        let issues = fix_schema(&dataset, &mut plan, Some(Agency::FDA), XptVersion::V5);
+
+        assert_eq!(plan.domain_code, "AE");
+        assert_eq!(
+            plan.dataset_label.as_deref(),
+            Some("Evenements indesirables")
+        );
+        assert_eq!(
+            plan.variables[0].label,
+            "Reported Term for the Adverse Event"
+        );
+        assert_eq!(plan.variables[0].length, 8);
+        assert_eq!(plan.row_len, 8);
+        assert_eq!(
+            issues.last(),
+            Some(&Issue::Fixed {
+                name: "AETERM".into(),
+                is_dataset: false,
+                fix: Fix::LengthShrunk { from: 200, to: 8 },
+            })
+        );
+        assert_eq!(issues.len(), 4);
+
+        // Lengths from variable metadata are kept
+        let mut plan = DatasetSchema::new("AE");
+        let mut var = VariableSpec::character("AETERM", 200).with_source_index(0);
+        var.length_specified = true;
+        plan.variables = vec![var];
+        plan.recalculate_positions();
+        assert!(fix_schema(&dataset, &mut plan, None, XptVersion::V5).is_empty());
+        assert_eq!(plan.variables[0].length, 200);
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("aeterm", 8, true), "AETERM");
+        assert_eq!(sanitize_name("1st-dose", 8, true), "_1ST_DOS");
+        assert_eq!(sanitize_name("größe", 8, true), "GROSSE");
+        assert_eq!(sanitize_name("ae_2", 8, false), "AE2");
+        assert_eq!(sanitize_name("2ae", 8, false), "AE");
+    }
+
+    #[test]
+    fn test_shorten_at_word() {
+        let plan = DatasetSchema::new("AE");
+        assert_eq!(
+            shorten_at_word("Study Day of Start", 12, &plan),
+            "Study Day of"
+        );
+        assert_eq!(
+            shorten_at_word("Study Day of Start", 13, &plan),
+            "Study Day of"
+        );
+        assert_eq!(shorten_at_word("Unbreakable", 6, &plan), "Unbrea");
+        assert_eq!(
+            transliterate("5 µg ± 2°C — “ok”").as_deref(),
+            Some("5 ug +/- 2degC - \"ok\"")
+        );
+        assert_eq!(transliterate("Céphalée 頭痛"), None);
+    }
+}
@@ -6,6 +6,9 @@
 use std::fmt;
 use std::path::PathBuf;
 
+use super::checks_schema::constraints::MIN_NUMERIC_LENGTH;
+use crate::metadata::XptVarType;
+
 /// A validation issue found during XPT generation or reading.
 ///
 /// Each variant represents a specific type of issue with relevant context data.
@@ -78,16 +81,27 @@ pub enum Issue {
         actual: usize,
     },
 
-    /// Numeric variable has incorrect length (must be 8).
+    /// Numeric variable has incorrect length (must be 3 to 8).
     NumericWrongLength {
         /// The variable name.
         variable: String,
-        /// Expected length (8).
+        /// Full length (8).
         expected: usize,
         /// Actual length.
         actual: usize,
     },
 
+    /// Numeric variable is shorter than 8 bytes and loses precision.
+    ///
+    /// The IBM mantissa is truncated to the variable length, so only
+    /// integers up to a limit (8,192 for 3 bytes) are stored exactly.
+    NumericPrecisionReduced {
+        /// The variable name.
+        variable: String,
+        /// The variable length.
+        length: usize,
+    },
+
     /// Character variable length is below minimum.
     CharacterLengthTooShort {
         /// The variable name.
@@ -116,6 +130,44 @@ pub enum Issue {
         computed: usize,
     },
 
+    // =========================================================================
+    // Transport File Issues
+    // =========================================================================
+    /// Variable position (`npos`) does not follow the preceding variables.
+    PositionInconsistent {
+        /// The variable name.
+        variable: String,
+        /// Position recorded in the NAMESTR record.
+        recorded: usize,
+        /// Position computed from the preceding variable lengths.
+        expected: usize,
+    },
+
+    /// Header record that is not part of the member structure, found where
+    /// observation data is expected.
+    UnknownRecord {
+        /// The dataset name.
+        dataset: String,
+        /// Byte offset of the record in the file.
+        offset: u64,
+    },
+
+    /// Padding after the last observation is not blank.
+    NonBlankPadding {
+        /// The dataset name.
+        dataset: String,
+        /// Byte offset of the padding in the file.
+        offset: u64,
+    },
+
+    /// Observation data ends with an incomplete 80-byte record.
+    IncompleteRecord {
+        /// The dataset name.
+        dataset: String,
+        /// Length of the incomplete record in bytes.
+        len: usize,
+    },
+
     // =========================================================================
     // Agency-Specific Issues
     // =========================================================================
@@ -215,6 +267,18 @@ pub enum Issue {
         max: usize,
     },
 
+    /// Estimated file size exceeds agency limit.
+    FileSizeExceeded {
+        /// The dataset name.
+        dataset: String,
+        /// Agency name.
+        agency: &'static str,
+        /// Estimated size of the largest file in bytes.
+        size: usize,
+        /// Maximum file size in bytes.
+        max: usize,
+    },
+
     /// Warning when a label contains multi-byte characters and is near the byte limit.
     ///
     /// This warning helps users catch potential truncation issues when using
@@ -264,6 +328,183 @@ pub enum Issue {
         /// Error message explaining why parsing failed.
         reason: String,
     },
+
+    // =========================================================================
+    // Data Issues
+    // =========================================================================
+    /// Character values contain non-ASCII characters.
+    ///
+    /// Reported once per variable.
+    NonAsciiValue {
+        /// The variable name.
+        variable: String,
+        /// The index of the first affected row (0-based).
+        row: usize,
+        /// Number of affected values.
+        count: usize,
+    },
+
+    /// Character values are longer than the variable length and are truncated.
+    ///
+    /// Reported once per variable, with the lengths of the longest value.
+    /// This is an error unless truncation is allowed with
+    /// [`XptWriterBuilder::allow_truncation`](crate::XptWriterBuilder::allow_truncation).
+    ValueTruncated {
+        /// The variable name.
+        variable: String,
+        /// The index of the first affected row (0-based).
+        row: usize,
+        /// Number of affected values.
+        count: usize,
+        /// Byte length of the longest value.
+        actual: usize,
+        /// Byte length of the longest value after truncation.
+        truncated: usize,
+        /// Whether truncation is allowed.
+        allowed: bool,
+    },
+
+    /// Label is longer than the XPT version allows and is truncated.
+    ///
+    /// This is an error unless truncation is allowed with
+    /// [`XptWriterBuilder::allow_truncation`](crate::XptWriterBuilder::allow_truncation).
+    LabelTruncated {
+        /// The name (dataset or variable).
+        name: String,
+        /// Whether this is a dataset (true) or variable (false).
+        is_dataset: bool,
+        /// Byte length of the label.
+        actual: usize,
+        /// Byte length of the label after truncation.
+        truncated: usize,
+        /// Whether truncation is allowed.
+        allowed: bool,
+    },
+
+    // =========================================================================
+    // Automatic Fixes
+    // =========================================================================
+    /// A problem was repaired automatically.
+    ///
+    /// Recorded for every change made by
+    /// [`XptWriterBuilder::auto_fix`](crate::XptWriterBuilder::auto_fix), so
+    /// the issues form an audit trail of what was changed.
+    Fixed {
+        /// The name (dataset or variable) after the fix.
+        name: String,
+        /// Whether this is a dataset (true) or variable (false).
+        is_dataset: bool,
+        /// The change that was made.
+        fix: Fix,
+    },
+
+    // =========================================================================
+    // Define-XML Issues
+    // =========================================================================
+    /// Dataset has no `ItemGroupDef` in the Define-XML document.
+    DatasetNotInDefine {
+        /// The dataset name.
+        dataset: String,
+    },
+
+    /// Variable in the data has no `ItemRef` in the dataset's definition.
+    VariableNotInDefine {
+        /// The variable name.
+        variable: String,
+    },
+
+    /// Variable in the dataset's definition is missing from the data.
+    ///
+    /// This is an error for mandatory variables and a warning otherwise.
+    DefineVariableMissing {
+        /// The variable name.
+        variable: String,
+        /// Whether the definition marks the variable as mandatory.
+        mandatory: bool,
+    },
+
+    /// Column data cannot be written as the Define-XML data type.
+    DefineTypeMismatch {
+        /// The variable name.
+        variable: String,
+        /// The Define-XML data type.
+        data_type: String,
+    },
+
+    /// Character values are longer than the Define-XML length.
+    DefineLengthExceeded {
+        /// The variable name.
+        variable: String,
+        /// The length in the definition.
+        length: usize,
+        /// The byte length of the longest value.
+        actual: usize,
+    },
+
+    // =========================================================================
+    // CDISC Standard Issues
+    // =========================================================================
+    /// Dataset has no variable definitions in the standard.
+    DatasetNotInStandard {
+        /// The dataset name.
+        dataset: String,
+        /// The standard name and version (e.g. `"SDTM-IG 3.4"`).
+        standard: String,
+    },
+
+    /// Required or Expected variable of the standard is missing from the data.
+    ///
+    /// This is an error for Required variables and a warning for Expected
+    /// variables.
+    StandardVariableMissing {
+        /// The variable name.
+        variable: String,
+        /// Whether the standard marks the variable as Required.
+        required: bool,
+    },
+
+    /// Variable type differs from the type in the standard.
+    StandardTypeMismatch {
+        /// The variable name.
+        variable: String,
+        /// The type in the standard.
+        expected: XptVarType,
+    },
+
+    /// Variable label differs from the label in the standard.
+    StandardLabelMismatch {
+        /// The variable name.
+        variable: String,
+        /// The variable label.
+        label: String,
+        /// The label in the standard.
+        expected: String,
+    },
+
+    /// Variable comes after a variable that follows it in the standard.
+    StandardOrderMismatch {
+        /// The variable name.
+        variable: String,
+        /// The variable it comes after.
+        after: String,
+    },
+
+    /// Character values are not terms of the variable's non-extensible
+    /// codelists.
+    ///
+    /// Reported once per variable, with the first value outside the codelists.
+    ValueNotInCodelist {
+        /// The variable name.
+        variable: String,
+        /// The index of the first affected row (0-based).
+        row: usize,
+        /// Number of affected values.
+        count: usize,
+        /// The first value outside the codelists.
+        value: String,
+        /// The submission value of the codelist (e.g. `"AESEV"`).
+        codelist: String,
+    },
 }
 
 impl Issue {
@@ -275,7 +516,24 @@ impl Issue {
             Self::CharacterValueLengthExceeded { .. }
             | Self::MultiByteLabelNearLimit { .. }
             | Self::MissingVariableLabel { .. }
-            | Self::MissingDatasetLabel { .. } => Severity::Warning,
+            | Self::MissingDatasetLabel { .. }
+            | Self::DatasetNotInDefine { .. }
+            | Self::VariableNotInDefine { .. }
+            | Self::DefineVariableMissing {
+                mandatory: false, ..
+            }
+            | Self::DatasetNotInStandard { .. }
+            | Self::StandardVariableMissing {
+                required: false, ..
+            }
+            | Self::StandardLabelMismatch { .. }
+            | Self::StandardOrderMismatch { .. }
+            | Self::ValueTruncated { allowed: true, .. }
+            | Self::LabelTruncated { allowed: true, .. }
+            | Self::NonBlankPadding { .. }
+            | Self::NumericPrecisionReduced { .. } => Severity::Warning,
+            // Information - records of changes already made
+            Self::Fixed { .. } => Severity::Info,
             // Everything else is an error
             _ => Severity::Error,
         }
@@ -293,12 +551,19 @@ impl Issue {
             | Self::NonAsciiDatasetName { dataset }
             | Self::NonAsciiDatasetLabel { dataset }
             | Self::AgencyDatasetNameTooLong { dataset, .. }
-            | Self::MissingDatasetLabel { dataset } => Some(Target::Dataset(dataset.clone())),
+            | Self::MissingDatasetLabel { dataset }
+            | Self::FileSizeExceeded { dataset, .. }
+            | Self::UnknownRecord { dataset, .. }
+            | Self::NonBlankPadding { dataset, .. }
+            | Self::IncompleteRecord { dataset, .. }
+            | Self::DatasetNotInDefine { dataset }
+            | Self::DatasetNotInStandard { dataset, .. } => Some(Target::Dataset(dataset.clone())),
 
             // Variable targets
             Self::VariableNameTooLong { variable, .. }
             | Self::VariableLabelTooLong { variable, .. }
             | Self::NumericWrongLength { variable, .. }
+            | Self::NumericPrecisionReduced { variable, .. }
             | Self::CharacterLengthTooShort { variable, .. }
             | Self::CharacterLengthTooLong { variable, .. }
             | Self::VariableNamePatternMismatch { variable, .. }
@@ -307,9 +572,19 @@ impl Issue {
             | Self::AgencyVariableNameTooLong { variable, .. }
             | Self::CharacterValueLengthExceeded { variable, .. }
             | Self::MissingVariableLabel { variable }
-            | Self::InvalidFormatSyntax { variable, .. } => {
-                Some(Target::Variable(variable.clone()))
-            }
+            | Self::InvalidFormatSyntax { variable, .. }
+            | Self::NonAsciiValue { variable, .. }
+            | Self::PositionInconsistent { variable, .. }
+            | Self::ValueTruncated { variable, .. }
+            | Self::VariableNotInDefine { variable }
+            | Self::DefineVariableMissing { variable, .. }
+            | Self::DefineTypeMismatch { variable, .. }
+            | Self::DefineLengthExceeded { variable, .. }
+            | Self::StandardVariableMissing { variable, .. }
+            | Self::StandardTypeMismatch { variable, .. }
+            | Self::StandardLabelMismatch { variable, .. }
+            | Self::StandardOrderMismatch { variable, .. }
+            | Self::ValueNotInCodelist { variable, .. } => Some(Target::Variable(variable.clone())),
 
             // Special case for label (can be either dataset or variable)
             Self::AgencyLabelTooLong {
@@ -317,6 +592,12 @@ impl Issue {
             }
             | Self::MultiByteLabelNearLimit {
                 name, is_dataset, ..
+            }
+            | Self::LabelTruncated {
+                name, is_dataset, ..
+            }
+            | Self::Fixed {
+                name, is_dataset, ..
             } => {
                 if *is_dataset {
                     Some(Target::Dataset(name.clone()))
@@ -409,8 +690,19 @@ impl fmt::Display for Issue {
             } => {
                 write!(
                     f,
-                    "numeric variable '{}' must have length {} (has {})",
-                    variable, expected, actual
+                    "numeric variable '{}' must have length {} to {} (has {})",
+                    variable, MIN_NUMERIC_LENGTH, expected, actual
+                )?;
+            }
+
+            Self::NumericPrecisionReduced { variable, length } => {
+                // The mantissa keeps (length - 1) bytes, less up to 3 bits
+                // lost to hexadecimal normalization
+                let exact = 1u64 << (8 * length).saturating_sub(11).min(63);
+                write!(
+                    f,
+                    "numeric variable '{}' has length {}; integers above {} and fractions lose precision",
+                    variable, length, exact
                 )?;
             }
 
@@ -446,6 +738,42 @@ impl fmt::Display for Issue {
                 )?;
             }
 
+            Self::PositionInconsistent {
+                variable,
+                recorded,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "variable '{}' is at position {} but the preceding variables end at {}",
+                    variable, recorded, expected
+                )?;
+            }
+
+            Self::UnknownRecord { offset, .. } => {
+                write!(
+                    f,
+                    "unknown header record at byte {} in the observation data",
+                    offset
+                )?;
+            }
+
+            Self::NonBlankPadding { offset, .. } => {
+                write!(
+                    f,
+                    "padding at byte {} after the last observation is not blank",
+                    offset
+                )?;
+            }
+
+            Self::IncompleteRecord { len, .. } => {
+                write!(
+                    f,
+                    "observation data ends with an incomplete record of {} bytes",
+                    len
+                )?;
+            }
+
             Self::DatasetNamePatternMismatch {
                 dataset,
                 agency,
@@ -531,6 +859,18 @@ impl fmt::Display for Issue {
                     variable, length, agency, max
                 )?;
             }
+            Self::FileSizeExceeded {
+                agency, size, max, ..
+            } => {
+                let gb = |bytes: usize| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+                write!(
+                    f,
+                    "estimated file size of {:.2} GB exceeds {} limit of {:.2} GB",
+                    gb(*size),
+                    agency,
+                    gb(*max)
+                )?;
+            }
             Self::MultiByteLabelNearLimit {
                 name,
                 is_dataset,
@@ -573,6 +913,206 @@ impl fmt::Display for Issue {
                     variable, format, reason
                 )?;
             }
+
+            Self::NonAsciiValue {
+                variable,
+                row,
+                count: 1,
+            } => {
+                write!(
+                    f,
+                    "value of variable '{}' in row {} contains non-ASCII characters",
+                    variable, row
+                )?;
+            }
+
+            Self::NonAsciiValue {
+                variable,
+                row,
+                count,
+            } => {
+                write!(
+                    f,
+                    "{} values of variable '{}' contain non-ASCII characters, the first in row {}",
+                    count, variable, row
+                )?;
+            }
+
+            Self::ValueTruncated {
+                variable,
+                row,
+                count: 1,
+                actual,
+                truncated,
+                ..
+            } => {
+                write!(
+                    f,
+                    "value of variable '{}' in row {} is truncated from {} to {} bytes",
+                    variable, row, actual, truncated
+                )?;
+            }
+
+            Self::ValueTruncated {
+                variable,
+                row,
+                count,
+                actual,
+                truncated,
+                ..
+            } => {
+                write!(
+                    f,
+                    "{} values of variable '{}' are truncated, the first in row {}, \
+                     the longest from {} to {} bytes",
+                    count, variable, row, actual, truncated
+                )?;
+            }
+
+            Self::LabelTruncated {
+                name,
+                is_dataset,
+                actual,
+                truncated,
+                ..
+            } => {
+                let kind = if *is_dataset { "dataset" } else { "variable" };
+                write!(
+                    f,
+                    "{} '{}' label is truncated from {} to {} bytes",
+                    kind, name, actual, truncated
+                )?;
+            }
+
+            Self::Fixed {
+                name,
+                is_dataset,
+                fix,
+            } => {
+                let kind = if *is_dataset { "dataset" } else { "variable" };
+                write!(f, "fixed {} '{}': {}", kind, name, fix)?;
+            }
+
+            Self::DatasetNotInDefine { dataset } => {
+                write!(f, "dataset '{}' is not defined in define.xml", dataset)?;
+            }
+
+            Self::VariableNotInDefine { variable } => {
+                write!(f, "variable '{}' is not defined in define.xml", variable)?;
+            }
+
+            Self::DefineVariableMissing {
+                variable,
+                mandatory,
+            } => {
+                let kind = if *mandatory {
+                    "mandatory"
+                } else {
+                    "permissible"
+                };
+                write!(
+                    f,
+                    "{} variable '{}' from define.xml is missing from the data",
+                    kind, variable
+                )?;
+            }
+
+            Self::DefineTypeMismatch {
+                variable,
+                data_type,
+            } => {
+                write!(
+                    f,
+                    "variable '{}' cannot be written as define.xml data type '{}'",
+                    variable, data_type
+                )?;
+            }
+
+            Self::DefineLengthExceeded {
+                variable,
+                length,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "character variable '{}' has values of {} bytes, longer than define.xml length {}",
+                    variable, actual, length
+                )?;
+            }
+
+            Self::DatasetNotInStandard { dataset, standard } => {
+                write!(f, "dataset '{}' is not defined in {}", dataset, standard)?;
+            }
+
+            Self::StandardVariableMissing { variable, required } => {
+                let kind = if *required { "required" } else { "expected" };
+                write!(
+                    f,
+                    "{} variable '{}' from the standard is missing from the data",
+                    kind, variable
+                )?;
+            }
+
+            Self::StandardTypeMismatch { variable, expected } => {
+                let kind = if expected.is_numeric() {
+                    "numeric"
+                } else {
+                    "character"
+                };
+                write!(
+                    f,
+                    "variable '{}' must be {} per the standard",
+                    variable, kind
+                )?;
+            }
+
+            Self::StandardLabelMismatch {
+                variable,
+                label,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "variable '{}' label '{}' differs from the standard label '{}'",
+                    variable, label, expected
+                )?;
+            }
+
+            Self::StandardOrderMismatch { variable, after } => {
+                write!(
+                    f,
+                    "variable '{}' comes after '{}' but precedes it in the standard",
+                    variable, after
+                )?;
+            }
+
+            Self::ValueNotInCodelist {
+                variable,
+                row,
+                count: 1,
+                value,
+                codelist,
+            } => {
+                write!(
+                    f,
+                    "value '{}' of variable '{}' in row {} is not in codelist {}",
+                    value, variable, row, codelist
+                )?;
+            }
+
+            Self::ValueNotInCodelist {
+                variable,
+                row,
+                count,
+                value,
+                codelist,
+            } => {
+                write!(
+                    f,
+                    "{} values of variable '{}' are not in codelist {}, the first '{}' in row {}",
+                    count, variable, codelist, value, row
+                )?;
+            }
         }
 
         // Append target if present
@@ -584,6 +1124,72 @@ impl fmt::Display for Issue {
     }
 }
 
+/// A change made by automatic remediation, recorded in [`Issue::Fixed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fix {
+    /// The name was uppercased and sanitized to the naming rules.
+    Renamed {
+        /// The original name.
+        from: String,
+    },
+
+    /// Non-ASCII characters in the label were transliterated or removed.
+    LabelTransliterated {
+        /// The original label.
+        from: String,
+    },
+
+    /// The label was shortened at a word boundary to fit the byte limit.
+    LabelShortened {
+        /// The original label.
+        from: String,
+    },
+
+    /// Non-ASCII characters in character values were transliterated or removed.
+    ValuesTransliterated {
+        /// The number of values changed.
+        values: usize,
+    },
+
+    /// Trailing whitespace was trimmed from character values.
+    TrailingWhitespaceTrimmed {
+        /// The number of values changed.
+        values: usize,
+    },
+
+    /// The character length was shrunk to the longest value.
+    LengthShrunk {
+        /// The original length.
+        from: usize,
+        /// The new length.
+        to: usize,
+    },
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Renamed { from } => write!(f, "renamed from '{}'", from),
+            Self::LabelTransliterated { from } => {
+                write!(f, "label transliterated to ASCII from '{}'", from)
+            }
+            Self::LabelShortened { from } => {
+                write!(f, "label shortened at a word boundary from '{}'", from)
+            }
+            Self::ValuesTransliterated { values } => {
+                write!(f, "{} values transliterated to ASCII", values)
+            }
+            Self::TrailingWhitespaceTrimmed { values } => {
+                write!(f, "trailing whitespace trimmed from {} values", values)
+            }
+            Self::LengthShrunk { from, to } => {
+                write!(f, "length shrunk from {} to {}", from, to)
+            }
+        }
+    }
+}
+
 /// The severity level of a validation [`Issue`].
 ///
 /// Severities are ordered from least to most severe: [`Severity::Info`] < [`Severity::Warning`] < [`Severity::Error`].
@@ -1,13 +1,22 @@
 //! Validation for xportrs.
 //!
 //! This module provides validation logic for XPT files. Structural
-//! checks for XPT v5 format are always applied. Agency-specific
+//! checks for the XPT version being written are always applied. Agency-specific
 //! validation is handled by the [`Agency`](crate::Agency) enum.
 
-mod checks_v5;
+mod checks_data;
+mod checks_file;
+mod checks_schema;
+mod fix;
 mod issues;
 
-pub(crate) use checks_v5::validate_v5_schema;
-pub use issues::{Issue, Severity};
+pub(crate) use checks_data::{
+    character_values, check_encodable_values, merge_value_issues, validate_data,
+};
+pub use checks_file::MemberIssues;
+pub(crate) use checks_file::validate_file;
+pub(crate) use checks_schema::validate_schema;
+pub(crate) use fix::{fix_dataset, fix_schema};
+pub use issues::{Fix, Issue, Severity};
 #[allow(unused_imports)]
 pub(crate) use issues::{IssueCollection, Target};
@@ -1,19 +1,34 @@
 //! Write plan for xportrs.
 //!
 //! This module provides the [`XptWriterBuilder`] and [`ValidatedWrite`] types
-//! for planning and executing XPT file writes from a [`Dataset`].
+//! for planning and executing XPT file writes from a [`Dataset`], and the
+//! [`XptLibraryWriter`] for writing several datasets into one transport file.
 
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 
 use crate::agency::Agency;
-use crate::config::Config;
+#[cfg(feature = "cdisc")]
+use crate::cdisc::{Standard, Terminology, apply_defaults, check_schema, check_schema_codelists};
+use crate::config::{Config, TextMode, WriteOptions};
 use crate::dataset::Dataset;
+#[cfg(feature = "define-xml")]
+use crate::define::{DefineXml, reconcile};
 use crate::error::{Error, Result};
 use crate::metadata::{DatasetMetadata, VariableMetadata};
-use crate::schema::{DatasetSchema, derive_schema_plan};
-use crate::validate::{Issue, IssueCollection, validate_v5_schema};
+use crate::schema::{DatasetSchema, derive_schema_plan, truncate_labels};
+use crate::validate::{
+    Fix, Issue, IssueCollection, check_encodable_values, fix_dataset, fix_schema, validate_data,
+    validate_schema,
+};
 use crate::xpt::XptVersion;
-use crate::xpt::v5::write::{SplitWriter, XptWriter, estimate_file_size_gb};
+use crate::xpt::v5::encoding::encode_str;
+use crate::xpt::v5::write::{
+    SplitWriter, XptStreamWriter, XptWriter, estimate_file_size, estimate_file_size_gb,
+    gb_to_bytes, max_rows_for_size,
+};
 
 /// A mutable builder for XPT write operations.
 ///
@@ -44,6 +59,12 @@ pub struct XptWriterBuilder {
     version: XptVersion,
     variable_meta: Option<Vec<VariableMetadata>>,
     dataset_meta: Option<DatasetMetadata>,
+    #[cfg(feature = "define-xml")]
+    define: Option<DefineXml>,
+    #[cfg(feature = "cdisc")]
+    standard: Option<Standard>,
+    #[cfg(feature = "cdisc")]
+    terminology: Option<Terminology>,
 }
 
 impl XptWriterBuilder {
@@ -57,6 +78,12 @@ impl XptWriterBuilder {
             version: XptVersion::V5,
             variable_meta: None,
             dataset_meta: None,
+            #[cfg(feature = "define-xml")]
+            define: None,
+            #[cfg(feature = "cdisc")]
+            standard: None,
+            #[cfg(feature = "cdisc")]
+            terminology: None,
         }
     }
 
@@ -90,44 +117,355 @@ impl XptWriterBuilder {
 
     /// Sets the XPT version.
     ///
-    /// Note: Currently only XPT v5 is supported. XPT v8 will return
-    /// an error during finalization.
+    /// Defaults to [`XptVersion::V5`]. [`XptVersion::V8`] allows variable names
+    /// up to 32 bytes and labels up to 256 bytes, but is not accepted for
+    /// regulatory submissions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, XptVersion, Dataset};
+    ///
+    /// # let dataset = Dataset::new("ARCHIVE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.xpt_version(XptVersion::V8);
+    /// builder.finalize()?.write_path("archive.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
     pub fn xpt_version(&mut self, version: XptVersion) -> &mut Self {
         self.version = version;
         self
     }
 
-    /// Sets variable metadata.
-    #[allow(dead_code)]
-    pub(crate) fn variable_metadata(&mut self, meta: Vec<VariableMetadata>) -> &mut Self {
-        self.variable_meta = Some(meta);
+    /// Sets the character set for character values, names and labels.
+    ///
+    /// Defaults to UTF-8. Use a legacy code page such as
+    /// [`TextMode::ShiftJis`] when the recipient expects one. Byte-length
+    /// limits are checked in the chosen character set, and finalizing fails
+    /// with [`Error::Encoding`] if any text cannot be represented in it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, TextMode, Dataset};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.text_mode(TextMode::ShiftJis);
+    /// builder.finalize()?.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn text_mode(&mut self, mode: TextMode) -> &mut Self {
+        self.config.write.text_mode = mode;
         self
     }
 
-    /// Sets dataset metadata.
-    #[allow(dead_code)]
-    pub(crate) fn dataset_metadata(&mut self, meta: DatasetMetadata) -> &mut Self {
+    /// Enables automatic repair of common problems.
+    ///
+    /// When enabled, [`finalize`](Self::finalize) fixes what it can before
+    /// validating, and records every change as an [`Issue::Fixed`]
+    /// ([`Severity::Info`](crate::Severity::Info)), giving an audit trail of
+    /// what was changed:
+    ///
+    /// - Dataset and variable names are uppercased and sanitized to the
+    ///   naming rules and length limit
+    /// - Non-ASCII characters in labels and values are transliterated if the
+    ///   [`Agency`] requires ASCII
+    /// - Labels over the length limit are shortened at a word boundary
+    /// - Trailing whitespace is trimmed from character values
+    /// - Character lengths are shrunk to the longest value, unless the length
+    ///   is set with [`Column::with_length`](crate::Column::with_length),
+    ///   variable metadata or Define-XML; streaming writes keep
+    ///   the lengths as they were
+    ///
+    /// Problems that cannot be fixed are still reported as usual, including
+    /// text with a character that has no ASCII equivalent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Agency, Dataset, Severity};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.agency(Agency::FDA).auto_fix(true);
+    /// let validated = builder.finalize()?;
+    /// for fix in validated.issues().iter().filter(|i| i.severity() == Severity::Info) {
+    ///     println!("{fix}");
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn auto_fix(&mut self, enabled: bool) -> &mut Self {
+        self.config.auto_fix = enabled;
+        self
+    }
+
+    /// Allows labels and character values to be truncated to fit.
+    ///
+    /// Labels longer than the XPT version allows and values longer than their
+    /// variable length are always reported, as [`Issue::LabelTruncated`] and
+    /// [`Issue::ValueTruncated`]. By default these are errors, so
+    /// [`finalize`](Self::finalize) fails; once truncation is allowed they are
+    /// warnings and the text is cut to fit, never splitting a character.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Dataset};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.allow_truncation(true);
+    /// let validated = builder.finalize()?;
+    /// for issue in validated.issues() {
+    ///     eprintln!("{issue}");
+    /// }
+    /// validated.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn allow_truncation(&mut self, allow: bool) -> &mut Self {
+        self.config.write.allow_truncation = allow;
+        self
+    }
+
+    /// Sets per-variable metadata from a specification.
+    ///
+    /// Metadata takes precedence over the [`Dataset`]: variables are ordered
+    /// by [`VariableMetadata::order`] (variables without an order follow in
+    /// their original order), and the type, length, label, format and role
+    /// given in the metadata replace those of the matching [`Column`]. Entries
+    /// are matched by variable name; entries whose domain code names another
+    /// dataset, or that have no matching column, are ignored, so one list can
+    /// cover a whole study.
+    ///
+    /// [`Column`]: crate::Column
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Dataset, VariableMetadata, XptVarType};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let spec = vec![
+    ///     VariableMetadata::new("AE", "USUBJID")
+    ///         .with_xpt_type(XptVarType::Character)
+    ///         .with_length(20)
+    ///         .with_label("Unique Subject Identifier")
+    ///         .with_order(1),
+    ///     VariableMetadata::new("AE", "AESTDTC")
+    ///         .with_label("Start Date/Time of Adverse Event")
+    ///         .with_format("$19.")
+    ///         .with_order(2),
+    /// ];
+    ///
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.variable_metadata(spec);
+    /// builder.finalize()?.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn variable_metadata(
+        &mut self,
+        meta: impl IntoIterator<Item = VariableMetadata>,
+    ) -> &mut Self {
+        self.variable_meta = Some(meta.into_iter().collect());
+        self
+    }
+
+    /// Sets dataset metadata from a specification.
+    ///
+    /// The domain code and label replace those of the [`Dataset`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Dataset, DatasetMetadata};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.dataset_metadata(DatasetMetadata::new("AE").with_label("Adverse Events"));
+    /// builder.finalize()?.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    pub fn dataset_metadata(&mut self, meta: DatasetMetadata) -> &mut Self {
         self.dataset_meta = Some(meta);
         self
     }
 
+    /// Uses a Define-XML document as the metadata source.
+    ///
+    /// The `ItemGroupDef` named like the dataset supplies the dataset label and,
+    /// through its `ItemRef`s and `ItemDef`s, the variable order, labels, data
+    /// types, lengths and display formats, as with
+    /// [`variable_metadata`](Self::variable_metadata). Metadata set explicitly
+    /// with [`dataset_metadata`](Self::dataset_metadata) or
+    /// [`variable_metadata`](Self::variable_metadata) takes precedence.
+    ///
+    /// Mismatches between the data and the definition are reported as
+    /// [`Issue`]s by [`finalize`](Self::finalize): variables missing on either
+    /// side, data that cannot be written as the defined data type, and values
+    /// longer than the defined length. In those cases the data decides the
+    /// written type or length.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Dataset};
+    /// use xportrs::define::DefineXml;
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let define = DefineXml::from_path("define.xml")?;
+    ///
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.define_xml(&define);
+    /// let validated = builder.finalize()?;
+    /// for issue in validated.issues() {
+    ///     eprintln!("{issue}");
+    /// }
+    /// validated.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[cfg(feature = "define-xml")]
+    pub fn define_xml(&mut self, define: &DefineXml) -> &mut Self {
+        self.define = Some(define.clone());
+        self
+    }
+
+    /// Checks the dataset against a CDISC Implementation Guide.
+    ///
+    /// [`finalize`](Self::finalize) first fills in the IG dataset label and
+    /// the IG label and role of each column that has none; labels from the
+    /// columns, [`variable_metadata`](Self::variable_metadata) or Define-XML
+    /// are kept. The dataset is looked up in the IG by the domain code of
+    /// [`dataset_metadata`](Self::dataset_metadata), if set.
+    ///
+    /// It then compares the variables as they will be written with the IG's
+    /// definitions for the dataset and reports missing Required and Expected
+    /// variables, Char/Num type mismatches, labels that differ from the IG
+    /// and variables out of IG order as [`Issue`]s. See the
+    /// [`cdisc`](crate::cdisc) module for the severities.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Dataset};
+    /// use xportrs::cdisc::sdtm_ig_v3_4;
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+    ///
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.standard(&standard);
+    /// let validated = builder.finalize()?;
+    /// for issue in validated.issues() {
+    ///     eprintln!("{issue}");
+    /// }
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[cfg(feature = "cdisc")]
+    pub fn standard(&mut self, standard: &Standard) -> &mut Self {
+        self.standard = Some(standard.clone());
+        self
+    }
+
+    /// Checks character values against controlled terminology.
+    ///
+    /// Together with [`standard`](Self::standard), [`finalize`](Self::finalize)
+    /// reports the values of a variable outside its non-extensible codelists
+    /// as an [`Issue::ValueNotInCodelist`] error, once per variable. The codelists of each
+    /// variable are taken from the standard; without a standard nothing is
+    /// checked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use xportrs::{Xpt, Dataset};
+    /// use xportrs::cdisc::{load_terminology, sdtm_ig_v3_4};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let standard = sdtm_ig_v3_4().expect("bundled SDTM-IG");
+    /// let ct = load_terminology(Path::new("SDTM Terminology.txt")).expect("CT package");
+    ///
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.standard(&standard).terminology(&ct);
+    /// builder.finalize()?.write_path("ae.xpt")?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    #[cfg(feature = "cdisc")]
+    pub fn terminology(&mut self, terminology: &Terminology) -> &mut Self {
+        self.terminology = Some(terminology.clone());
+        self
+    }
+
     /// Finalizes the write plan, performing validation.
     ///
     /// This validates:
-    /// 1. [`XptVersion::V5`] structural requirements (always)
-    /// 2. [`Agency`]-specific requirements (if an agency is set)
+    /// 1. Structural requirements of the selected [`XptVersion`] (always)
+    /// 2. Labels and character values that are truncated (always)
+    /// 3. [`Agency`]-specific requirements, including the character values
+    ///    themselves (if an agency is set)
+    /// 4. Conformance with a CDISC Implementation Guide (if a standard is set
+    ///    with `standard`, behind the `cdisc` feature)
+    ///
+    /// If [`auto_fix`](Self::auto_fix) is enabled, fixes are applied first.
     ///
     /// When an [`Agency`] is specified and no `max_size_gb` is configured,
     /// the agency's recommended maximum file size is automatically applied,
     /// enabling automatic file splitting for large datasets.
     ///
+    /// The agency's rules on the output file are checked when the file is
+    /// written; use [`finalize_for_path`](Self::finalize_for_path) to report
+    /// them here.
+    ///
     /// # Errors
     ///
     /// Returns an [`Error`] if:
-    /// - [`XptVersion::V8`] is requested (not yet implemented)
-    /// - Strict mode is enabled and validation errors are found
+    /// - The requested [`XptVersion`] is not implemented
+    /// - The metadata is invalid (e.g. an unparseable format)
+    /// - Strict mode is enabled and validation errors are found, including
+    ///   truncation that was not allowed with
+    ///   [`allow_truncation`](Self::allow_truncation)
     #[must_use = "this returns a Result that should be handled"]
-    pub fn finalize(mut self) -> Result<ValidatedWrite> {
+    pub fn finalize(self) -> Result<ValidatedWrite> {
+        self.finalize_with(None)
+    }
+
+    /// Finalizes the write plan for the file at `path`.
+    ///
+    /// Performs the validation of [`finalize`](Self::finalize) together with
+    /// the [`Agency`] rules on the output file:
+    ///
+    /// - The dataset name must match the file stem (`ae.xpt` for `AE`)
+    /// - The estimated size of the largest file, after any splitting, must
+    ///   not exceed the agency's maximum file size
+    ///
+    /// Nothing is written; pass the same path to
+    /// [`ValidatedWrite::write_path`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::{Xpt, Agency, Dataset};
+    ///
+    /// # let dataset = Dataset::new("AE", vec![]).unwrap();
+    /// let mut builder = Xpt::writer(dataset);
+    /// builder.agency(Agency::FDA);
+    /// // Fails: the dataset name does not match the file stem
+    /// assert!(builder.finalize_for_path("adverse_events.xpt").is_err());
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] in the cases of [`finalize`](Self::finalize),
+    /// including errors found by the checks on the output file.
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn finalize_for_path(self, path: impl AsRef<Path>) -> Result<ValidatedWrite> {
+        self.finalize_with(Some(path.as_ref()))
+    }
+
+    /// Finalizes the write plan, checking the output file if a path is given.
+    fn finalize_with(mut self, path: Option<&Path>) -> Result<ValidatedWrite> {
         // Check version support
         if !self.version.is_implemented() {
             return Err(Error::UnsupportedVersion {
@@ -135,6 +473,8 @@ impl XptWriterBuilder {
             });
         }
 
+        self.config.write.version = self.version;
+
         // Auto-enable file splitting for agency compliance
         if let Some(agency) = self.agency
             && self.config.write.max_size_gb.is_none()
@@ -142,39 +482,165 @@ impl XptWriterBuilder {
             self.config.write.max_size_gb = Some(agency.max_file_size_gb());
         }
 
+        let mut issues = Vec::new();
+
+        // Repair the data before anything is derived from it
+        if self.config.auto_fix {
+            issues.extend(fix_dataset(&mut self.dataset, self.agency, self.version));
+        }
+
+        // Fill in metadata from Define-XML
+        #[cfg(feature = "define-xml")]
+        issues.extend(self.apply_define_xml());
+
+        #[cfg(feature = "cdisc")]
+        self.apply_standard();
+
         // Derive schema plan
-        let schema = derive_schema_plan(
+        let mut schema = derive_schema_plan(
             &self.dataset,
             self.dataset_meta.as_ref(),
             self.variable_meta.as_deref(),
-            self.agency,
             &self.config,
         )?;
 
+        if self.config.auto_fix {
+            issues.extend(fix_schema(
+                &self.dataset,
+                &mut schema,
+                self.agency,
+                self.version,
+            ));
+        }
+
+        // All text must be representable in the target character set
+        check_encodable(&self.dataset, &schema)?;
+
         // Validate
-        let mut issues = Vec::new();
+        let allow_truncation = self.config.write.allow_truncation;
+        issues.extend(truncate_labels(&mut schema, self.version, allow_truncation));
+
+        // XPT structural checks (always applied)
+        issues.extend(validate_schema(&schema, self.version));
 
-        // XPT v5 structural checks (always applied)
-        issues.extend(validate_v5_schema(&schema));
+        // Character value checks (always applied)
+        issues.extend(validate_data(&self.dataset, &schema, allow_truncation));
 
         // Agency checks (only if agency is set)
         if let Some(agency) = self.agency {
-            issues.extend(agency.validate(&schema, None));
+            issues.extend(agency.validate(&schema));
+            issues.extend(agency.validate_data(&self.dataset, &schema));
         }
 
-        // Check for errors in strict mode
-        if self.config.strict_checks && issues.has_errors() {
-            let error_messages: Vec<String> = issues.errors().map(ToString::to_string).collect();
-            return Err(Error::validation_failed(error_messages.join("; ")));
+        // CDISC conformance checks (only if a standard is set)
+        #[cfg(feature = "cdisc")]
+        if let Some(standard) = &self.standard {
+            issues.extend(check_schema(&schema, standard));
+            if let Some(terminology) = &self.terminology {
+                issues.extend(check_schema_codelists(
+                    &self.dataset,
+                    &schema,
+                    standard,
+                    terminology,
+                ));
+            }
         }
 
-        Ok(ValidatedWrite {
+        let mut validated = ValidatedWrite {
             dataset: self.dataset,
             schema,
             issues,
             config: self.config,
-        })
+            agency: self.agency,
+        };
+
+        // Output file checks (only if a path is given)
+        if let Some(path) = path {
+            let issues = validated.check_path(path, true);
+            validated.issues.extend(issues);
+        }
+
+        // Check for errors in strict mode
+        if validated.config.strict_checks {
+            ensure_no_errors(&validated.issues)?;
+        }
+
+        Ok(validated)
+    }
+}
+
+#[cfg(feature = "define-xml")]
+impl XptWriterBuilder {
+    /// Converts the Define-XML definition of the dataset into metadata,
+    /// keeping any metadata set explicitly, and returns the mismatches.
+    fn apply_define_xml(&mut self) -> Vec<Issue> {
+        let Some(define) = self.define.take() else {
+            return Vec::new();
+        };
+        let name = self
+            .dataset_meta
+            .as_ref()
+            .map_or(self.dataset.domain_code(), |m| m.domain_code.as_str());
+        let Some(spec) = define.dataset(name) else {
+            return vec![Issue::DatasetNotInDefine {
+                dataset: name.to_string(),
+            }];
+        };
+
+        let (dataset_meta, variable_meta, issues) =
+            reconcile(&self.dataset, spec, self.config.write.text_mode);
+        self.dataset_meta.get_or_insert(dataset_meta);
+        self.variable_meta.get_or_insert(variable_meta);
+        issues
+    }
+}
+
+#[cfg(feature = "cdisc")]
+impl XptWriterBuilder {
+    /// Fills in the IG dataset label and the IG labels and roles of the
+    /// columns that have none.
+    fn apply_standard(&mut self) {
+        let Some(standard) = &self.standard else {
+            return;
+        };
+        let name = self
+            .dataset_meta
+            .as_ref()
+            .map_or(self.dataset.domain_code(), |m| m.domain_code.as_str())
+            .to_string();
+        apply_defaults(&mut self.dataset, &name, standard);
+    }
+}
+
+/// Fails with the messages of all [`Severity::Error`](crate::Severity::Error)
+/// issues, if there are any.
+fn ensure_no_errors(issues: &[Issue]) -> Result<()> {
+    if issues.has_errors() {
+        let error_messages: Vec<String> = issues.errors().map(ToString::to_string).collect();
+        return Err(Error::validation_failed(error_messages.join("; ")));
     }
+    Ok(())
+}
+
+/// Checks that names, labels and character values can be encoded in the
+/// schema's character set.
+fn check_encodable(dataset: &Dataset, schema: &DatasetSchema) -> Result<()> {
+    let mode = schema.text_mode;
+    if matches!(mode, TextMode::StrictUtf8 | TextMode::LossyUtf8) {
+        return Ok(());
+    }
+    let error = |context: String, e| Error::encoding(format!("{context}: {e}"));
+
+    encode_str(&schema.domain_code, mode).map_err(|e| error("dataset name".into(), e))?;
+    if let Some(label) = &schema.dataset_label {
+        encode_str(label, mode).map_err(|e| error("dataset label".into(), e))?;
+    }
+    for var in &schema.variables {
+        encode_str(&var.name, mode).map_err(|e| error("variable name".into(), e))?;
+        encode_str(&var.label, mode)
+            .map_err(|e| error(format!("label of variable '{}'", var.name), e))?;
+    }
+    check_encodable_values(dataset, mode, 0)
 }
 
 /// An immutable, validated write plan ready for execution.
@@ -187,6 +653,7 @@ pub struct ValidatedWrite {
     schema: DatasetSchema,
     issues: Vec<Issue>,
     config: Config,
+    agency: Option<Agency>,
 }
 
 impl ValidatedWrite {
@@ -215,12 +682,53 @@ impl ValidatedWrite {
         &self.schema
     }
 
+    /// Returns the dataset to be written.
+    #[must_use]
+    #[allow(dead_code)]
+    pub(crate) fn dataset(&self) -> &Dataset {
+        &self.dataset
+    }
+
+    /// Checks the output file against the [`Agency`] rules.
+    ///
+    /// `split` tells whether the file is split when it exceeds `max_size_gb`.
+    fn check_path(&self, path: &Path, split: bool) -> Vec<Issue> {
+        let Some(agency) = self.agency else {
+            return Vec::new();
+        };
+
+        let nrows = self.dataset.nrows();
+        let rows = self
+            .config
+            .write
+            .max_size_gb
+            .filter(|_| split)
+            .and_then(|max_gb| max_rows_for_size(&self.schema, gb_to_bytes(max_gb)))
+            .filter(|&rows| rows > 0)
+            .map_or(nrows, |rows| rows.min(nrows));
+
+        agency.validate_path(&self.schema, path, estimate_file_size(&self.schema, rows))
+    }
+
+    /// Fails if the output file does not meet the [`Agency`] rules, in
+    /// strict mode.
+    fn ensure_path(&self, path: &Path, split: bool) -> Result<()> {
+        if self.config.strict_checks {
+            ensure_no_errors(&self.check_path(path, split))?;
+        }
+        Ok(())
+    }
+
     /// Writes the XPT file to the specified path.
     ///
     /// Returns a list of file paths created. If the file was split due to size
     /// limits (configured via `max_size_gb` or automatically when an [`Agency`] is
     /// specified), multiple paths are returned (e.g., `ae_001.xpt`, `ae_002.xpt`).
     ///
+    /// The [`Agency`] rules on the output file are checked before anything
+    /// is written: the dataset name must match the file stem, and no file may
+    /// exceed the agency's maximum file size.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -240,10 +748,12 @@ impl ValidatedWrite {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error`] if writing fails.
+    /// Returns an [`Error`] if the output file does not meet the [`Agency`]
+    /// rules (in strict mode) or writing fails.
     #[must_use = "this returns a Result that should be handled"]
     pub fn write_path(self, path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
         let path = path.as_ref();
+        self.ensure_path(path, true)?;
 
         // Check if file splitting is needed
         if let Some(max_gb) = self.config.write.max_size_gb {
@@ -273,6 +783,251 @@ impl ValidatedWrite {
         xpt_writer.write(&self.dataset, &self.schema)?;
         Ok(())
     }
+
+    /// Starts a streaming write to the specified path.
+    ///
+    /// The finalized schema is fixed for the whole file: further rows are
+    /// appended with [`XptStreamWriter::write_chunk`], so datasets larger than
+    /// memory can be written chunk by chunk. Rows already in the finalized
+    /// [`Dataset`] are written first, so the first chunk can serve as the
+    /// template. Character lengths are taken from the template, so set
+    /// explicit lengths (or metadata) wide enough for all chunks. Lengths
+    /// shrunk by [`auto_fix`](XptWriterBuilder::auto_fix) are restored, as
+    /// later chunks may hold longer values.
+    ///
+    /// Streamed files are never split. The [`Agency`] rules on the output
+    /// file are checked before the file is created, with the size estimated
+    /// from the rows of the finalized [`Dataset`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xportrs::Xpt;
+    ///
+    /// # let first = xportrs::Dataset::new("LB", vec![]).unwrap();
+    /// # let rest: Vec<xportrs::Dataset> = vec![];
+    /// let mut stream = Xpt::writer(first).finalize()?.stream_path("lb.xpt")?;
+    /// for chunk in &rest {
+    ///     stream.write_chunk(chunk)?;
+    /// }
+    /// stream.finish()?;
+    /// # Ok::<(), xportrs::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the output file does not meet the [`Agency`]
+    /// rules (in strict mode), the file cannot be created or writing fails.
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn stream_path(
+        mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<XptStreamWriter<BufWriter<File>>> {
+        self.restore_lengths();
+        self.ensure_path(path.as_ref(), false)?;
+        let file = File::create(path.as_ref()).map_err(Error::Io)?;
+        self.stream_to(BufWriter::new(file))
+    }
+
+    /// Starts a streaming write to a seekable writer.
+    ///
+    /// See [`stream_path`](Self::stream_path). The writer must be seekable
+    /// so [`XptStreamWriter::finish`] can fill in the header timestamps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if writing fails.
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn stream_to<W: std::io::Write + std::io::Seek>(
+        mut self,
+        writer: W,
+    ) -> Result<XptStreamWriter<W>> {
+        self.restore_lengths();
+        let mut stream = XptStreamWriter::new(writer, self.schema, &self.config, self.agency)?;
+        stream.write_chunk(&self.dataset)?;
+        Ok(stream)
+    }
+
+    /// Undoes the [`Fix::LengthShrunk`] repairs of auto-fix.
+    fn restore_lengths(&mut self) {
+        let variables = &mut self.schema.variables;
+        self.issues.retain(|issue| {
+            let Issue::Fixed {
+                name,
+                is_dataset: false,
+                fix: Fix::LengthShrunk { from, .. },
+            } = issue
+            else {
+                return true;
+            };
+            if let Some(var) = variables.iter_mut().find(|v| &v.name == name) {
+                var.length = *from;
+            }
+            false
+        });
+        self.schema.recalculate_positions();
+    }
+}
+
+/// A writer for transport files with several members.
+///
+/// Each member is a [`ValidatedWrite`], written in the order it was added
+/// after a single library header. Members must use the same [`XptVersion`]
+/// and have distinct dataset names. Library files are never split; the
+/// `max_size_gb` setting of the members is ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use xportrs::{Xpt, Dataset};
+///
+/// # let dm = Dataset::new("DM", vec![]).unwrap();
+/// # let ae = Dataset::new("AE", vec![]).unwrap();
+/// let mut library = Xpt::library_writer();
+/// library.add_dataset(dm)?;
+/// library.add(Xpt::writer(ae).finalize()?);
+/// library.write_path("study.xpt")?;
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct XptLibraryWriter {
+    version: XptVersion,
+    members: Vec<ValidatedWrite>,
+}
+
+impl XptLibraryWriter {
+    /// Creates an empty library writer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the XPT version used by [`add_dataset`](Self::add_dataset).
+    ///
+    /// Members added with [`add`](Self::add) must have been finalized with
+    /// the same version.
+    pub fn xpt_version(&mut self, version: XptVersion) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    /// Adds an already validated member.
+    ///
+    /// All members are written with the same options: writing fails if
+    /// members were finalized with a different version, text mode,
+    /// truncation setting or timestamps.
+    pub fn add(&mut self, member: ValidatedWrite) -> &mut Self {
+        self.members.push(member);
+        self
+    }
+
+    /// Validates a [`Dataset`] with default settings and adds it as a member.
+    ///
+    /// Use [`Xpt::writer`](crate::Xpt::writer) and [`add`](Self::add) for
+    /// control over agency and metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if finalization fails.
+    pub fn add_dataset(&mut self, dataset: Dataset) -> Result<&mut Self> {
+        let mut builder = XptWriterBuilder::new(dataset);
+        builder.xpt_version(self.version);
+        Ok(self.add(builder.finalize()?))
+    }
+
+    /// Returns the number of members.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if no members have been added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Returns the [`Issue`] items found while finalizing the members.
+    pub fn issues(&self) -> impl Iterator<Item = &Issue> {
+        self.members.iter().flat_map(ValidatedWrite::issues)
+    }
+
+    /// Writes all members to the specified path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the library is empty, the members are
+    /// inconsistent, or writing fails.
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn write_path(self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = path.as_ref();
+        self.check_members()?;
+        let file = File::create(path).map_err(Error::Io)?;
+        self.write_members(BufWriter::new(file))?;
+        Ok(path.to_path_buf())
+    }
+
+    /// Writes all members to a [`std::io::Write`] implementor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the library is empty, the members are
+    /// inconsistent, or writing fails.
+    #[must_use = "this returns a Result that should be handled"]
+    pub fn write_to<W: std::io::Write>(self, writer: W) -> Result<()> {
+        self.check_members()?;
+        self.write_members(writer)?;
+        Ok(())
+    }
+
+    /// Checks that the members can be written into one file.
+    fn check_members(&self) -> Result<()> {
+        let first = self
+            .members
+            .first()
+            .ok_or_else(|| Error::invalid_schema("library writer has no members"))?;
+
+        // Libraries are not split, so the split size may differ
+        let written = |options: &WriteOptions| WriteOptions {
+            max_size_gb: None,
+            ..options.clone()
+        };
+        let options = &first.config.write;
+        let mut names = HashSet::new();
+        for member in &self.members {
+            if member.config.write.version != options.version {
+                return Err(Error::invalid_schema(format!(
+                    "member '{}' uses XPT {} but the library uses XPT {}",
+                    member.schema.domain_code, member.config.write.version, options.version
+                )));
+            }
+            if written(&member.config.write) != written(options) {
+                return Err(Error::invalid_schema(format!(
+                    "member '{}' uses different write options than member '{}'",
+                    member.schema.domain_code, first.schema.domain_code
+                )));
+            }
+            if !names.insert(member.schema.domain_code.to_ascii_uppercase()) {
+                return Err(Error::invalid_schema(format!(
+                    "duplicate member name '{}'",
+                    member.schema.domain_code
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the members using the write options they share.
+    fn write_members<W: std::io::Write>(self, writer: W) -> Result<W> {
+        let options = self.members[0].config.write.clone();
+        let members: Vec<(&Dataset, &DatasetSchema)> = self
+            .members
+            .iter()
+            .map(|m| (&m.dataset, &m.schema))
+            .collect();
+        XptWriter::new(writer, options).write_library(&members)
+    }
 }
 
 #[cfg(test)]
@@ -316,13 +1071,67 @@ mod tests {
     }
 
     #[test]
-    fn test_write_plan_v8_unsupported() {
-        let dataset = Dataset::new("AE", vec![]).unwrap();
+    fn test_write_plan_v8_long_names() {
+        let dataset = Dataset::new(
+            "LONG_DATASET_NAME",
+            vec![Column::new(
+                "SUBJECT_IDENTIFIER",
+                ColumnData::F64(vec![Some(1.0)]),
+            )],
+        )
+        .unwrap();
 
-        let mut builder = XptWriterBuilder::new(dataset);
+        let mut builder = XptWriterBuilder::new(dataset.clone());
         builder.xpt_version(XptVersion::V8);
         let plan = builder.finalize();
+        assert!(plan.is_ok());
+
+        // The same dataset is rejected under the V5 limits
+        let builder = XptWriterBuilder::new(dataset);
+        assert!(builder.finalize().is_err());
+    }
+
+    #[test]
+    fn test_library_writer_rejects_duplicates() {
+        let mut library = XptLibraryWriter::new();
+        library
+            .add_dataset(Dataset::new("AE", vec![]).unwrap())
+            .unwrap();
+        library
+            .add_dataset(Dataset::new("ae", vec![]).unwrap())
+            .unwrap();
+
+        assert_eq!(library.len(), 2);
+        assert!(library.write_to(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_library_writer_rejects_mixed_versions() {
+        let mut builder = XptWriterBuilder::new(Dataset::new("DM", vec![]).unwrap());
+        builder.xpt_version(XptVersion::V8);
+
+        let mut library = XptLibraryWriter::new();
+        library.add(builder.finalize().unwrap());
+        library
+            .add_dataset(Dataset::new("AE", vec![]).unwrap())
+            .unwrap();
+
+        assert!(library.write_to(Vec::new()).is_err());
+        assert!(XptLibraryWriter::new().write_to(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_library_writer_rejects_mixed_options() {
+        let mut builder = XptWriterBuilder::new(Dataset::new("DM", vec![]).unwrap());
+        builder.text_mode(TextMode::Windows1252);
+
+        let mut library = XptLibraryWriter::new();
+        library.add(builder.finalize().unwrap());
+        library
+            .add_dataset(Dataset::new("AE", vec![]).unwrap())
+            .unwrap();
 
-        assert!(plan.is_err());
+        let err = library.write_to(Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("different write options"));
     }
 }
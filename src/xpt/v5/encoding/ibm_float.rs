@@ -87,6 +87,45 @@ impl SasMissingValue {
         };
         [first_byte, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
     }
+
+    /// Returns the missing value for a SAS missing code (`.`, `A`-`Z` or `_`).
+    ///
+    /// Returns `None` for any other character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use xportrs::SasMissingValue;
+    ///
+    /// assert_eq!(SasMissingValue::from_code('U'), Some(SasMissingValue::Special('U')));
+    /// assert_eq!(SasMissingValue::from_code('_'), Some(SasMissingValue::Underscore));
+    /// assert_eq!(SasMissingValue::from_code('u'), None);
+    /// ```
+    #[must_use]
+    pub const fn from_code(code: char) -> Option<Self> {
+        match code {
+            '.' => Some(Self::Standard),
+            '_' => Some(Self::Underscore),
+            'A'..='Z' => Some(Self::Special(code)),
+            _ => None,
+        }
+    }
+
+    /// Returns the code character (`.`, `A`-`Z` or `_`).
+    #[must_use]
+    pub const fn code(self) -> char {
+        match self {
+            Self::Standard => '.',
+            Self::Underscore => '_',
+            Self::Special(c) => c,
+        }
+    }
+
+    /// Returns `true` for `.A`-`.Z` and `._`, `false` for the standard `.`.
+    #[must_use]
+    pub const fn is_special(self) -> bool {
+        !matches!(self, Self::Standard)
+    }
 }
 
 impl std::fmt::Display for SasMissingValue {
@@ -118,9 +157,15 @@ pub fn identify_missing_value(bytes: &[u8; 8]) -> Option<SasMissingValue> {
 }
 
 /// Encodes a SAS missing value to IBM float format.
+///
+/// A [`SasMissingValue::Special`] holding anything other than `A`-`Z` is
+/// not a valid SAS code and is encoded as a standard missing value.
 #[must_use]
 pub const fn encode_missing_value(missing: SasMissingValue) -> [u8; 8] {
-    missing.to_bytes()
+    match missing {
+        SasMissingValue::Special(c) if !c.is_ascii_uppercase() => MISSING_PATTERN,
+        _ => missing.to_bytes(),
+    }
 }
 
 /// SAS missing value patterns for reference.
@@ -369,4 +414,18 @@ mod tests {
         let encoded = encode_ibm_float(Some(f64::NAN));
         assert!(is_missing_value(&encoded));
     }
+
+    #[test]
+    fn test_special_missing_roundtrip() {
+        for code in ['.', 'A', 'N', 'U', 'Z', '_'] {
+            let missing = SasMissingValue::from_code(code).unwrap();
+            let encoded = encode_missing_value(missing);
+            assert_eq!(identify_missing_value(&encoded), Some(missing));
+            assert_eq!(missing.code(), code);
+        }
+        assert_eq!(
+            encode_missing_value(SasMissingValue::Special('a')),
+            missing_patterns::MISSING
+        );
+    }
 }
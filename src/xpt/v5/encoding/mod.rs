@@ -13,4 +13,7 @@ pub use ibm_float::{
     SasMissingValue, decode_ibm_float, encode_ibm_float, encode_missing_value,
     identify_missing_value, is_missing_value, missing_patterns,
 };
-pub use text::{decode_text, encode_text, is_valid_xpt_string, truncate_utf8};
+pub use text::{
+    TextEncodingError, decode_text, encode_str, encode_text, encoded_len, is_valid_xpt_string,
+    truncate_encoded, truncate_utf8,
+};
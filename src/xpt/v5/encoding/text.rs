@@ -2,10 +2,29 @@
 //!
 //! This module handles encoding and decoding of character data in XPT v5 files.
 
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, GB18030, SHIFT_JIS, WINDOWS_1252};
+
 use crate::config::TextMode;
 
+/// Returns the `encoding_rs` code page for the legacy text modes.
+///
+/// UTF-8 and Latin-1 are handled directly and return `None`.
+fn code_page(mode: TextMode) -> Option<&'static Encoding> {
+    match mode {
+        TextMode::Windows1252 => Some(WINDOWS_1252),
+        TextMode::ShiftJis => Some(SHIFT_JIS),
+        TextMode::Gb18030 => Some(GB18030),
+        TextMode::StrictUtf8 | TextMode::LossyUtf8 | TextMode::Latin1 => None,
+    }
+}
+
 /// Decodes bytes to a string using the specified text mode.
 ///
+/// Malformed sequences in the legacy code pages are replaced with U+FFFD,
+/// as in [`TextMode::LossyUtf8`].
+///
 /// # Arguments
 ///
 /// * `bytes` - The raw bytes from the XPT file
@@ -19,11 +38,15 @@ pub fn decode_text(
     bytes: &[u8],
     mode: TextMode,
     trim_spaces: bool,
-) -> Result<String, std::string::FromUtf8Error> {
-    let s = match mode {
-        TextMode::StrictUtf8 => String::from_utf8(bytes.to_vec())?,
-        TextMode::LossyUtf8 => String::from_utf8_lossy(bytes).into_owned(),
-        TextMode::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+) -> Result<String, TextEncodingError> {
+    let s = if let Some(encoding) = code_page(mode) {
+        encoding.decode_without_bom_handling(bytes).0.into_owned()
+    } else {
+        match mode {
+            TextMode::StrictUtf8 => String::from_utf8(bytes.to_vec())?,
+            TextMode::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+            _ => String::from_utf8_lossy(bytes).into_owned(),
+        }
     };
 
     Ok(if trim_spaces {
@@ -33,46 +56,117 @@ pub fn decode_text(
     })
 }
 
+/// Encodes a string in the character set of the specified text mode.
+///
+/// The UTF-8 modes write UTF-8; the other modes write their code page.
+///
+/// # Errors
+///
+/// Returns an error if the string contains a character the code page
+/// cannot represent.
+pub fn encode_str(s: &str, mode: TextMode) -> Result<Cow<'_, [u8]>, TextEncodingError> {
+    let unmappable = || TextEncodingError::Unmappable {
+        text: s.to_string(),
+        mode,
+    };
+
+    if let Some(encoding) = code_page(mode) {
+        let (bytes, _, had_errors) = encoding.encode(s);
+        return if had_errors {
+            Err(unmappable())
+        } else {
+            Ok(bytes)
+        };
+    }
+    match mode {
+        TextMode::Latin1 => s
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)).map_err(|_| unmappable()))
+            .collect::<Result<Vec<u8>, _>>()
+            .map(Cow::Owned),
+        _ => Ok(Cow::Borrowed(s.as_bytes())),
+    }
+}
+
+/// Returns the byte length of a string in the character set of `mode`.
+///
+/// Characters the code page cannot represent are counted as they would be
+/// replaced, so the result is an estimate for such strings.
+#[must_use]
+pub fn encoded_len(s: &str, mode: TextMode) -> usize {
+    if let Some(encoding) = code_page(mode) {
+        return encoding.encode(s).0.len();
+    }
+    match mode {
+        TextMode::Latin1 => s.chars().count(),
+        _ => s.len(),
+    }
+}
+
+/// Truncates a string so that its encoding in `mode` fits `max_bytes`.
+///
+/// Never splits a character, in UTF-8 or in the target code page.
+#[must_use]
+pub fn truncate_encoded(s: &str, max_bytes: usize, mode: TextMode) -> &str {
+    if encoded_len(s, mode) <= max_bytes {
+        return s;
+    }
+
+    let mut total = 0;
+    let mut buf = [0u8; 4];
+    for (idx, c) in s.char_indices() {
+        total += encoded_len(c.encode_utf8(&mut buf), mode);
+        if total > max_bytes {
+            return &s[..idx];
+        }
+    }
+    s
+}
+
 /// Encodes a string to bytes for XPT v5, padding to the specified length.
 ///
+/// Values longer than `length` are truncated without splitting a character.
+///
 /// # Arguments
 ///
 /// * `s` - The string to encode
 /// * `length` - The fixed width to pad to
-/// * `require_ascii` - If true, validates that the string contains only ASCII
+/// * `mode` - The text mode whose character set is written
 ///
 /// # Errors
 ///
-/// Returns an error if `require_ascii` is true and the string contains non-ASCII.
+/// Returns an error if the string contains a character the code page
+/// cannot represent.
 pub fn encode_text(
     s: Option<&str>,
     length: usize,
-    require_ascii: bool,
+    mode: TextMode,
 ) -> Result<Vec<u8>, TextEncodingError> {
-    let bytes = match s {
-        Some(s) => {
-            if require_ascii && !s.is_ascii() {
-                return Err(TextEncodingError::NonAscii(s.to_string()));
-            }
-            let mut b = s.as_bytes().to_vec();
-            b.truncate(length);
-            b
-        }
+    let mut bytes = match s {
+        Some(s) => encode_str(truncate_encoded(s, length, mode), mode)?.into_owned(),
         None => Vec::new(),
     };
 
     // Pad with spaces to the required length
-    let mut result = bytes;
-    result.resize(length, b' ');
-    Ok(result)
+    bytes.resize(length, b' ');
+    Ok(bytes)
 }
 
 /// Error type for text encoding operations.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum TextEncodingError {
-    /// The text contains non-ASCII characters when ASCII was required.
-    #[error("non-ASCII character in text: {0}")]
-    NonAscii(String),
+    /// The bytes are not valid UTF-8 in strict mode.
+    #[error("invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+
+    /// The text contains a character the target code page cannot represent.
+    #[error("text cannot be encoded as {mode:?}: {text}")]
+    Unmappable {
+        /// The text that failed to encode.
+        text: String,
+        /// The target text mode.
+        mode: TextMode,
+    },
 }
 
 /// Validates that a string contains only printable ASCII characters suitable for XPT.
@@ -130,21 +224,53 @@ mod tests {
     }
 
     #[test]
-    fn test_encode_ascii() {
-        let result = encode_text(Some("Test"), 8, true).unwrap();
+    fn test_encode_utf8() {
+        let result = encode_text(Some("Test"), 8, TextMode::LossyUtf8).unwrap();
         assert_eq!(result, b"Test    ");
     }
 
     #[test]
-    fn test_encode_non_ascii_rejected() {
-        let result = encode_text(Some("Tëst"), 8, true);
-        assert!(result.is_err());
+    fn test_encode_none() {
+        let result = encode_text(None, 8, TextMode::LossyUtf8).unwrap();
+        assert_eq!(result, b"        ");
     }
 
     #[test]
-    fn test_encode_none() {
-        let result = encode_text(None, 8, false).unwrap();
-        assert_eq!(result, b"        ");
+    fn test_code_page_roundtrip() {
+        for (mode, text) in [
+            (TextMode::Latin1, "Caf\u{e9}"),
+            (TextMode::Windows1252, "\u{201c}Caf\u{e9}\u{201d} \u{20ac}5"),
+            (TextMode::ShiftJis, "\u{6709}\u{5bb3}\u{4e8b}\u{8c61}"),
+            (TextMode::Gb18030, "\u{4e0d}\u{826f}\u{4e8b}\u{4ef6}"),
+        ] {
+            let len = encoded_len(text, mode);
+            let bytes = encode_text(Some(text), len + 2, mode).unwrap();
+            assert_eq!(decode_text(&bytes, mode, true).unwrap(), text, "{mode:?}");
+        }
+        // Double-byte characters in Shift-JIS and GB18030
+        assert_eq!(encoded_len("\u{6709}\u{5bb3}", TextMode::ShiftJis), 4);
+        assert_eq!(encoded_len("\u{6709}\u{5bb3}", TextMode::LossyUtf8), 6);
+    }
+
+    #[test]
+    fn test_encode_unmappable_rejected() {
+        assert!(encode_text(Some("\u{6709}"), 8, TextMode::Windows1252).is_err());
+        assert!(encode_text(Some("\u{20ac}"), 8, TextMode::Latin1).is_err());
+    }
+
+    #[test]
+    fn test_truncate_encoded() {
+        // Never splits a double-byte character
+        let text = "A\u{6709}\u{5bb3}";
+        assert_eq!(truncate_encoded(text, 4, TextMode::ShiftJis), "A\u{6709}");
+        assert_eq!(truncate_encoded(text, 5, TextMode::ShiftJis), text);
+        assert_eq!(truncate_encoded(text, 4, TextMode::LossyUtf8), "A\u{6709}");
+        assert_eq!(
+            encode_text(Some(text), 4, TextMode::ShiftJis)
+                .unwrap()
+                .len(),
+            4
+        );
     }
 
     #[test]
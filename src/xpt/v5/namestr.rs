@@ -6,11 +6,13 @@
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use std::io::Cursor;
 
+use crate::config::TextMode;
 use crate::error::{Error, Result};
 use crate::metadata::XptVarType;
 use crate::schema::VariableSpec;
 
 use super::constants::NAMESTR_LEN;
+use super::encoding::{decode_text, encode_text};
 
 /// A parsed NAMESTR record.
 ///
@@ -106,7 +108,11 @@ impl NamestrV5 {
 /// # Errors
 ///
 /// Returns an error if packing fails (should not happen with valid input).
-pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NAMESTR_LEN]> {
+pub(crate) fn pack_namestr(
+    var: &VariableSpec,
+    var_num: usize,
+    mode: TextMode,
+) -> Result<[u8; NAMESTR_LEN]> {
     let mut buf = [0u8; NAMESTR_LEN];
     let mut cursor = Cursor::new(&mut buf[..]);
 
@@ -128,7 +134,7 @@ pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NA
         .map_err(Error::Io)?;
 
     // nname: variable name (8 bytes, space-padded)
-    let name_bytes = pad_string(&var.name, 8);
+    let name_bytes = pad_string(&var.name, 8, mode)?;
     cursor
         .get_mut()
         .get_mut(8..16)
@@ -136,7 +142,7 @@ pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NA
         .copy_from_slice(&name_bytes);
 
     // nlabel: label (40 bytes, space-padded)
-    let label_bytes = pad_string(&var.label, 40);
+    let label_bytes = pad_string(&var.label, 40, mode)?;
     cursor
         .get_mut()
         .get_mut(16..56)
@@ -144,7 +150,7 @@ pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NA
         .copy_from_slice(&label_bytes);
 
     // nform: format name (8 bytes, space-padded)
-    let format_bytes = pad_string(var.format_name(), 8);
+    let format_bytes = pad_string(var.format_name(), 8, mode)?;
     cursor
         .get_mut()
         .get_mut(56..64)
@@ -167,7 +173,7 @@ pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NA
     cursor.set_position(72);
 
     // niform: informat name (8 bytes, space-padded)
-    let informat_bytes = pad_string(var.informat_name(), 8);
+    let informat_bytes = pad_string(var.informat_name(), 8, mode)?;
     cursor
         .get_mut()
         .get_mut(72..80)
@@ -196,10 +202,17 @@ pub(crate) fn pack_namestr(var: &VariableSpec, var_num: usize) -> Result<[u8; NA
 
 /// Unpacks a 140-byte NAMESTR record into a [`NamestrV5`].
 ///
+/// The name, label and format names are decoded with `mode`.
+///
 /// # Errors
 ///
-/// Returns an error if the record is malformed.
-pub fn unpack_namestr(data: &[u8; NAMESTR_LEN]) -> Result<NamestrV5> {
+/// Returns an error if the record is malformed or a text field cannot be
+/// decoded.
+pub fn unpack_namestr(data: &[u8; NAMESTR_LEN], mode: TextMode) -> Result<NamestrV5> {
+    let text = |range: std::ops::Range<usize>| {
+        decode_text(&data[range], mode, true).map_err(|e| Error::encoding(e.to_string()))
+    };
+
     let mut cursor = Cursor::new(data);
 
     let ntype = cursor.read_i16::<BigEndian>().map_err(Error::Io)?;
@@ -207,13 +220,9 @@ pub fn unpack_namestr(data: &[u8; NAMESTR_LEN]) -> Result<NamestrV5> {
     let nlng = cursor.read_i16::<BigEndian>().map_err(Error::Io)?;
     let nvar0 = cursor.read_i16::<BigEndian>().map_err(Error::Io)?;
 
-    let nname = String::from_utf8_lossy(&data[8..16]).trim_end().to_string();
-    let nlabel = String::from_utf8_lossy(&data[16..56])
-        .trim_end()
-        .to_string();
-    let nform = String::from_utf8_lossy(&data[56..64])
-        .trim_end()
-        .to_string();
+    let nname = text(8..16)?;
+    let nlabel = text(16..56)?;
+    let nform = text(56..64)?;
 
     cursor.set_position(64);
     let nfl = cursor.read_i16::<BigEndian>().map_err(Error::Io)?;
@@ -223,9 +232,7 @@ pub fn unpack_namestr(data: &[u8; NAMESTR_LEN]) -> Result<NamestrV5> {
     let mut nfill = [0u8; 2];
     nfill.copy_from_slice(&data[70..72]);
 
-    let niform = String::from_utf8_lossy(&data[72..80])
-        .trim_end()
-        .to_string();
+    let niform = text(72..80)?;
 
     cursor.set_position(80);
     let nifl = cursor.read_i16::<BigEndian>().map_err(Error::Io)?;
@@ -259,12 +266,9 @@ pub fn unpack_namestr(data: &[u8; NAMESTR_LEN]) -> Result<NamestrV5> {
     })
 }
 
-/// Pads a string with spaces to the specified length.
-fn pad_string(s: &str, len: usize) -> Vec<u8> {
-    let mut bytes = s.as_bytes().to_vec();
-    bytes.truncate(len);
-    bytes.resize(len, b' ');
-    bytes
+/// Encodes a string in the character set of `mode`, padded with spaces.
+fn pad_string(s: &str, len: usize, mode: TextMode) -> Result<Vec<u8>> {
+    encode_text(Some(s), len, mode).map_err(|e| Error::encoding(e.to_string()))
 }
 
 #[cfg(test)]
@@ -279,8 +283,8 @@ mod tests {
             .with_format(Format::numeric(8, 0))
             .with_source_index(0);
 
-        let packed = pack_namestr(&var, 0).unwrap();
-        let unpacked = unpack_namestr(&packed).unwrap();
+        let packed = pack_namestr(&var, 0, TextMode::default()).unwrap();
+        let unpacked = unpack_namestr(&packed, TextMode::default()).unwrap();
 
         assert_eq!(unpacked.nname, "AESEQ");
         assert_eq!(unpacked.nlabel, "Sequence Number");
@@ -294,8 +298,8 @@ mod tests {
     fn test_character_variable() {
         let var = VariableSpec::character("USUBJID", 20).with_format(Format::character(20));
 
-        let packed = pack_namestr(&var, 1).unwrap();
-        let unpacked = unpack_namestr(&packed).unwrap();
+        let packed = pack_namestr(&var, 1, TextMode::default()).unwrap();
+        let unpacked = unpack_namestr(&packed, TextMode::default()).unwrap();
 
         assert_eq!(unpacked.ntype, 2); // character
         assert_eq!(unpacked.nlng, 20);
@@ -309,8 +313,8 @@ mod tests {
             .with_label("Start Date")
             .with_format(Format::parse("DATE9.").unwrap());
 
-        let packed = pack_namestr(&var, 0).unwrap();
-        let unpacked = unpack_namestr(&packed).unwrap();
+        let packed = pack_namestr(&var, 0, TextMode::default()).unwrap();
+        let unpacked = unpack_namestr(&packed, TextMode::default()).unwrap();
 
         assert_eq!(unpacked.nform, "DATE");
         assert_eq!(unpacked.nfl, 9);
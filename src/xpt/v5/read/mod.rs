@@ -10,5 +10,6 @@ mod parse;
 mod reader;
 
 pub use obs::ObservationReader;
+pub(crate) use parse::find_members;
 pub use parse::{XptMemberInfo, parse_header};
-pub use reader::{XptInfo, XptReader};
+pub use reader::{XptBatchReader, XptInfo, XptReader};
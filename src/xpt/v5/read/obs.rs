@@ -6,15 +6,15 @@ use std::io::Read;
 
 use crate::config::ReadOptions;
 use crate::error::{Error, Result};
-use crate::xpt::v5::constants::{PAD_CHAR, RECORD_LEN};
-use crate::xpt::v5::encoding::{decode_ibm_float, decode_text};
+use crate::xpt::v5::constants::RECORD_LEN;
+use crate::xpt::v5::encoding::{decode_ibm_float, decode_text, identify_missing_value};
 use crate::xpt::v5::namestr::NamestrV5;
 
 use super::reader::ObsValue;
 
 /// Reader for observation data.
-pub struct ObservationReader<'a, R: Read> {
-    reader: &'a mut R,
+pub struct ObservationReader<R: Read> {
+    reader: R,
     variables: Vec<NamestrV5>,
     row_len: usize,
     options: ReadOptions,
@@ -24,22 +24,33 @@ pub struct ObservationReader<'a, R: Read> {
     at_eof: bool,
 }
 
-impl<'a, R: Read> ObservationReader<'a, R> {
+impl<R: Read> ObservationReader<R> {
     /// Creates a new observation reader.
     ///
+    /// `reader` must end with the last observation: padding after it would
+    /// be decoded as further rows.
+    ///
     /// # Errors
     ///
     /// Returns an error if the reader cannot be initialized.
-    pub(crate) fn new(
-        reader: &'a mut R,
-        variables: &[NamestrV5],
-        options: &ReadOptions,
-    ) -> Result<Self> {
+    pub(crate) fn new(reader: R, variables: &[NamestrV5], options: &ReadOptions) -> Result<Self> {
         let row_len: usize = variables.iter().map(NamestrV5::length).sum();
+        let variables = project_variables(variables, options.columns.as_deref())?;
+
+        if let Some(var) = variables
+            .iter()
+            .find(|v| v.xpt_type().is_numeric() && !(1..=8).contains(&v.length()))
+        {
+            return Err(Error::corrupt(format!(
+                "numeric variable '{}' has invalid length {}",
+                var.nname,
+                var.length()
+            )));
+        }
 
         Ok(Self {
             reader,
-            variables: variables.to_vec(),
+            variables,
             row_len,
             options: options.clone(),
             buffer: vec![0u8; RECORD_LEN * 10], // Buffer multiple records
@@ -49,6 +60,12 @@ impl<'a, R: Read> ObservationReader<'a, R> {
         })
     }
 
+    /// Returns the variables decoded for each row, after projection.
+    #[must_use]
+    pub fn variables(&self) -> &[NamestrV5] {
+        &self.variables
+    }
+
     /// Reads a single observation (row).
     ///
     /// Returns `None` when all observations have been read.
@@ -57,7 +74,7 @@ impl<'a, R: Read> ObservationReader<'a, R> {
     ///
     /// Returns an error if reading fails.
     pub fn read_observation(&mut self) -> Result<Option<Vec<ObsValue>>> {
-        if self.at_eof {
+        if self.at_eof || self.row_len == 0 {
             return Ok(None);
         }
 
@@ -67,14 +84,6 @@ impl<'a, R: Read> ObservationReader<'a, R> {
             None => return Ok(None),
         };
 
-        // Check if the entire row is padding (all spaces)
-        // This indicates we've reached the end of actual observation data
-        // XPT files pad to 80-byte record boundaries with spaces (0x20)
-        if row_data.iter().all(|&b| b == PAD_CHAR) {
-            self.at_eof = true;
-            return Ok(None);
-        }
-
         // Decode each variable
         let mut values = Vec::with_capacity(self.variables.len());
 
@@ -93,27 +102,20 @@ impl<'a, R: Read> ObservationReader<'a, R> {
             let var_data = &row_data[start..end];
 
             let value = if var.xpt_type().is_numeric() {
-                // Decode numeric value
+                // Decode numeric value; shorter numerics are zero-extended
                 let mut bytes = [0u8; 8];
-                bytes.copy_from_slice(var_data);
-                let f = decode_ibm_float(&bytes);
-                ObsValue::Numeric(f)
+                bytes[..var_data.len()].copy_from_slice(var_data);
+                match identify_missing_value(&bytes) {
+                    Some(missing) if missing.is_special() => ObsValue::SpecialMissing(missing),
+                    _ => ObsValue::Numeric(decode_ibm_float(&bytes)),
+                }
             } else {
                 // Decode character value
                 let trim = !self.options.preserve_blanks;
-                match decode_text(var_data, self.options.text_mode, trim) {
-                    Ok(s) => ObsValue::Character(if s.is_empty() { None } else { Some(s) }),
-                    Err(_) => {
-                        // Fall back to lossy decoding
-                        let s = String::from_utf8_lossy(var_data);
-                        let s = if trim {
-                            s.trim_end().to_string()
-                        } else {
-                            s.into_owned()
-                        };
-                        ObsValue::Character(if s.is_empty() { None } else { Some(s) })
-                    }
-                }
+                let s = decode_text(var_data, self.options.text_mode, trim).map_err(|e| {
+                    Error::encoding(format!("cannot decode variable '{}': {e}", var.nname))
+                })?;
+                ObsValue::Character(if s.is_empty() { None } else { Some(s) })
             };
 
             values.push(value);
@@ -171,9 +173,38 @@ impl<'a, R: Read> ObservationReader<'a, R> {
     }
 }
 
+/// Selects the variables named in `columns`, keeping file order.
+///
+/// Names are matched case-insensitively. The NAMESTR positions are kept, so
+/// only the selected variables are decoded from each row.
+fn project_variables(
+    variables: &[NamestrV5],
+    columns: Option<&[String]>,
+) -> Result<Vec<NamestrV5>> {
+    let Some(columns) = columns else {
+        return Ok(variables.to_vec());
+    };
+
+    if let Some(missing) = columns
+        .iter()
+        .find(|c| !variables.iter().any(|v| v.nname.eq_ignore_ascii_case(c)))
+    {
+        return Err(Error::invalid_schema(format!(
+            "column '{missing}' not found in member"
+        )));
+    }
+
+    Ok(variables
+        .iter()
+        .filter(|v| columns.iter().any(|c| v.nname.eq_ignore_ascii_case(c)))
+        .cloned()
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TextMode;
     use std::io::Cursor;
 
     fn make_namestr(name: &str, ntype: i16, length: i16, position: i64) -> NamestrV5 {
@@ -207,4 +238,52 @@ mod tests {
         let reader = ObservationReader::new(&mut cursor, &vars, &options);
         assert!(reader.is_ok());
     }
+
+    #[test]
+    fn test_projection_decodes_selected_variables() {
+        let mut row = crate::xpt::v5::encoding::encode_ibm_float(Some(1.0)).to_vec();
+        row.extend_from_slice(b"ABC");
+        row.extend_from_slice(&crate::xpt::v5::encoding::encode_ibm_float(Some(2.0)));
+        let vars = vec![
+            make_namestr("A", 1, 8, 0),
+            make_namestr("B", 2, 3, 8),
+            make_namestr("C", 1, 8, 11),
+        ];
+        let options = ReadOptions::default().with_columns(vec!["c".into(), "B".into()]);
+
+        let mut reader = ObservationReader::new(Cursor::new(row), &vars, &options).unwrap();
+        let names: Vec<_> = reader
+            .variables()
+            .iter()
+            .map(|v| v.nname.as_str())
+            .collect();
+        assert_eq!(names, vec!["B", "C"]);
+
+        let values = reader.read_observation().unwrap().unwrap();
+        assert!(matches!(&values[0], ObsValue::Character(Some(s)) if s == "ABC"));
+        assert!(matches!(values[1], ObsValue::Numeric(Some(v)) if v == 2.0));
+
+        let options = ReadOptions::default().with_columns(vec!["MISSING".into()]);
+        assert!(ObservationReader::new(Cursor::new(vec![]), &vars, &options).is_err());
+    }
+
+    #[test]
+    fn test_text_modes() {
+        let row = b"caf\xE9  ".to_vec();
+        let vars = vec![make_namestr("NAME", 2, 6, 0)];
+
+        let options = ReadOptions::default().with_text_mode(TextMode::StrictUtf8);
+        let mut reader = ObservationReader::new(Cursor::new(row.clone()), &vars, &options).unwrap();
+        assert!(matches!(
+            reader.read_observation(),
+            Err(Error::Encoding { message }) if message.contains("NAME")
+        ));
+
+        let options = ReadOptions::default()
+            .with_text_mode(TextMode::Latin1)
+            .with_preserve_blanks(true);
+        let mut reader = ObservationReader::new(Cursor::new(row), &vars, &options).unwrap();
+        let values = reader.read_observation().unwrap().unwrap();
+        assert!(matches!(&values[0], ObsValue::Character(Some(s)) if s == "café  "));
+    }
 }
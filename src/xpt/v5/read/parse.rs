@@ -1,14 +1,21 @@
-//! XPT v5 header parsing.
+//! XPT header parsing.
 //!
-//! This module handles parsing the header sections of XPT v5 files.
+//! This module handles parsing the header sections of XPT v5 files. V8 files
+//! share the same overall layout and are parsed here as well, delegating the
+//! V8-specific records to [`crate::xpt::v8`].
 
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 
+use crate::config::TextMode;
 use crate::error::{Error, Result};
+use crate::xpt::XptVersion;
 use crate::xpt::v5::constants::{
-    LIBRARY_HEADER, MEMBER_HEADER, NAMESTR_HEADER, NAMESTR_LEN, OBS_HEADER, RECORD_LEN,
+    LIBRARY_HEADER, MEMBER_HEADER, NAMESTR_HEADER, NAMESTR_LEN, OBS_HEADER, PAD_CHAR, RECORD_LEN,
 };
+use crate::xpt::v5::encoding::decode_text;
 use crate::xpt::v5::namestr::{NamestrV5, unpack_namestr};
+use crate::xpt::v8;
+use crate::xpt::v8::labels::{LabelSection, read_label_section};
 
 use super::reader::XptInfo;
 
@@ -23,27 +30,36 @@ pub struct XptMemberInfo {
     pub variables: Vec<NamestrV5>,
     /// File offset to the observation data.
     pub obs_offset: u64,
-    /// Number of observations (if known).
+    /// Length of the observation data in bytes, including trailing padding.
+    pub obs_len: u64,
+    /// Number of observations.
+    ///
+    /// Computed from the length of the observation data, see
+    /// [`obs_len`](Self::obs_len): trailing blank rows that fit within the
+    /// padding of the last record are counted as padding.
     pub obs_count: usize,
     /// Row length in bytes.
     pub row_len: usize,
 }
 
-/// Parses the XPT file header and returns file information.
+/// Parses the XPT file header and the first member, and returns file
+/// information.
+///
+/// Members after the first are only found by scanning the observation data
+/// before them, see [`find_members`]. Until then, the first member is taken
+/// to extend to the end of the file.
+///
+/// Dataset names and labels, and the NAMESTR text fields, are decoded
+/// with `mode`.
 ///
 /// # Errors
 ///
-/// Returns an error if the file is not a valid XPT v5 file.
-pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<XptInfo> {
-    // Read and validate library header
+/// Returns an error if the file is not a valid XPT v5 or v8 file.
+pub fn parse_header<R: Read + Seek>(reader: &mut R, mode: TextMode) -> Result<XptInfo> {
+    // Read the library header and detect the version from it
     let mut header_buf = [0u8; RECORD_LEN];
     reader.read_exact(&mut header_buf).map_err(Error::Io)?;
-
-    if &header_buf != LIBRARY_HEADER {
-        return Err(Error::corrupt(
-            "invalid library header - not an XPT v5 file",
-        ));
-    }
+    let version = detect_version(&header_buf)?;
 
     // Read first real header record (contains SAS identifier)
     // Per SAS spec: created timestamp is at bytes 64-79
@@ -55,31 +71,18 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<XptInfo> {
     reader.read_exact(&mut header_buf).map_err(Error::Io)?;
     let modified = extract_timestamp(&header_buf, 0, 16);
 
-    // Parse members
+    // Parse the first member. Anything other than a member header after the
+    // library header (padding, unknown records) ends the file.
     let mut members = Vec::new();
-
-    loop {
-        // Try to read the next header
-        match reader.read_exact(&mut header_buf) {
-            Ok(()) => {}
-            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
-            Err(e) => return Err(Error::Io(e)),
-        }
-
-        // Check if this is a member header
-        if &header_buf == MEMBER_HEADER {
-            let member = parse_member(reader)?;
-            members.push(member);
-        } else if header_buf.iter().all(|&b| b == 0x20 || b == 0) {
-            // End of file (padding or EOF)
-            break;
-        } else {
-            // Unknown record type - might be end of file
-            break;
-        }
+    if read_record(reader, &mut header_buf)? && is_member_header(&header_buf, version) {
+        let mut member = parse_member(reader, version, mode)?;
+        let obs_end = reader.seek(SeekFrom::End(0)).map_err(Error::Io)?;
+        set_obs_end(reader, &mut member, obs_end)?;
+        members.push(member);
     }
 
     Ok(XptInfo {
+        version,
         members,
         library_label: None,
         created,
@@ -87,37 +90,168 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<XptInfo> {
     })
 }
 
+/// Finds the members after the last member of `info`, by scanning its
+/// observation data for the next member header.
+///
+/// The observation data of each member runs until the next member header or
+/// the end of the file; the extent of the last member of `info` is corrected
+/// when a member follows it.
+///
+/// # Errors
+///
+/// Returns an error if reading fails or a member header cannot be parsed.
+pub(crate) fn find_members<R: Read + Seek>(
+    reader: &mut R,
+    info: &mut XptInfo,
+    mode: TextMode,
+) -> Result<()> {
+    let Some(last) = info.members.last() else {
+        return Ok(());
+    };
+    reader
+        .seek(SeekFrom::Start(last.obs_offset))
+        .map_err(Error::Io)?;
+
+    let mut header_buf = [0u8; RECORD_LEN];
+    while skip_observations(reader, &mut header_buf, info.version)? {
+        let obs_end = reader.stream_position().map_err(Error::Io)? - RECORD_LEN as u64;
+        if let Some(last) = info.members.last_mut() {
+            set_obs_end(reader, last, obs_end)?;
+        }
+
+        let mut member = parse_member(reader, info.version, mode)?;
+        let obs_end = reader.seek(SeekFrom::End(0)).map_err(Error::Io)?;
+        set_obs_end(reader, &mut member, obs_end)?;
+        reader
+            .seek(SeekFrom::Start(member.obs_offset))
+            .map_err(Error::Io)?;
+        info.members.push(member);
+    }
+    Ok(())
+}
+
+/// Sets the length and count of a member's observations from the end of its
+/// observation data, leaving the reader where it was.
+fn set_obs_end<R: Read + Seek>(
+    reader: &mut R,
+    member: &mut XptMemberInfo,
+    obs_end: u64,
+) -> Result<()> {
+    member.obs_len = obs_end.saturating_sub(member.obs_offset);
+    member.obs_count = read_obs_count(reader, member, obs_end)?;
+    Ok(())
+}
+
+/// Reads the next 80-byte record, returning `false` at end of file.
+fn read_record<R: Read>(reader: &mut R, buf: &mut [u8; RECORD_LEN]) -> Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(Error::Io(e)),
+    }
+}
+
+/// Skips observation records until the next member header.
+///
+/// Returns `true` if a member header was found (and consumed into `buf`),
+/// `false` at end of file.
+fn skip_observations<R: Read>(
+    reader: &mut R,
+    buf: &mut [u8; RECORD_LEN],
+    version: XptVersion,
+) -> Result<bool> {
+    while read_record(reader, buf)? {
+        if is_member_header(buf, version) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Detects the XPT version from the library header record.
+fn detect_version(header: &[u8; RECORD_LEN]) -> Result<XptVersion> {
+    if header == LIBRARY_HEADER {
+        Ok(XptVersion::V5)
+    } else if header.starts_with(&v8::constants::LIBRARY_HEADER[..v8::constants::HEADER_PREFIX_LEN])
+    {
+        Ok(XptVersion::V8)
+    } else {
+        Err(Error::corrupt(
+            "invalid library header - not an XPT v5 or v8 file",
+        ))
+    }
+}
+
+/// Returns `true` if the record starts a new member for the given version.
+fn is_member_header(record: &[u8; RECORD_LEN], version: XptVersion) -> bool {
+    match version {
+        XptVersion::V8 => {
+            record.starts_with(&v8::constants::MEMBER_HEADER[..v8::constants::HEADER_PREFIX_LEN])
+        }
+        _ => record == MEMBER_HEADER,
+    }
+}
+
 /// Parses a single member (dataset) from the file.
-fn parse_member<R: Read + Seek>(reader: &mut R) -> Result<XptMemberInfo> {
+fn parse_member<R: Read + Seek>(
+    reader: &mut R,
+    version: XptVersion,
+    mode: TextMode,
+) -> Result<XptMemberInfo> {
     let mut buf = [0u8; RECORD_LEN];
+    let is_v8 = version.is_v8();
 
-    // Read and verify DSCRPTR header
+    // Read and verify DSCRPTR (V8: DSCPTV8) header
     reader.read_exact(&mut buf).map_err(Error::Io)?;
-    if !buf.starts_with(b"HEADER RECORD*******DSCRPTR") {
-        return Err(Error::corrupt("expected DSCRPTR header"));
+    let descriptor_header: &[u8] = if is_v8 {
+        &v8::constants::MEMBER_HEADER_DATA[..v8::constants::HEADER_PREFIX_LEN]
+    } else {
+        b"HEADER RECORD*******DSCRPTR"
+    };
+    if !buf.starts_with(descriptor_header) {
+        return Err(Error::corrupt(if is_v8 {
+            "expected DSCPTV8 header"
+        } else {
+            "expected DSCRPTR header"
+        }));
     }
 
     // Read member descriptor data record 1 (contains dataset name)
+    // V5 names are 8 bytes at 8-15, V8 names are 32 bytes at 8-39
     reader.read_exact(&mut buf).map_err(Error::Io)?;
-    let name = String::from_utf8_lossy(&buf[8..16]).trim().to_string();
+    let name_end = if is_v8 {
+        8 + v8::constants::DATASET_NAME_LEN
+    } else {
+        16
+    };
+    let name = decode_field(&buf[8..name_end], mode)?;
 
     // Read member descriptor data record 2 (contains label)
     reader.read_exact(&mut buf).map_err(Error::Io)?;
     let label = {
-        let l = String::from_utf8_lossy(&buf[32..72]).trim().to_string();
+        let l = decode_field(&buf[32..72], mode)?;
         if l.is_empty() { None } else { Some(l) }
     };
 
     // Read NAMESTR header
     reader.read_exact(&mut buf).map_err(Error::Io)?;
 
-    if !buf.starts_with(&NAMESTR_HEADER[..]) {
+    let namestr_header: &[u8] = if is_v8 {
+        v8::constants::NAMESTR_HEADER_STEM
+    } else {
+        &NAMESTR_HEADER[..]
+    };
+    if !buf.starts_with(namestr_header) {
         return Err(Error::corrupt("expected NAMESTR header"));
     }
 
     // Parse number of variables from NAMESTR header
-    // Per SAS spec: nvars is a 4-digit field at bytes 54-57 (right-aligned with leading zeros)
-    let nvars_str = String::from_utf8_lossy(&buf[54..58]).trim().to_string();
+    // Per SAS spec: nvars is a 4-digit field at bytes 54-57 (right-aligned with leading zeros).
+    // V8 allows more variables, so the whole zero-filled field at bytes 48-57 is used.
+    let nvars_start = if is_v8 { 48 } else { 54 };
+    let nvars_str = String::from_utf8_lossy(&buf[nvars_start..58])
+        .trim()
+        .to_string();
     let nvars: usize = nvars_str
         .parse()
         .map_err(|_| Error::corrupt(format!("invalid variable count: {}", nvars_str)))?;
@@ -139,39 +273,251 @@ fn parse_member<R: Read + Seek>(reader: &mut R) -> Result<XptMemberInfo> {
 
         let mut namestr_buf = [0u8; NAMESTR_LEN];
         namestr_buf.copy_from_slice(&namestr_data[start..end]);
-        let namestr = unpack_namestr(&namestr_buf)?;
+        let mut namestr = unpack_namestr(&namestr_buf, mode)?;
+        if is_v8 {
+            v8::namestr::apply_long_name(&mut namestr, mode)?;
+        }
         variables.push(namestr);
     }
 
     // Calculate row length
     let row_len: usize = variables.iter().map(NamestrV5::length).sum();
 
-    // Read OBS header
+    // Read OBS header, preceded in V8 files by an optional label section
     reader.read_exact(&mut buf).map_err(Error::Io)?;
 
-    if !buf.starts_with(&OBS_HEADER[..54]) {
+    if is_v8 {
+        while let Some(section) = LabelSection::from_header(&buf) {
+            read_label_section(reader, section, &buf, &mut variables, mode)?;
+            reader.read_exact(&mut buf).map_err(Error::Io)?;
+        }
+    }
+
+    let obs_header = if is_v8 {
+        v8::constants::OBS_HEADER
+    } else {
+        OBS_HEADER
+    };
+    if !buf.starts_with(&obs_header[..54]) {
         return Err(Error::corrupt("expected OBS header"));
     }
 
     // Record the offset to observation data
     let obs_offset = reader.stream_position().map_err(Error::Io)?;
 
-    // Note: obs_count is set to 0 here because the actual observation count
-    // is determined during reading by detecting padding rows (all 0x20 bytes).
-    // XPT v5 doesn't store an explicit observation count, and files are padded
-    // to 80-byte record boundaries with spaces.
-    let obs_count = 0;
-
+    // The length and count of the observations are known once the end of
+    // the observation data is found
     Ok(XptMemberInfo {
         name,
         label,
         variables,
         obs_offset,
-        obs_count,
+        obs_len: 0,
+        obs_count: 0,
         row_len,
     })
 }
 
+/// Reads the last record of a member's observation data and counts the
+/// observations, leaving the reader where it was.
+fn read_obs_count<R: Read + Seek>(
+    reader: &mut R,
+    member: &XptMemberInfo,
+    obs_end: u64,
+) -> Result<usize> {
+    let position = reader.stream_position().map_err(Error::Io)?;
+    let mut tail = vec![0u8; member.obs_len.min(RECORD_LEN as u64) as usize];
+    reader
+        .seek(SeekFrom::Start(obs_end - tail.len() as u64))
+        .map_err(Error::Io)?;
+    reader.read_exact(&mut tail).map_err(Error::Io)?;
+    reader.seek(SeekFrom::Start(position)).map_err(Error::Io)?;
+
+    Ok(count_observations(member.obs_len, member.row_len, &tail))
+}
+
+/// Returns the number of observations in `obs_len` bytes of observation
+/// data, given the last bytes of the data in `tail`.
+///
+/// XPT files don't store the count: the rows are written back to back and
+/// the last record is padded with blanks. Every whole row is an observation,
+/// except for trailing blank rows that end less than a record from the end
+/// of the data, as those are indistinguishable from padding. Blank rows
+/// elsewhere are observations.
+fn count_observations(obs_len: u64, row_len: usize, tail: &[u8]) -> usize {
+    if row_len == 0 {
+        return 0;
+    }
+    let row_len = row_len as u64;
+    let tail_start = obs_len - tail.len() as u64;
+
+    let mut count = obs_len / row_len;
+    while count > 0 {
+        let start = (count - 1) * row_len;
+        if obs_len - start >= RECORD_LEN as u64 || start < tail_start {
+            break;
+        }
+        let row = &tail[(start - tail_start) as usize..(start - tail_start + row_len) as usize];
+        if row.iter().any(|&b| b != PAD_CHAR) {
+            break;
+        }
+        count -= 1;
+    }
+
+    count as usize
+}
+
+/// The observation data of a member, read up to the last requested row.
+///
+/// While the members after it have not been found, the end of a member's
+/// observation data is not known: the data is then read a record ahead, and
+/// ends before the next member header or at the end of the file, leaving
+/// out the padding of the last record.
+pub(crate) struct MemberData<R> {
+    reader: R,
+    /// Bytes left until the last requested row.
+    remaining: u64,
+    /// The search for the end of the data, if it is not known.
+    search: Option<EndSearch>,
+}
+
+/// Reads a member's observation data a record ahead to find where it ends.
+struct EndSearch {
+    version: XptVersion,
+    row_len: u64,
+    /// Offset of `record` from the start of the observation data.
+    record_offset: u64,
+    /// The record being read, and the position of the next byte in it.
+    record: Vec<u8>,
+    pos: usize,
+    /// The record after it; empty at the end of the data.
+    next: Vec<u8>,
+}
+
+impl<R: Read> MemberData<R> {
+    /// Reads `len` bytes of observation data from `reader`.
+    pub(crate) fn new(reader: R, len: u64) -> Self {
+        Self {
+            reader,
+            remaining: len,
+            search: None,
+        }
+    }
+
+    /// Reads at most `len` bytes of observation data of unknown extent,
+    /// starting `offset` bytes into the data.
+    ///
+    /// `reader` must be at the start of the record containing `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the first records cannot be read.
+    pub(crate) fn searching(
+        mut reader: R,
+        offset: u64,
+        len: u64,
+        row_len: usize,
+        version: XptVersion,
+    ) -> Result<Self> {
+        let record_offset = offset - offset % RECORD_LEN as u64;
+        let record = read_data_record(&mut reader, version)?;
+        let next = if record.is_empty() {
+            Vec::new()
+        } else {
+            read_data_record(&mut reader, version)?
+        };
+        let search = EndSearch {
+            version,
+            row_len: row_len as u64,
+            record_offset,
+            record,
+            pos: (offset - record_offset) as usize,
+            next,
+        };
+        Ok(Self {
+            reader,
+            remaining: len,
+            search: Some(search),
+        })
+    }
+}
+
+impl EndSearch {
+    /// Returns the end of the bytes of `record` that hold observations.
+    fn record_end(&self) -> usize {
+        if !self.next.is_empty() {
+            return self.record.len();
+        }
+        // The last record: trailing blank rows within it are padding
+        let obs_len = self.record_offset + self.record.len() as u64;
+        let count = count_observations(obs_len, self.row_len as usize, &self.record) as u64;
+        let end = (count * self.row_len).saturating_sub(self.record_offset);
+        (end as usize).min(self.record.len())
+    }
+
+    /// Reads observation bytes into `buf`, returning `0` at the end of the
+    /// data.
+    fn read<R: Read>(&mut self, reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let end = self.record_end();
+            if self.pos < end {
+                let n = buf.len().min(end - self.pos);
+                buf[..n].copy_from_slice(&self.record[self.pos..self.pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+            if self.next.is_empty() {
+                return Ok(0);
+            }
+            self.record_offset += self.record.len() as u64;
+            self.record = std::mem::take(&mut self.next);
+            self.pos = 0;
+            self.next = read_data_record(reader, self.version).map_err(std::io::Error::other)?;
+        }
+    }
+}
+
+impl<R: Read> Read for MemberData<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        if len == 0 {
+            return Ok(0);
+        }
+        let n = match &mut self.search {
+            Some(search) => search.read(&mut self.reader, &mut buf[..len])?,
+            None => self.reader.read(&mut buf[..len])?,
+        };
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+/// Reads the next record of observation data, which is empty at a member
+/// header or the end of the file, and shorter than a record if the file
+/// ends within it.
+fn read_data_record<R: Read>(reader: &mut R, version: XptVersion) -> Result<Vec<u8>> {
+    let mut record = Vec::with_capacity(RECORD_LEN);
+    reader
+        .by_ref()
+        .take(RECORD_LEN as u64)
+        .read_to_end(&mut record)
+        .map_err(Error::Io)?;
+    if let Ok(header) = <&[u8; RECORD_LEN]>::try_from(record.as_slice())
+        && is_member_header(header, version)
+    {
+        record.clear();
+    }
+    Ok(record)
+}
+
+/// Decodes a blank-padded header text field.
+fn decode_field(bytes: &[u8], mode: TextMode) -> Result<String> {
+    let s = decode_text(bytes, mode, true).map_err(|e| Error::encoding(e.to_string()))?;
+    Ok(s.trim_start().to_string())
+}
+
 /// Extracts a timestamp string from a header buffer.
 fn extract_timestamp(buf: &[u8], start: usize, end: usize) -> Option<String> {
     if end > buf.len() {
@@ -193,6 +539,26 @@ mod tests {
         assert_eq!(ts, Some("15JUN24:14:30:45".to_string()));
     }
 
+    #[test]
+    fn test_count_observations() {
+        // Three 8-byte rows padded to a record
+        let mut data = [b' '; 80];
+        data[..8].copy_from_slice(b"A       ");
+        data[16..24].copy_from_slice(b"B       ");
+        assert_eq!(count_observations(80, 8, &data), 3);
+
+        // Trailing blank rows within the padding can't be told apart from
+        // it, but a record holds at least one row
+        assert_eq!(count_observations(80, 8, &[b' '; 80]), 1);
+
+        // Blank rows longer than the padding are observations
+        let data = [b' '; 240];
+        assert_eq!(count_observations(240, 100, &data[160..]), 2);
+        assert_eq!(count_observations(240, 40, &data[160..]), 5);
+        assert_eq!(count_observations(0, 8, &[]), 0);
+        assert_eq!(count_observations(80, 0, &data[..80]), 0);
+    }
+
     #[test]
     fn test_parse_dm_xpt_header() {
         let path = std::path::Path::new("tests/data/dm.xpt");
@@ -203,7 +569,7 @@ mod tests {
         let file = std::fs::File::open(path).expect("Failed to open dm.xpt");
         let mut reader = std::io::BufReader::new(file);
 
-        let info = parse_header(&mut reader).expect("parse_header failed");
+        let info = parse_header(&mut reader, TextMode::default()).expect("parse_header failed");
 
         // Verify parsing results
         assert_eq!(info.members.len(), 1);
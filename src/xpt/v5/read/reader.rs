@@ -6,19 +6,35 @@ use std::fs::File;
 use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::config::ReadOptions;
+use crate::config::{ReadOptions, TextMode};
 use crate::dataset::{Column, ColumnData, Dataset, Format};
 use crate::error::{Error, Result};
+use crate::xpt::XptVersion;
+use crate::xpt::v5::constants::RECORD_LEN;
+use crate::xpt::v5::encoding::SasMissingValue;
+use crate::xpt::v5::namestr::NamestrV5;
 
 use super::obs::ObservationReader;
-use super::parse::{XptMemberInfo, parse_header};
+use super::parse::{MemberData, XptMemberInfo, find_members, parse_header};
 
 /// Information about an XPT file.
 ///
 /// This struct provides metadata about the file without reading all data.
+/// Members after the first are only found by scanning the observation data
+/// before them; see [`XptReaderBuilder::find_members`].
+///
+/// [`XptReaderBuilder::find_members`]: crate::XptReaderBuilder::find_members
 #[derive(Debug, Clone)]
 pub struct XptInfo {
-    /// The members (datasets) in the file.
+    /// The transport format version, detected from the library header.
+    pub version: XptVersion,
+    /// The members (datasets) found in the file.
+    ///
+    /// Until the members after it are found, the last member is taken to
+    /// extend to the end of the file: in a file with several members, its
+    /// [`obs_len`](XptMemberInfo::obs_len) and
+    /// [`obs_count`](XptMemberInfo::obs_count) then include the members
+    /// after it.
     pub members: Vec<XptMemberInfo>,
     /// The library label (if present).
     pub library_label: Option<String>,
@@ -49,6 +65,10 @@ impl XptInfo {
 pub struct XptReader<R: Read + Seek> {
     reader: BufReader<R>,
     file_info: XptInfo,
+    /// The text mode `file_info` was decoded with.
+    text_mode: TextMode,
+    /// Whether the members after the first have been found.
+    members_found: bool,
 }
 
 impl<R: Read + Seek> XptReader<R> {
@@ -58,35 +78,131 @@ impl<R: Read + Seek> XptReader<R> {
     ///
     /// Returns an error if the file header cannot be parsed.
     pub fn new(reader: R) -> Result<Self> {
+        Self::with_text_mode(reader, TextMode::default())
+    }
+
+    /// Creates a new XPT reader that decodes the header text with `text_mode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file header cannot be parsed.
+    pub fn with_text_mode(reader: R, text_mode: TextMode) -> Result<Self> {
         let mut buf_reader = BufReader::new(reader);
-        let file_info = parse_header(&mut buf_reader)?;
+        let file_info = parse_header(&mut buf_reader, text_mode)?;
 
         Ok(Self {
             reader: buf_reader,
             file_info,
+            text_mode,
+            members_found: false,
         })
     }
 
+    /// Re-decodes the header text if `mode` differs from the current mode.
+    ///
+    /// Names and labels are decoded when the reader is opened, before the
+    /// read options are known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the headers cannot be re-read or decoded.
+    pub(crate) fn decode_headers_with(&mut self, mode: TextMode) -> Result<()> {
+        if mode != self.text_mode {
+            self.reader.seek(SeekFrom::Start(0)).map_err(Error::Io)?;
+            self.file_info = parse_header(&mut self.reader, mode)?;
+            self.text_mode = mode;
+            self.members_found = false;
+        }
+        Ok(())
+    }
+
     /// Returns file information.
+    ///
+    /// Only the first member is included until [`find_members`](Self::find_members)
+    /// has been called.
     #[must_use]
     pub fn file_info(&self) -> &XptInfo {
         &self.file_info
     }
 
+    /// Finds the members after the first, by scanning the observation data
+    /// of the members before them. The file is only scanned once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or a member header is invalid.
+    pub fn find_members(&mut self) -> Result<&XptInfo> {
+        if !self.members_found {
+            find_members(&mut self.reader, &mut self.file_info, self.text_mode)?;
+            self.members_found = true;
+        }
+        Ok(&self.file_info)
+    }
+
+    /// Returns the underlying reader, for scanning the raw file.
+    pub(crate) fn get_mut(&mut self) -> &mut BufReader<R> {
+        &mut self.reader
+    }
+
+    /// Reads the observations of a member in batches, passing each batch to
+    /// `f`.
+    ///
+    /// Unlike [`member_batches`](Self::member_batches), the reader is kept,
+    /// so several members can be read in turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or `f` returns an error.
+    pub(crate) fn for_each_batch(
+        &mut self,
+        member: &XptMemberInfo,
+        options: &ReadOptions,
+        batch_size: usize,
+        mut f: impl FnMut(Dataset) -> Result<()>,
+    ) -> Result<()> {
+        self.decode_headers_with(options.text_mode)?;
+        let data = member_data(
+            &mut self.reader,
+            member,
+            options,
+            self.members_found,
+            self.file_info.version,
+        )?;
+        let mut obs_reader = ObservationReader::new(data, &member.variables, options)?;
+
+        loop {
+            let columns = read_columns(&mut obs_reader, batch_size.max(1))?;
+            let nrows = columns.first().map_or(0, |c| c.data.len());
+            if nrows == 0 {
+                return Ok(());
+            }
+            f(build_dataset(member, obs_reader.variables(), columns)?)?;
+        }
+    }
+
+    /// Returns the member with the given name (case-insensitive).
+    ///
+    /// The file is only scanned for further members if the name does not
+    /// match the first member.
+    fn member(&mut self, name: &str) -> Result<XptMemberInfo> {
+        let first = self.file_info.members.first();
+        let member = match first.filter(|m| m.name.eq_ignore_ascii_case(name)) {
+            Some(member) => Some(member),
+            None => self.find_members()?.find_member(name),
+        };
+        member.cloned().ok_or_else(|| Error::MemberNotFound {
+            domain_code: name.to_string(),
+        })
+    }
+
     /// Reads a specific member by name.
     ///
     /// # Errors
     ///
     /// Returns an error if the member is not found or cannot be read.
     pub(crate) fn read_member(&mut self, name: &str, options: &ReadOptions) -> Result<Dataset> {
-        let member = self
-            .file_info
-            .find_member(name)
-            .ok_or_else(|| Error::MemberNotFound {
-                domain_code: name.to_string(),
-            })?
-            .clone();
-
+        self.decode_headers_with(options.text_mode)?;
+        let member = self.member(name)?;
         self.read_member_data(&member, options)
     }
 
@@ -96,7 +212,8 @@ impl<R: Read + Seek> XptReader<R> {
     ///
     /// Returns an error if any member cannot be read.
     pub(crate) fn read_all(&mut self, options: &ReadOptions) -> Result<Vec<Dataset>> {
-        let members: Vec<_> = self.file_info.members.clone();
+        self.decode_headers_with(options.text_mode)?;
+        let members = self.find_members()?.members.clone();
         let mut datasets = Vec::with_capacity(members.len());
 
         for member in members {
@@ -113,94 +230,285 @@ impl<R: Read + Seek> XptReader<R> {
         member: &XptMemberInfo,
         options: &ReadOptions,
     ) -> Result<Dataset> {
-        // Seek to the observation data
-        self.reader
-            .seek(SeekFrom::Start(member.obs_offset))
+        let data = member_data(
+            &mut self.reader,
+            member,
+            options,
+            self.members_found,
+            self.file_info.version,
+        )?;
+        let mut obs_reader = ObservationReader::new(data, &member.variables, options)?;
+
+        let row_limit = options.row_limit.unwrap_or(usize::MAX);
+        let columns = read_columns(&mut obs_reader, row_limit)?;
+        build_dataset(member, obs_reader.variables(), columns)
+    }
+
+    /// Consumes the reader and returns an iterator of row batches for a member.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the member is not found.
+    pub(crate) fn member_batches(
+        mut self,
+        name: &str,
+        options: &ReadOptions,
+        batch_size: usize,
+    ) -> Result<XptBatchReader<R>> {
+        self.decode_headers_with(options.text_mode)?;
+        let member = self.member(name)?;
+
+        let data = member_data(
+            self.reader,
+            &member,
+            options,
+            self.members_found,
+            self.file_info.version,
+        )?;
+        let obs_reader = ObservationReader::new(data, &member.variables, options)?;
+
+        Ok(XptBatchReader {
+            obs_reader,
+            member,
+            batch_size: batch_size.max(1),
+            rows_remaining: options.row_limit.unwrap_or(usize::MAX),
+            done: false,
+        })
+    }
+}
+
+/// An iterator over a member's observations in fixed-size [`Dataset`] batches.
+///
+/// Created by [`XptReaderBuilder::batches`](crate::XptReaderBuilder::batches)
+/// or [`XptReaderBuilder::member_batches`](crate::XptReaderBuilder::member_batches).
+/// Only one batch is held in memory at a time. Each batch carries the full
+/// column metadata (labels, formats, lengths); the last batch may be shorter
+/// than the batch size. A member without observations yields no batches.
+///
+/// # Example
+///
+/// ```no_run
+/// use xportrs::Xpt;
+///
+/// let mut total = 0;
+/// for batch in Xpt::reader("lb.xpt")?.batches(100_000)? {
+///     let batch = batch?;
+///     total += batch.nrows();
+/// }
+/// println!("Rows: {total}");
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+pub struct XptBatchReader<R: Read + Seek = BufReader<File>> {
+    obs_reader: ObservationReader<MemberData<BufReader<R>>>,
+    member: XptMemberInfo,
+    batch_size: usize,
+    rows_remaining: usize,
+    done: bool,
+}
+
+impl<R: Read + Seek> XptBatchReader<R> {
+    /// Returns information about the member being read.
+    #[must_use]
+    pub fn member_info(&self) -> &XptMemberInfo {
+        &self.member
+    }
+
+    /// Reads the next batch, or `None` when all rows have been read.
+    fn next_batch(&mut self) -> Result<Option<Dataset>> {
+        let limit = self.batch_size.min(self.rows_remaining);
+        let columns = read_columns(&mut self.obs_reader, limit)?;
+
+        let nrows = columns.first().map_or(0, |c| c.data.len());
+        if nrows == 0 {
+            return Ok(None);
+        }
+        self.rows_remaining -= nrows;
+
+        build_dataset(&self.member, self.obs_reader.variables(), columns).map(Some)
+    }
+}
+
+impl<R: Read + Seek> Iterator for XptBatchReader<R> {
+    type Item = Result<Dataset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_batch() {
+            Ok(Some(batch)) => Some(Ok(batch)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> std::fmt::Debug for XptBatchReader<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XptBatchReader")
+            .field("member", &self.member.name)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Seeks to the first requested row of a member and returns a reader of its
+/// observation data.
+///
+/// Rows are fixed-width and start at `obs_offset`, so skipped rows are
+/// seeked past rather than read. The data ends with the last observation,
+/// before the padding; until the members after the first are found, that
+/// end is found while reading.
+fn member_data<B: Read + Seek>(
+    mut reader: B,
+    member: &XptMemberInfo,
+    options: &ReadOptions,
+    members_found: bool,
+    version: XptVersion,
+) -> Result<MemberData<B>> {
+    let rows = member.obs_count.saturating_sub(options.skip_rows) as u64;
+    let skip = member.obs_count.min(options.skip_rows) as u64;
+    let row_len = member.row_len as u64;
+    let (offset, len) = (skip * row_len, rows * row_len);
+
+    if members_found {
+        reader
+            .seek(SeekFrom::Start(member.obs_offset + offset))
             .map_err(Error::Io)?;
+        return Ok(MemberData::new(reader, len));
+    }
 
-        // Create observation reader
-        let mut obs_reader = ObservationReader::new(&mut self.reader, &member.variables, options)?;
+    let record_start = offset - offset % RECORD_LEN as u64;
+    reader
+        .seek(SeekFrom::Start(member.obs_offset + record_start))
+        .map_err(Error::Io)?;
+    MemberData::searching(reader, offset, len, member.row_len, version)
+}
 
-        // Read observations
-        let row_limit = options.row_limit.unwrap_or(usize::MAX);
-        let mut rows_read = 0;
+/// The values read for one variable.
+struct ColumnValues {
+    data: ColumnData,
+    /// Special missing codes, allocated on the first special missing value.
+    special_missing: Option<Vec<Option<SasMissingValue>>>,
+}
 
-        // Initialize column data vectors
-        let mut columns: Vec<ColumnData> = member
-            .variables
-            .iter()
-            .map(|v| {
-                if v.xpt_type().is_numeric() {
-                    ColumnData::F64(Vec::new())
-                } else {
-                    ColumnData::String(Vec::new())
-                }
-            })
-            .collect();
-
-        // Read rows
-        while rows_read < row_limit {
-            match obs_reader.read_observation()? {
-                Some(row) => {
-                    for (i, value) in row.into_iter().enumerate() {
-                        match (&mut columns[i], value) {
-                            (ColumnData::F64(vec), ObsValue::Numeric(v)) => vec.push(v),
-                            (ColumnData::String(vec), ObsValue::Character(v)) => vec.push(v),
-                            _ => {
-                                return Err(Error::corrupt("type mismatch in observation data"));
-                            }
-                        }
-                    }
-                    rows_read += 1;
+impl ColumnValues {
+    /// Appends one observation value.
+    fn push(&mut self, value: ObsValue) -> Result<()> {
+        match (&mut self.data, value) {
+            (ColumnData::F64(vec), ObsValue::SpecialMissing(missing)) => {
+                let rows = vec.len();
+                vec.push(None);
+                self.special_missing
+                    .get_or_insert_with(|| vec![None; rows])
+                    .push(Some(missing));
+                return Ok(());
+            }
+            (ColumnData::F64(vec), ObsValue::Numeric(v)) => vec.push(v),
+            (ColumnData::String(vec), ObsValue::Character(v)) => vec.push(v),
+            _ => return Err(Error::corrupt("type mismatch in observation data")),
+        }
+        if let Some(codes) = &mut self.special_missing {
+            codes.push(None);
+        }
+        Ok(())
+    }
+}
+
+/// Reads up to `limit` observations into one [`ColumnValues`] per variable.
+fn read_columns<R: Read>(
+    obs_reader: &mut ObservationReader<R>,
+    limit: usize,
+) -> Result<Vec<ColumnValues>> {
+    let mut rows_read = 0;
+
+    // Initialize column data vectors
+    let mut columns: Vec<ColumnValues> = obs_reader
+        .variables()
+        .iter()
+        .map(|v| ColumnValues {
+            data: if v.xpt_type().is_numeric() {
+                ColumnData::F64(Vec::new())
+            } else {
+                ColumnData::String(Vec::new())
+            },
+            special_missing: None,
+        })
+        .collect();
+
+    // Read rows
+    while rows_read < limit {
+        match obs_reader.read_observation()? {
+            Some(row) => {
+                for (column, value) in columns.iter_mut().zip(row) {
+                    column.push(value)?;
                 }
-                None => break,
+                rows_read += 1;
             }
+            None => break,
         }
+    }
 
-        // Build columns with full metadata from NAMESTR records
-        let cols: Vec<Column> = member
-            .variables
-            .iter()
-            .zip(columns)
-            .map(|(var, data)| {
-                let mut col = Column::new(&var.nname, data);
+    Ok(columns)
+}
 
-                // Transfer label if present
-                if !var.nlabel.is_empty() {
-                    col = col.with_label(var.nlabel.as_str());
-                }
+/// Builds a [`Dataset`] with full metadata from NAMESTR records.
+fn build_dataset(
+    member: &XptMemberInfo,
+    variables: &[NamestrV5],
+    columns: Vec<ColumnValues>,
+) -> Result<Dataset> {
+    let cols: Vec<Column> = variables
+        .iter()
+        .zip(columns)
+        .map(|(var, values)| {
+            let mut col = Column::new(&var.nname, values.data);
+
+            // Transfer special missing codes
+            if let Some(codes) = values.special_missing {
+                col = col.with_special_missing(codes);
+            }
 
-                // Transfer format if present
-                if !var.nform.is_empty() {
-                    col = col
-                        .with_format(Format::from_namestr(&var.nform, var.nfl, var.nfd, var.nfj));
-                }
+            // Transfer label if present
+            if !var.nlabel.is_empty() {
+                col = col.with_label(var.nlabel.as_str());
+            }
 
-                // Transfer informat if present
-                if !var.niform.is_empty() {
-                    col = col.with_informat(Format::from_namestr(
-                        &var.niform,
-                        var.nifl,
-                        var.nifd,
-                        0, // informats don't have justification
-                    ));
-                }
+            // Transfer format if present
+            if !var.nform.is_empty() {
+                col = col.with_format(Format::from_namestr(&var.nform, var.nfl, var.nfd, var.nfj));
+            }
 
-                // Set explicit length for character variables
-                if var.xpt_type().is_character() {
-                    col = col.with_length(var.length());
-                }
+            // Transfer informat if present
+            if !var.niform.is_empty() {
+                col = col.with_informat(Format::from_namestr(
+                    &var.niform,
+                    var.nifl,
+                    var.nifd,
+                    0, // informats don't have justification
+                ));
+            }
+
+            // Set explicit length for character variables
+            if var.xpt_type().is_character() {
+                col = col.with_length(var.length());
+            }
 
-                col
-            })
-            .collect();
+            col
+        })
+        .collect();
 
-        let mut dataset = Dataset::new(member.name.clone(), cols)?;
-        if let Some(ref label) = member.label {
-            dataset.set_label(label.as_str());
-        }
-        Ok(dataset)
+    let mut dataset = Dataset::new(member.name.clone(), cols)?;
+    if let Some(ref label) = member.label {
+        dataset.set_label(label.as_str());
     }
+    Ok(dataset)
 }
 
 impl XptReader<BufReader<File>> {
@@ -220,6 +528,8 @@ impl XptReader<BufReader<File>> {
 pub enum ObsValue {
     /// A numeric value.
     Numeric(Option<f64>),
+    /// A numeric SAS special missing value (`.A`-`.Z`, `._`).
+    SpecialMissing(SasMissingValue),
     /// A character value.
     Character(Option<String>),
 }
@@ -231,11 +541,13 @@ mod tests {
     #[test]
     fn test_xpt_file_find_member() {
         let file = XptInfo {
+            version: XptVersion::V5,
             members: vec![XptMemberInfo {
                 name: "AE".into(),
                 label: Some("Adverse Events".into()),
                 variables: vec![],
                 obs_offset: 0,
+                obs_len: 0,
                 obs_count: 0,
                 row_len: 0,
             }],
@@ -4,8 +4,13 @@
 
 mod size;
 mod split;
+mod stream;
 mod writer;
 
-pub(crate) use size::estimate_file_size_gb;
+pub(crate) use size::{estimate_file_size, estimate_file_size_gb, gb_to_bytes, max_rows_for_size};
 pub use split::SplitWriter;
+pub use stream::XptStreamWriter;
 pub use writer::XptWriter;
+pub(crate) use writer::get_character_value;
+#[cfg(feature = "define-xml")]
+pub(crate) use writer::get_numeric_value;
@@ -57,6 +57,12 @@ pub(crate) fn estimate_file_size_gb(plan: &DatasetSchema, nrows: usize) -> f64 {
     bytes as f64 / (1024.0 * 1024.0 * 1024.0)
 }
 
+/// Converts a size in gigabytes to bytes.
+#[must_use]
+pub(crate) fn gb_to_bytes(gb: f64) -> usize {
+    (gb * 1024.0 * 1024.0 * 1024.0) as usize
+}
+
 /// Calculates the maximum number of rows that fit in a given file size.
 ///
 /// Returns `None` if even zero rows would exceed the limit.
@@ -10,7 +10,7 @@ use crate::dataset::{Column, ColumnData, Dataset};
 use crate::error::{Error, Result};
 use crate::schema::DatasetSchema;
 
-use super::size::max_rows_for_size;
+use super::size::{gb_to_bytes, max_rows_for_size};
 use super::writer::XptWriter;
 
 /// Writer that automatically splits output into multiple files.
@@ -33,10 +33,9 @@ impl SplitWriter {
         max_size_gb: f64,
         options: WriteOptions,
     ) -> Self {
-        let max_size_bytes = (max_size_gb * 1024.0 * 1024.0 * 1024.0) as usize;
         Self {
             base_path: base_path.as_ref().to_path_buf(),
-            max_size_bytes,
+            max_size_bytes: gb_to_bytes(max_size_gb),
             options,
         }
     }
@@ -118,10 +117,14 @@ fn slice_dataset(dataset: &Dataset, start: usize, end: usize) -> Result<Dataset>
         .iter()
         .map(|col| {
             let data = slice_column_data(col.data(), start, end);
-            if let Some(role) = col.role() {
+            let column = if let Some(role) = col.role() {
                 Column::with_role(col.name(), role, data)
             } else {
                 Column::new(col.name(), data)
+            };
+            match col.special_missing() {
+                Some(codes) => column.with_special_missing(codes[start..end].to_vec()),
+                None => column,
             }
         })
         .collect();
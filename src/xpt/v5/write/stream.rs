@@ -0,0 +1,389 @@
+//! Streaming writer for XPT files.
+//!
+//! This module provides the [`XptStreamWriter`], which writes a single member
+//! from a sequence of [`Dataset`] chunks. The schema is fixed when the writer
+//! is created, so only the current chunk needs to be held in memory.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use chrono::Utc;
+
+use crate::agency::Agency;
+use crate::config::{Config, WriteOptions};
+use crate::dataset::Dataset;
+use crate::error::{Error, Result};
+use crate::schema::DatasetSchema;
+use crate::validate::{
+    Issue, IssueCollection, check_encodable_values, merge_value_issues, validate_data,
+};
+use crate::xpt::v5::constants::RECORD_LEN;
+use crate::xpt::v5::timestamp::format_sas_timestamp;
+
+use super::writer::{XptWriter, get_character_value, get_numeric_value};
+
+/// Offset of the library modified timestamp (third library header record).
+const LIBRARY_MODIFIED_OFFSET: u64 = 2 * RECORD_LEN as u64;
+
+/// Offset of the member modified timestamp (second member descriptor record).
+const MEMBER_MODIFIED_OFFSET: u64 = 6 * RECORD_LEN as u64;
+
+/// Writer that streams observations for one member in chunks.
+///
+/// Created by [`ValidatedWrite::stream_path`](crate::ValidatedWrite::stream_path)
+/// or [`ValidatedWrite::stream_to`](crate::ValidatedWrite::stream_to). The
+/// headers are written up front from the validated schema; each call to
+/// [`write_chunk`](Self::write_chunk) appends rows, and
+/// [`finish`](Self::finish) pads the last record and goes back to fill in
+/// the modified timestamps.
+///
+/// # Example
+///
+/// ```no_run
+/// use xportrs::{Xpt, Dataset, Column, ColumnData};
+///
+/// let template = Dataset::new(
+///     "LB",
+///     vec![
+///         Column::new("USUBJID", ColumnData::String(vec![])).with_length(20),
+///         Column::new("LBSTRESN", ColumnData::F64(vec![])),
+///     ],
+/// )?;
+///
+/// let mut stream = Xpt::writer(template).finalize()?.stream_path("lb.xpt")?;
+/// # let chunks: Vec<Dataset> = vec![];
+/// for chunk in chunks {
+///     stream.write_chunk(&chunk)?;
+/// }
+/// stream.finish()?;
+/// # Ok::<(), xportrs::Error>(())
+/// ```
+pub struct XptStreamWriter<W: Write + Seek> {
+    writer: XptWriter<W>,
+    schema: DatasetSchema,
+    options: WriteOptions,
+    agency: Option<Agency>,
+    strict: bool,
+    rows_written: usize,
+    issues: Vec<Issue>,
+}
+
+impl<W: Write + Seek> XptStreamWriter<W> {
+    /// Creates a stream writer and writes the headers for `schema`.
+    ///
+    /// Chunks are checked against the agency's rules on character values,
+    /// if one is given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the headers fails.
+    pub(crate) fn new(
+        writer: W,
+        schema: DatasetSchema,
+        config: &Config,
+        agency: Option<Agency>,
+    ) -> Result<Self> {
+        let options = config.write.clone();
+        let mut writer = XptWriter::new(writer, options.clone());
+        writer.begin(&schema)?;
+        Ok(Self {
+            writer,
+            schema,
+            options,
+            agency,
+            strict: config.strict_checks,
+            rows_written: 0,
+            issues: Vec::new(),
+        })
+    }
+
+    /// Appends the rows of a chunk.
+    ///
+    /// The chunk must have a column for every variable in the schema, with a
+    /// compatible type, and its character values get the same checks as at
+    /// [`finalize`](crate::XptWriterBuilder::finalize): they must be
+    /// encodable in the text mode, fit the schema lengths unless truncation
+    /// was allowed with
+    /// [`XptWriterBuilder::allow_truncation`](crate::XptWriterBuilder::allow_truncation),
+    /// and meet the [`Agency`] rules. Issues that are not errors are recorded
+    /// in [`issues`](Self::issues). Column order within the chunk doesn't
+    /// matter. A chunk that fails these checks is rejected before any of its
+    /// rows are written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk doesn't match the schema, contains
+    /// values that fail the checks, or writing fails.
+    pub fn write_chunk(&mut self, chunk: &Dataset) -> Result<()> {
+        let issues = self.check_chunk(chunk)?;
+        self.writer.write_rows(chunk, &self.schema)?;
+        merge_value_issues(&mut self.issues, issues, 0);
+        self.rows_written += chunk.nrows();
+        Ok(())
+    }
+
+    /// Returns the issues found in the values of the chunks written so far.
+    ///
+    /// There is one issue per kind and variable across all chunks. Row
+    /// indices count from the first row of the file.
+    #[must_use]
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Returns the number of rows written so far.
+    #[must_use]
+    pub fn rows_written(&self) -> usize {
+        self.rows_written
+    }
+
+    /// Completes the file and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if flushing or patching the headers fails.
+    pub fn finish(self) -> Result<W> {
+        let mut inner = self.writer.finish_member()?;
+
+        // Record when the data was completed, unless a timestamp was fixed
+        if self.options.modified.is_none() {
+            let modified = format_sas_timestamp(Utc::now());
+            for offset in [LIBRARY_MODIFIED_OFFSET, MEMBER_MODIFIED_OFFSET] {
+                inner.seek(SeekFrom::Start(offset)).map_err(Error::Io)?;
+                inner.write_all(modified.as_bytes()).map_err(Error::Io)?;
+            }
+            inner.seek(SeekFrom::End(0)).map_err(Error::Io)?;
+            inner.flush().map_err(Error::Io)?;
+        }
+
+        Ok(inner)
+    }
+
+    /// Checks that a chunk matches the schema, returning the issues found in
+    /// its values with rows counted from the start of the file.
+    fn check_chunk(&self, chunk: &Dataset) -> Result<Vec<Issue>> {
+        for col in chunk.columns() {
+            if !self.schema.variables.iter().any(|v| v.name == col.name()) {
+                return Err(Error::invalid_schema(format!(
+                    "column '{}' is not in the stream schema",
+                    col.name()
+                )));
+            }
+        }
+
+        for var in &self.schema.variables {
+            let col = chunk.column(&var.name).ok_or_else(|| {
+                Error::invalid_schema(format!("column '{}' not found in chunk", var.name))
+            })?;
+
+            // Type errors depend only on the column data variant
+            if chunk.nrows() > 0 {
+                if var.xpt_type.is_numeric() {
+                    get_numeric_value(col.data(), 0)?;
+                } else {
+                    get_character_value(col.data(), 0)?;
+                }
+            }
+        }
+
+        check_encodable_values(chunk, self.schema.text_mode, self.rows_written)?;
+
+        let mut issues = Vec::new();
+        let allowed = self.options.allow_truncation;
+        let truncated = validate_data(chunk, &self.schema, allowed);
+        merge_value_issues(&mut issues, truncated, self.rows_written);
+        if let Some(agency) = self.agency {
+            let agency_issues = agency.validate_data(chunk, &self.schema);
+            merge_value_issues(&mut issues, agency_issues, self.rows_written);
+        }
+
+        if self.strict && issues.has_errors() {
+            let messages: Vec<String> = issues.errors().map(ToString::to_string).collect();
+            return Err(Error::validation_failed(messages.join("; ")));
+        }
+        Ok(issues)
+    }
+}
+
+impl<W: Write + Seek> std::fmt::Debug for XptStreamWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XptStreamWriter")
+            .field("domain_code", &self.schema.domain_code)
+            .field("rows_written", &self.rows_written)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TextMode;
+    use crate::dataset::{Column, ColumnData};
+    use crate::schema::plan::VariableSpec;
+    use std::io::Cursor;
+
+    fn schema() -> DatasetSchema {
+        let mut plan = DatasetSchema::new("LB");
+        plan.variables = vec![
+            VariableSpec::character("USUBJID", 4),
+            VariableSpec::numeric("LBSEQ"),
+        ];
+        plan.recalculate_positions();
+        plan
+    }
+
+    fn chunk(ids: &[&str], start: f64) -> Dataset {
+        let seq = (0..ids.len()).map(|i| Some(start + i as f64)).collect();
+        Dataset::new(
+            "LB",
+            vec![
+                Column::new(
+                    "USUBJID",
+                    ColumnData::String(ids.iter().map(|s| Some((*s).to_string())).collect()),
+                ),
+                Column::new("LBSEQ", ColumnData::F64(seq)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn stream_writer(
+        options: WriteOptions,
+        agency: Option<Agency>,
+    ) -> XptStreamWriter<Cursor<Vec<u8>>> {
+        let config = Config {
+            write: options,
+            ..Config::default()
+        };
+        XptStreamWriter::new(Cursor::new(Vec::new()), schema(), &config, agency).unwrap()
+    }
+
+    #[test]
+    fn test_stream_matches_single_write() {
+        let options = WriteOptions::default()
+            .with_created(Utc::now())
+            .with_modified(Utc::now());
+
+        let mut stream = stream_writer(options.clone(), None);
+        stream.write_chunk(&chunk(&["S1", "S2"], 1.0)).unwrap();
+        stream.write_chunk(&chunk(&["S3"], 3.0)).unwrap();
+        assert_eq!(stream.rows_written(), 3);
+        let streamed = stream.finish().unwrap().into_inner();
+
+        let whole = XptWriter::new(Cursor::new(Vec::new()), options)
+            .write(&chunk(&["S1", "S2", "S3"], 1.0), &schema())
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(streamed, whole);
+    }
+
+    #[test]
+    fn test_finish_patches_modified_timestamp() {
+        let created = chrono::DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let options = WriteOptions::default().with_created(created);
+
+        let mut stream = stream_writer(options, None);
+        stream.write_chunk(&chunk(&["S1"], 1.0)).unwrap();
+        let bytes = stream.finish().unwrap().into_inner();
+
+        let library = LIBRARY_MODIFIED_OFFSET as usize;
+        let member = MEMBER_MODIFIED_OFFSET as usize;
+        assert_eq!(bytes.len() % RECORD_LEN, 0);
+        assert_eq!(&bytes[library..library + 16], &bytes[member..member + 16]);
+        assert_ne!(&bytes[member..member + 16], b"01JAN20:00:00:00");
+    }
+
+    #[test]
+    fn test_reject_mismatched_chunk() {
+        let mut stream = stream_writer(WriteOptions::default(), None);
+
+        // Value longer than the schema length
+        assert!(matches!(
+            stream.write_chunk(&chunk(&["TOO-LONG"], 1.0)),
+            Err(Error::ValidationFailed { .. })
+        ));
+
+        // Missing column
+        let missing = Dataset::new(
+            "LB",
+            vec![Column::new("LBSEQ", ColumnData::F64(vec![Some(1.0)]))],
+        )
+        .unwrap();
+        assert!(stream.write_chunk(&missing).is_err());
+
+        // Wrong type
+        let wrong = Dataset::new(
+            "LB",
+            vec![
+                Column::new("USUBJID", ColumnData::F64(vec![Some(1.0)])),
+                Column::new("LBSEQ", ColumnData::F64(vec![Some(1.0)])),
+            ],
+        )
+        .unwrap();
+        assert!(stream.write_chunk(&wrong).is_err());
+        assert_eq!(stream.rows_written(), 0);
+    }
+
+    #[test]
+    fn test_allowed_truncation() {
+        let options = WriteOptions::default().with_allow_truncation(true);
+        let mut stream = stream_writer(options, None);
+        stream.write_chunk(&chunk(&["S1", "S2"], 1.0)).unwrap();
+        stream
+            .write_chunk(&chunk(&["S3", "TOO-LONG"], 3.0))
+            .unwrap();
+        stream
+            .write_chunk(&chunk(&["LONGER-ID", "S6"], 5.0))
+            .unwrap();
+
+        assert_eq!(stream.rows_written(), 6);
+        assert_eq!(
+            stream.issues(),
+            [Issue::ValueTruncated {
+                variable: "USUBJID".into(),
+                row: 3,
+                count: 2,
+                actual: 9,
+                truncated: 4,
+                allowed: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_agency_checks_chunks() {
+        let mut stream = stream_writer(WriteOptions::default(), Some(Agency::FDA));
+        stream.write_chunk(&chunk(&["S1", "S2"], 1.0)).unwrap();
+
+        let Err(Error::ValidationFailed { message }) =
+            stream.write_chunk(&chunk(&["S3", "É4"], 3.0))
+        else {
+            panic!("expected the non-ASCII chunk to be rejected");
+        };
+        assert!(message.contains("in row 3 contains non-ASCII"), "{message}");
+        assert_eq!(stream.rows_written(), 2);
+
+        // Without an agency, only the text mode applies
+        let mut stream = stream_writer(WriteOptions::default(), None);
+        stream.write_chunk(&chunk(&["É4"], 1.0)).unwrap();
+        assert!(stream.issues().is_empty());
+    }
+
+    #[test]
+    fn test_unencodable_chunk() {
+        let config = Config::default();
+        let mut plan = schema();
+        plan.text_mode = TextMode::Windows1252;
+        let mut stream =
+            XptStreamWriter::new(Cursor::new(Vec::new()), plan, &config, None).unwrap();
+        stream.write_chunk(&chunk(&["S1"], 1.0)).unwrap();
+
+        let Err(Error::Encoding { message }) = stream.write_chunk(&chunk(&["頭痛"], 2.0)) else {
+            panic!("expected the chunk to be rejected");
+        };
+        assert!(message.contains("row 1"), "{message}");
+        assert_eq!(stream.rows_written(), 1);
+    }
+}
@@ -1,6 +1,9 @@
 //! Main XPT v5 writer.
 //!
-//! This module provides the [`XptWriter`] for writing XPT v5 files.
+//! This module provides the [`XptWriter`] for writing XPT v5 files. XPT v8
+//! files share the same layout and are written by the same writer, calling
+//! into [`crate::xpt::v8`] for the header records, long-name NAMESTRs and
+//! the label section.
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -16,12 +19,13 @@ use crate::xpt::v5::constants::{
     LIBRARY_HEADER, MEMBER_HEADER, MEMBER_HEADER_DATA, NAMESTR_HEADER, OBS_HEADER, PAD_CHAR,
     RECORD_LEN,
 };
-use crate::xpt::v5::encoding::encode_ibm_float;
+use crate::xpt::v5::encoding::{encode_ibm_float, encode_missing_value, encode_text};
 use crate::xpt::v5::namestr::pack_namestr;
 use crate::xpt::v5::record::RecordWriter;
 use crate::xpt::v5::timestamp::{
     format_sas_timestamp, sas_days_since_1960, sas_seconds_since_1960, sas_seconds_since_midnight,
 };
+use crate::xpt::v8;
 
 /// Writer for XPT v5 files.
 pub struct XptWriter<W: Write> {
@@ -43,17 +47,38 @@ impl<W: Write> XptWriter<W> {
     /// # Errors
     ///
     /// Returns an error if writing fails.
-    pub(crate) fn write(mut self, dataset: &Dataset, plan: &DatasetSchema) -> Result<W> {
+    pub(crate) fn write(self, dataset: &Dataset, plan: &DatasetSchema) -> Result<W> {
+        self.write_library(&[(dataset, plan)])
+    }
+
+    /// Writes a complete XPT file with one member per dataset, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub(crate) fn write_library(mut self, members: &[(&Dataset, &DatasetSchema)]) -> Result<W> {
         self.write_library_header()?;
-        self.write_member(dataset, plan)?;
+        for (dataset, plan) in members {
+            self.write_member(dataset, plan)?;
+        }
         self.writer.finish().map_err(Error::Io)
     }
 
+    /// Returns `true` if this writer emits XPT v8.
+    fn is_v8(&self) -> bool {
+        self.options.version.is_v8()
+    }
+
     /// Writes the library header section.
     fn write_library_header(&mut self) -> Result<()> {
         // Record 1: Library header marker
+        let library_header = if self.is_v8() {
+            v8::constants::LIBRARY_HEADER
+        } else {
+            LIBRARY_HEADER
+        };
         self.writer
-            .write_record(LIBRARY_HEADER)
+            .write_record(library_header)
             .map_err(Error::Io)?;
 
         // Record 2: SAS identifier and timestamps
@@ -88,12 +113,32 @@ impl<W: Write> XptWriter<W> {
 
     /// Writes a single member (dataset).
     fn write_member(&mut self, dataset: &Dataset, plan: &DatasetSchema) -> Result<()> {
-        self.write_member_header(plan)?;
-        self.write_namestr_section(plan)?;
+        self.begin_member(plan)?;
         self.write_observations(dataset, plan)?;
         Ok(())
     }
 
+    /// Writes the library header and the member headers, up to the first observation.
+    ///
+    /// Rows are then appended with [`write_rows`](Self::write_rows) and the
+    /// file completed with [`finish_member`](Self::finish_member).
+    pub(super) fn begin(&mut self, plan: &DatasetSchema) -> Result<()> {
+        self.write_library_header()?;
+        self.begin_member(plan)
+    }
+
+    /// Pads the last observation record and flushes the output.
+    pub(super) fn finish_member(mut self) -> Result<W> {
+        self.writer.pad_and_flush().map_err(Error::Io)?;
+        self.writer.finish().map_err(Error::Io)
+    }
+
+    /// Writes the member header and NAMESTR sections.
+    fn begin_member(&mut self, plan: &DatasetSchema) -> Result<()> {
+        self.write_member_header(plan)?;
+        self.write_namestr_section(plan)
+    }
+
     /// Writes the member header section.
     fn write_member_header(&mut self, plan: &DatasetSchema) -> Result<()> {
         let now = Utc::now();
@@ -103,11 +148,18 @@ impl<W: Write> XptWriter<W> {
         let modified_str = format_sas_timestamp(modified);
 
         // Record 1: Member header marker
-        self.writer.write_record(MEMBER_HEADER).map_err(Error::Io)?;
-
         // Record 2: DSCRPTR header marker
+        let (member_header, member_header_data) = if self.is_v8() {
+            (
+                v8::constants::MEMBER_HEADER,
+                v8::constants::MEMBER_HEADER_DATA,
+            )
+        } else {
+            (MEMBER_HEADER, MEMBER_HEADER_DATA)
+        };
+        self.writer.write_record(member_header).map_err(Error::Io)?;
         self.writer
-            .write_record(MEMBER_HEADER_DATA)
+            .write_record(member_header_data)
             .map_err(Error::Io)?;
 
         // Record 3: Member descriptor data 1
@@ -119,13 +171,26 @@ impl<W: Write> XptWriter<W> {
         // [32..40]: OS name
         // [40..64]: blanks
         // [64..80]: created timestamp (16 chars)
+        //
+        // V8 widens the dataset name to 32 bytes:
+        // [8..40]: dataset name
+        // [40..48]: "SASDATA "
+        // [48..56]: SAS version
+        // [56..64]: OS name
         let mut rec1 = [PAD_CHAR; RECORD_LEN];
         rec1[..8].copy_from_slice(b"SAS     ");
-        rec1[8..16].copy_from_slice(pad_string(&plan.domain_code, 8).as_slice());
-        rec1[16..24].copy_from_slice(b"SASDATA ");
-        rec1[24..32].copy_from_slice(b"9.4     "); // SAS version
-        // [32..40] OS name - leave as spaces
-        // [40..64] blanks - leave as spaces
+        if self.is_v8() {
+            let name_len = v8::constants::DATASET_NAME_LEN;
+            rec1[8..8 + name_len].copy_from_slice(&pad_string(&plan.domain_code, name_len, plan)?);
+            rec1[40..48].copy_from_slice(b"SASDATA ");
+            rec1[48..56].copy_from_slice(b"9.4     "); // SAS version
+        } else {
+            rec1[8..16].copy_from_slice(&pad_string(&plan.domain_code, 8, plan)?);
+            rec1[16..24].copy_from_slice(b"SASDATA ");
+            rec1[24..32].copy_from_slice(b"9.4     "); // SAS version
+            // [32..40] OS name - leave as spaces
+            // [40..64] blanks - leave as spaces
+        }
         rec1[64..80].copy_from_slice(created_str.as_bytes());
         self.writer.write_record(&rec1).map_err(Error::Io)?;
 
@@ -138,7 +203,7 @@ impl<W: Write> XptWriter<W> {
         let mut rec2 = [PAD_CHAR; RECORD_LEN];
         rec2[..16].copy_from_slice(modified_str.as_bytes());
         if let Some(ref label) = plan.dataset_label {
-            let label_bytes = pad_string(label, 40);
+            let label_bytes = pad_string(label, 40, plan)?;
             rec2[32..72].copy_from_slice(&label_bytes);
         }
         self.writer.write_record(&rec2).map_err(Error::Io)?;
@@ -152,10 +217,18 @@ impl<W: Write> XptWriter<W> {
 
         // NAMESTR header record
         // Per SAS spec: nvars is a 4-digit field at bytes 54-57
+        // (V8: a 10-digit field at bytes 48-57)
         let mut header = [PAD_CHAR; RECORD_LEN];
-        header[..54].copy_from_slice(NAMESTR_HEADER);
-        let nvars_str = format!("{:04}", nvars);
-        header[54..58].copy_from_slice(nvars_str.as_bytes());
+        if self.is_v8() {
+            let prefix_len = v8::constants::HEADER_PREFIX_LEN;
+            header[..prefix_len].copy_from_slice(v8::constants::NAMESTR_HEADER);
+            let nvars_str = format!("{:010}", nvars);
+            header[prefix_len..58].copy_from_slice(nvars_str.as_bytes());
+        } else {
+            header[..54].copy_from_slice(NAMESTR_HEADER);
+            let nvars_str = format!("{:04}", nvars);
+            header[54..58].copy_from_slice(nvars_str.as_bytes());
+        }
         header[58..78].copy_from_slice(b"00000000000000000000");
         header[78..80].copy_from_slice(b"  ");
 
@@ -163,21 +236,47 @@ impl<W: Write> XptWriter<W> {
 
         // Write NAMESTR records for each variable
         for (i, var) in plan.variables.iter().enumerate() {
-            let namestr = pack_namestr(var, i)?;
+            let namestr = if self.is_v8() {
+                v8::namestr::pack_namestr(var, i, plan.text_mode)?
+            } else {
+                pack_namestr(var, i, plan.text_mode)?
+            };
             self.writer.write_bytes(&namestr).map_err(Error::Io)?;
         }
 
         // Pad to record boundary
         self.writer.pad_and_flush().map_err(Error::Io)?;
 
+        // V8 label section for labels and format names that don't fit the NAMESTR
+        if self.is_v8()
+            && let Some((_, section)) = v8::labels::build_label_section(plan)?
+        {
+            self.writer.write_bytes(&section).map_err(Error::Io)?;
+        }
+
         // OBS header record
-        self.writer.write_record(OBS_HEADER).map_err(Error::Io)?;
+        let obs_header = if self.is_v8() {
+            v8::constants::OBS_HEADER
+        } else {
+            OBS_HEADER
+        };
+        self.writer.write_record(obs_header).map_err(Error::Io)?;
 
         Ok(())
     }
 
     /// Writes observation data.
     fn write_observations(&mut self, dataset: &Dataset, plan: &DatasetSchema) -> Result<()> {
+        self.write_rows(dataset, plan)?;
+
+        // Pad final record
+        self.writer.pad_and_flush().map_err(Error::Io)?;
+
+        Ok(())
+    }
+
+    /// Writes the rows of `dataset` without padding the last record.
+    pub(super) fn write_rows(&mut self, dataset: &Dataset, plan: &DatasetSchema) -> Result<()> {
         for row_idx in 0..dataset.nrows() {
             for var in &plan.variables {
                 let col = dataset.column(&var.name).ok_or_else(|| {
@@ -186,19 +285,25 @@ impl<W: Write> XptWriter<W> {
 
                 if var.xpt_type.is_numeric() {
                     let value = get_numeric_value(col.data(), row_idx)?;
-                    let bytes = encode_ibm_float(value);
-                    self.writer.write_bytes(&bytes).map_err(Error::Io)?;
+                    let bytes = match (value, col.special_missing_at(row_idx)) {
+                        (None, Some(missing)) => encode_missing_value(missing),
+                        _ => encode_ibm_float(value),
+                    };
+                    // Shorter numerics keep the leading bytes of the mantissa
+                    self.writer
+                        .write_bytes(&bytes[..var.length])
+                        .map_err(Error::Io)?;
                 } else {
                     let value = get_character_value(col.data(), row_idx)?;
-                    let bytes = pad_string(&value.unwrap_or_default(), var.length);
+                    let bytes =
+                        encode_text(value.as_deref(), var.length, plan.text_mode).map_err(|e| {
+                            Error::encoding(format!("variable '{}', row {row_idx}: {e}", var.name))
+                        })?;
                     self.writer.write_bytes(&bytes).map_err(Error::Io)?;
                 }
             }
         }
 
-        // Pad final record
-        self.writer.pad_and_flush().map_err(Error::Io)?;
-
         Ok(())
     }
 }
@@ -216,7 +321,7 @@ impl XptWriter<BufWriter<File>> {
 }
 
 /// Gets a numeric value from column data at the given row index.
-fn get_numeric_value(data: &ColumnData, row: usize) -> Result<Option<f64>> {
+pub(crate) fn get_numeric_value(data: &ColumnData, row: usize) -> Result<Option<f64>> {
     match data {
         ColumnData::F64(v) => Ok(v.get(row).copied().flatten()),
         ColumnData::I64(v) => Ok(v.get(row).copied().flatten().map(|i| i as f64)),
@@ -245,7 +350,7 @@ fn get_numeric_value(data: &ColumnData, row: usize) -> Result<Option<f64>> {
 }
 
 /// Gets a character value from column data at the given row index.
-fn get_character_value(data: &ColumnData, row: usize) -> Result<Option<String>> {
+pub(crate) fn get_character_value(data: &ColumnData, row: usize) -> Result<Option<String>> {
     match data {
         ColumnData::String(v) => Ok(v.get(row).cloned().flatten()),
         ColumnData::Bytes(v) => Ok(v
@@ -273,12 +378,9 @@ fn get_character_value(data: &ColumnData, row: usize) -> Result<Option<String>>
     }
 }
 
-/// Pads a string with spaces to the specified length.
-fn pad_string(s: &str, len: usize) -> Vec<u8> {
-    let mut bytes = s.as_bytes().to_vec();
-    bytes.truncate(len);
-    bytes.resize(len, PAD_CHAR);
-    bytes
+/// Encodes a header field in the schema's character set, padded with spaces.
+fn pad_string(s: &str, len: usize, plan: &DatasetSchema) -> Result<Vec<u8>> {
+    encode_text(Some(s), len, plan.text_mode).map_err(|e| Error::encoding(e.to_string()))
 }
 
 #[cfg(test)]
@@ -0,0 +1,87 @@
+//! XPT v8 constants and marker records.
+//!
+//! V8 files keep the 80-byte record structure of V5 but use their own set of
+//! header record names. Each header record is laid out as
+//! `HEADER RECORD*******` + an 8-byte record name + `HEADER RECORD!!!!!!!`,
+//! followed by 32 bytes of record-specific data.
+
+use crate::xpt::v5::constants::RECORD_LEN;
+
+/// Length of the common header record prefix (everything before the data field).
+pub const HEADER_PREFIX_LEN: usize = 48;
+
+/// First library header record.
+///
+/// Format: "HEADER RECORD*******LIBV8   HEADER RECORD!!!!!!!000000000000000000000000000000  "
+pub const LIBRARY_HEADER: &[u8; RECORD_LEN] =
+    b"HEADER RECORD*******LIBV8   HEADER RECORD!!!!!!!000000000000000000000000000000  ";
+
+/// Member header record.
+///
+/// Format: "HEADER RECORD*******MEMBV8  HEADER RECORD!!!!!!!000000000000000001600000000140  "
+pub const MEMBER_HEADER: &[u8; RECORD_LEN] =
+    b"HEADER RECORD*******MEMBV8  HEADER RECORD!!!!!!!000000000000000001600000000140  ";
+
+/// Member descriptor header record.
+///
+/// Format: "HEADER RECORD*******DSCPTV8 HEADER RECORD!!!!!!!000000000000000000000000000000  "
+pub const MEMBER_HEADER_DATA: &[u8; RECORD_LEN] =
+    b"HEADER RECORD*******DSCPTV8 HEADER RECORD!!!!!!!000000000000000000000000000000  ";
+
+/// NAMESTR header record prefix.
+///
+/// The variable count follows the prefix, right-aligned with leading zeros
+/// in bytes 48-57. SAS writes the record name as `NAMSTV8`, but `NAMSTV08`
+/// is also seen in the wild, so readers should only match on [`NAMESTR_HEADER_STEM`].
+pub const NAMESTR_HEADER: &[u8; HEADER_PREFIX_LEN] =
+    b"HEADER RECORD*******NAMSTV8 HEADER RECORD!!!!!!!";
+
+/// The part of the NAMESTR header shared by the `NAMSTV8` and `NAMSTV08` spellings.
+pub const NAMESTR_HEADER_STEM: &[u8; 26] = b"HEADER RECORD*******NAMSTV";
+
+/// Observation header record.
+///
+/// Format: "HEADER RECORD*******OBSV8   HEADER RECORD!!!!!!!000000000000000000000000000000  "
+pub const OBS_HEADER: &[u8; RECORD_LEN] =
+    b"HEADER RECORD*******OBSV8   HEADER RECORD!!!!!!!000000000000000000000000000000  ";
+
+/// Label section header prefix for long labels.
+///
+/// The number of label entries follows the prefix, left-aligned.
+pub const LABELV8_HEADER: &[u8; HEADER_PREFIX_LEN] =
+    b"HEADER RECORD*******LABELV8 HEADER RECORD!!!!!!!";
+
+/// Label section header prefix for long labels and long format names.
+///
+/// The number of label entries follows the prefix, left-aligned.
+pub const LABELV9_HEADER: &[u8; HEADER_PREFIX_LEN] =
+    b"HEADER RECORD*******LABELV9 HEADER RECORD!!!!!!!";
+
+/// Length of the dataset name field in the member descriptor record.
+pub const DATASET_NAME_LEN: usize = 32;
+
+/// Length of the `longname` field in a V8 NAMESTR record.
+pub const LONG_NAME_LEN: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_lengths() {
+        assert_eq!(LIBRARY_HEADER.len(), RECORD_LEN);
+        assert_eq!(MEMBER_HEADER.len(), RECORD_LEN);
+        assert_eq!(MEMBER_HEADER_DATA.len(), RECORD_LEN);
+        assert_eq!(OBS_HEADER.len(), RECORD_LEN);
+    }
+
+    #[test]
+    fn test_header_prefixes_line_up() {
+        assert_eq!(&LIBRARY_HEADER[28..48], b"HEADER RECORD!!!!!!!");
+        assert_eq!(&MEMBER_HEADER[28..48], b"HEADER RECORD!!!!!!!");
+        assert_eq!(&NAMESTR_HEADER[28..48], b"HEADER RECORD!!!!!!!");
+        assert_eq!(&LABELV8_HEADER[28..48], b"HEADER RECORD!!!!!!!");
+        assert_eq!(&LABELV9_HEADER[28..48], b"HEADER RECORD!!!!!!!");
+        assert!(NAMESTR_HEADER.starts_with(NAMESTR_HEADER_STEM));
+    }
+}
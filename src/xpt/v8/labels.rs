@@ -0,0 +1,443 @@
+//! Label section handling for XPT v8.
+//!
+//! Labels longer than 40 bytes, and format or informat names longer than
+//! 8 bytes, don't fit in the NAMESTR record. V8 files store them in a label
+//! section between the NAMESTR records and the observation header:
+//!
+//! - `LABELV8`: entries of `varnum, namelen, lablen` (2-byte integers)
+//!   followed by the name and label text.
+//! - `LABELV9`: entries of `varnum, namelen, lablen, fmtlen, infmtlen`
+//!   followed by the name, label, format and informat text.
+//!
+//! Entries are streamed back to back and the section is padded with spaces
+//! to an 80-byte record boundary.
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::config::TextMode;
+use crate::error::{Error, Result};
+use crate::schema::{DatasetSchema, VariableSpec};
+use crate::xpt::v5::constants::{PAD_CHAR, RECORD_LEN};
+use crate::xpt::v5::encoding::{decode_text, encode_str};
+use crate::xpt::v5::namestr::NamestrV5;
+
+use super::constants::{HEADER_PREFIX_LEN, LABELV8_HEADER, LABELV9_HEADER};
+
+/// The kind of label section that follows the NAMESTR records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelSection {
+    /// `LABELV8`: long names and labels only.
+    V8,
+    /// `LABELV9`: long names, labels, format and informat descriptions.
+    V9,
+}
+
+impl LabelSection {
+    /// Identifies a label section header record.
+    #[must_use]
+    pub fn from_header(record: &[u8; RECORD_LEN]) -> Option<Self> {
+        if record.starts_with(LABELV8_HEADER) {
+            Some(Self::V8)
+        } else if record.starts_with(LABELV9_HEADER) {
+            Some(Self::V9)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of 2-byte length fields at the start of each entry.
+    const fn field_count(self) -> usize {
+        match self {
+            Self::V8 => 3,
+            Self::V9 => 5,
+        }
+    }
+}
+
+/// Reads a label section and applies its entries to `variables`.
+///
+/// `header` is the already-consumed `LABELV8`/`LABELV9` header record. On
+/// return the reader is positioned at the record following the section.
+/// Text is decoded with `mode`.
+///
+/// # Errors
+///
+/// Returns an error if the section is truncated, refers to an unknown
+/// variable, or holds text that cannot be decoded.
+pub fn read_label_section<R: Read>(
+    reader: &mut R,
+    section: LabelSection,
+    header: &[u8; RECORD_LEN],
+    variables: &mut [NamestrV5],
+    mode: TextMode,
+) -> Result<()> {
+    let count = parse_entry_count(header)?;
+    let mut consumed = 0usize;
+
+    for _ in 0..count {
+        let mut fields = [0usize; 5];
+        for field in fields.iter_mut().take(section.field_count()) {
+            let value = reader.read_i16::<BigEndian>().map_err(Error::Io)?;
+            *field = usize::try_from(value)
+                .map_err(|_| Error::corrupt(format!("negative length in label record: {value}")))?;
+        }
+        consumed += section.field_count() * 2;
+
+        let [varnum, name_len, label_len, format_len, informat_len] = fields;
+        let name = read_text(reader, name_len, mode)?;
+        let label = read_text(reader, label_len, mode)?;
+        let format = read_text(reader, format_len, mode)?;
+        let informat = read_text(reader, informat_len, mode)?;
+        consumed += name_len + label_len + format_len + informat_len;
+
+        let var = find_variable(variables, varnum)?;
+        if !name.is_empty() {
+            var.nname = name;
+        }
+        if !label.is_empty() {
+            var.nlabel = label;
+        }
+        if !format.is_empty() {
+            apply_format_description(&format, &mut var.nform, &mut var.nfl, &mut var.nfd);
+        }
+        if !informat.is_empty() {
+            apply_format_description(&informat, &mut var.niform, &mut var.nifl, &mut var.nifd);
+        }
+    }
+
+    // Skip the padding up to the next record boundary
+    let padding = (RECORD_LEN - consumed % RECORD_LEN) % RECORD_LEN;
+    let mut pad = vec![0u8; padding];
+    reader.read_exact(&mut pad).map_err(Error::Io)?;
+
+    Ok(())
+}
+
+/// Maximum label bytes that fit in the NAMESTR `nlabel` field.
+const NAMESTR_LABEL_LEN: usize = 40;
+
+/// Maximum format name bytes that fit in the NAMESTR `nform`/`niform` fields.
+const NAMESTR_FORMAT_LEN: usize = 8;
+
+/// Builds the label section for `plan`, if any variable needs one.
+///
+/// Returns the header record followed by the entries, padded to an 80-byte
+/// record boundary. Only variables whose label or format names don't fit in
+/// the NAMESTR record get an entry; `LABELV9` is used when any format or
+/// informat name is longer than 8 bytes, `LABELV8` otherwise. Text is
+/// written, and its length measured, in the schema's character set.
+///
+/// # Errors
+///
+/// Returns an error if a name or label cannot be encoded.
+pub(crate) fn build_label_section(plan: &DatasetSchema) -> Result<Option<(LabelSection, Vec<u8>)>> {
+    let long_format = |var: &VariableSpec| {
+        var.format_name().len() > NAMESTR_FORMAT_LEN
+            || var.informat_name().len() > NAMESTR_FORMAT_LEN
+    };
+
+    let entries: Vec<(usize, &VariableSpec)> = plan
+        .variables
+        .iter()
+        .enumerate()
+        .filter(|(_, var)| plan.byte_len(&var.label) > NAMESTR_LABEL_LEN || long_format(var))
+        .collect();
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let section = if entries.iter().any(|(_, var)| long_format(var)) {
+        LabelSection::V9
+    } else {
+        LabelSection::V8
+    };
+
+    let mut bytes = vec![PAD_CHAR; RECORD_LEN];
+    let prefix = match section {
+        LabelSection::V8 => LABELV8_HEADER,
+        LabelSection::V9 => LABELV9_HEADER,
+    };
+    bytes[..HEADER_PREFIX_LEN].copy_from_slice(prefix);
+    let count = entries.len().to_string();
+    bytes[HEADER_PREFIX_LEN..HEADER_PREFIX_LEN + count.len()].copy_from_slice(count.as_bytes());
+
+    for (i, var) in entries {
+        let format = var.format.as_ref().map(ToString::to_string);
+        let informat = var.informat.as_ref().map(ToString::to_string);
+        let mut texts = vec![var.name.as_str(), var.label.as_str()];
+        if section == LabelSection::V9 {
+            texts.push(format.as_deref().unwrap_or_default());
+            texts.push(informat.as_deref().unwrap_or_default());
+        }
+
+        let texts = texts
+            .into_iter()
+            .map(|text| encode_str(text, plan.text_mode))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::encoding(e.to_string()))?;
+
+        let varnum = i16::try_from(i + 1).unwrap_or(i16::MAX);
+        bytes.extend_from_slice(&varnum.to_be_bytes());
+        for text in &texts {
+            let len = i16::try_from(text.len()).unwrap_or(i16::MAX);
+            bytes.extend_from_slice(&len.to_be_bytes());
+        }
+        for text in &texts {
+            bytes.extend_from_slice(text);
+        }
+    }
+
+    let padding = (RECORD_LEN - bytes.len() % RECORD_LEN) % RECORD_LEN;
+    bytes.resize(bytes.len() + padding, PAD_CHAR);
+
+    Ok(Some((section, bytes)))
+}
+
+/// Parses the number of entries from a label section header.
+///
+/// The count follows the header prefix and may be left-aligned and blank-padded
+/// or zero-filled.
+fn parse_entry_count(header: &[u8; RECORD_LEN]) -> Result<usize> {
+    let field = String::from_utf8_lossy(&header[HEADER_PREFIX_LEN..]);
+    let count = field.split_whitespace().next().unwrap_or_default();
+    count
+        .parse()
+        .map_err(|_| Error::corrupt(format!("invalid label entry count: {count}")))
+}
+
+/// Reads `len` bytes of text, trimming trailing blanks.
+fn read_text<R: Read>(reader: &mut R, len: usize, mode: TextMode) -> Result<String> {
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).map_err(Error::Io)?;
+    decode_text(&buf, mode, true).map_err(|e| Error::encoding(e.to_string()))
+}
+
+/// Finds the variable a label entry refers to by its 1-based number.
+fn find_variable(variables: &mut [NamestrV5], varnum: usize) -> Result<&mut NamestrV5> {
+    let index = variables
+        .iter()
+        .position(|v| usize::try_from(v.nvar0).ok() == Some(varnum))
+        .or_else(|| varnum.checked_sub(1).filter(|&i| i < variables.len()))
+        .ok_or_else(|| {
+            Error::corrupt(format!("label record refers to unknown variable {varnum}"))
+        })?;
+    Ok(&mut variables[index])
+}
+
+/// Applies a format description such as `MY_LONG_FMT8.2` to the NAMESTR fields.
+///
+/// The name replaces the truncated 8-byte name. Width and decimals are only
+/// overwritten when the description includes them.
+fn apply_format_description(desc: &str, name: &mut String, length: &mut i16, decimals: &mut i16) {
+    let (head, decimals_part) = desc.rsplit_once('.').unwrap_or((desc, ""));
+    let width_start = head.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+
+    *name = head[..width_start].to_string();
+    if let Ok(width) = head[width_start..].parse() {
+        *length = width;
+    }
+    if let Ok(d) = decimals_part.parse() {
+        *decimals = d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn label_header(prefix: &[u8; HEADER_PREFIX_LEN], count: &str) -> [u8; RECORD_LEN] {
+        let mut header = [b' '; RECORD_LEN];
+        header[..HEADER_PREFIX_LEN].copy_from_slice(prefix);
+        header[HEADER_PREFIX_LEN..HEADER_PREFIX_LEN + count.len()]
+            .copy_from_slice(count.as_bytes());
+        header
+    }
+
+    fn variables() -> Vec<NamestrV5> {
+        vec![
+            NamestrV5 {
+                nvar0: 1,
+                nname: "LONGVARN".into(),
+                nlabel: "this is a label that is over 40 characte".into(),
+                nform: "LONGFMTN".into(),
+                ..NamestrV5::default()
+            },
+            NamestrV5 {
+                nvar0: 2,
+                nname: "Y".into(),
+                ..NamestrV5::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_section_detection() {
+        let v8 = label_header(LABELV8_HEADER, "1");
+        let v9 = label_header(LABELV9_HEADER, "1");
+        assert_eq!(LabelSection::from_header(&v8), Some(LabelSection::V8));
+        assert_eq!(LabelSection::from_header(&v9), Some(LabelSection::V9));
+        assert_eq!(LabelSection::from_header(&[b' '; RECORD_LEN]), None);
+    }
+
+    #[test]
+    fn test_read_labelv8_section() {
+        let label = "a very long label for x is given right here";
+        let mut data = Vec::new();
+        data.extend_from_slice(&1i16.to_be_bytes());
+        data.extend_from_slice(&11i16.to_be_bytes());
+        data.extend_from_slice(&(label.len() as i16).to_be_bytes());
+        data.extend_from_slice(b"LONGVARNAME");
+        data.extend_from_slice(label.as_bytes());
+        data.resize(RECORD_LEN, b' ');
+        data.extend_from_slice(b"NEXT");
+
+        let mut vars = variables();
+        let mut cursor = Cursor::new(data);
+        let header = label_header(LABELV8_HEADER, "1");
+        read_label_section(
+            &mut cursor,
+            LabelSection::V8,
+            &header,
+            &mut vars,
+            TextMode::default(),
+        )
+        .unwrap();
+
+        assert_eq!(vars[0].nname, "LONGVARNAME");
+        assert_eq!(vars[0].nlabel, label);
+        assert_eq!(vars[1].nname, "Y");
+        assert_eq!(cursor.position(), RECORD_LEN as u64);
+    }
+
+    #[test]
+    fn test_read_labelv9_section() {
+        let label = "this is a label that is over 40 characters long";
+        let mut data = Vec::new();
+        for value in [1i16, 11, label.len() as i16, 12, 0] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(b"LONGVARNAME");
+        data.extend_from_slice(label.as_bytes());
+        data.extend_from_slice(b"LONGFMTNAME.");
+        data.resize(RECORD_LEN, b' ');
+
+        let mut vars = variables();
+        let mut cursor = Cursor::new(data);
+        let header = label_header(LABELV9_HEADER, "1");
+        read_label_section(
+            &mut cursor,
+            LabelSection::V9,
+            &header,
+            &mut vars,
+            TextMode::default(),
+        )
+        .unwrap();
+
+        assert_eq!(vars[0].nname, "LONGVARNAME");
+        assert_eq!(vars[0].nlabel, label);
+        assert_eq!(vars[0].nform, "LONGFMTNAME");
+    }
+
+    #[test]
+    fn test_unknown_variable() {
+        let mut data = Vec::new();
+        for value in [9i16, 1, 0] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.push(b'Z');
+        data.resize(RECORD_LEN, b' ');
+
+        let mut vars = variables();
+        let header = label_header(LABELV8_HEADER, "1");
+        let result = read_label_section(
+            &mut Cursor::new(data),
+            LabelSection::V8,
+            &header,
+            &mut vars,
+            TextMode::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_label_section_round_trip() {
+        let label = "a label that is comfortably longer than forty bytes";
+        let mut plan = DatasetSchema::new("LONGDS");
+        plan.variables = vec![
+            VariableSpec::numeric("LONGVARNAME").with_label(label),
+            VariableSpec::numeric("Y").with_label("Short"),
+        ];
+
+        let (section, bytes) = build_label_section(&plan).unwrap().unwrap();
+        assert_eq!(section, LabelSection::V8);
+        assert_eq!(bytes.len() % RECORD_LEN, 0);
+
+        let header: [u8; RECORD_LEN] = bytes[..RECORD_LEN].try_into().unwrap();
+        assert_eq!(LabelSection::from_header(&header), Some(LabelSection::V8));
+
+        let mut vars = variables();
+        let mut cursor = Cursor::new(&bytes[RECORD_LEN..]);
+        read_label_section(
+            &mut cursor,
+            section,
+            &header,
+            &mut vars,
+            TextMode::default(),
+        )
+        .unwrap();
+        assert_eq!(vars[0].nname, "LONGVARNAME");
+        assert_eq!(vars[0].nlabel, label);
+        assert_eq!(cursor.position() as usize, bytes.len() - RECORD_LEN);
+    }
+
+    #[test]
+    fn test_build_labelv9_section() {
+        let format = crate::dataset::Format::parse("E8601DATETIME19.").unwrap();
+        let mut plan = DatasetSchema::new("LONGDS");
+        plan.variables = vec![
+            VariableSpec::numeric("X").with_label("Short"),
+            VariableSpec::numeric("Y").with_format(format),
+        ];
+
+        let (section, bytes) = build_label_section(&plan).unwrap().unwrap();
+        assert_eq!(section, LabelSection::V9);
+
+        let header: [u8; RECORD_LEN] = bytes[..RECORD_LEN].try_into().unwrap();
+        let mut vars = variables();
+        read_label_section(
+            &mut Cursor::new(&bytes[RECORD_LEN..]),
+            section,
+            &header,
+            &mut vars,
+            TextMode::default(),
+        )
+        .unwrap();
+        assert_eq!(vars[1].nform, "E8601DATETIME");
+        assert_eq!(vars[1].nfl, 19);
+    }
+
+    #[test]
+    fn test_no_label_section_needed() {
+        let mut plan = DatasetSchema::new("DM");
+        plan.variables = vec![VariableSpec::numeric("SUBJECT_AGE").with_label("Age")];
+        assert!(build_label_section(&plan).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_format_description() {
+        let mut name = String::new();
+        let (mut length, mut decimals) = (0, 0);
+
+        apply_format_description("MY_LONG_FMT8.2", &mut name, &mut length, &mut decimals);
+        assert_eq!((name.as_str(), length, decimals), ("MY_LONG_FMT", 8, 2));
+
+        apply_format_description("E8601DATETIME.", &mut name, &mut length, &mut decimals);
+        assert_eq!((name.as_str(), length, decimals), ("E8601DATETIME", 8, 2));
+
+        apply_format_description("LONGFMTNAME", &mut name, &mut length, &mut decimals);
+        assert_eq!(name, "LONGFMTNAME");
+    }
+}
@@ -1,56 +1,18 @@
-//! XPT Version 8 implementation (not yet implemented).
+//! XPT Version 8 implementation.
 //!
-//! This module is a placeholder for future XPT v8 support.
-//! V8 differs from V5 in several ways:
+//! V8 keeps the overall layout of V5 (80-byte records, 140-byte NAMESTR
+//! records, IBM floating-point numerics) and extends it with:
 //!
-//! - Variable names can be up to 32 bytes (vs 8)
-//! - Labels can be up to 256 bytes (vs 40)
-//! - Different header structure
-//! - Different NAMESTR format
+//! - Variable and dataset names up to 32 bytes (`longname` NAMESTR field,
+//!   32-byte name in the member descriptor)
+//! - Labels up to 256 bytes (`LABELV8`/`LABELV9` label section)
+//! - Format and informat names longer than 8 bytes (`LABELV9` section)
+//! - Its own header record names (`LIBV8`, `MEMBV8`, `DSCPTV8`, `NAMSTV8`, `OBSV8`)
 //!
-//! See `README.md` in this module for more details.
+//! Header parsing, observation encoding and decoding are shared with the V5
+//! reader and writer in [`crate::xpt::v5`]; this module holds the V8-specific
+//! pieces.
 
-// Allow dead code for placeholder implementation - these functions
-// are part of the planned v8 API but not yet used.
-#![allow(dead_code)]
-
-use crate::error::{Error, Result};
-use crate::xpt::XptVersion;
-
-/// Checks if V8 operations are supported.
-///
-/// Currently returns `false` as V8 is not implemented.
-#[must_use]
-pub const fn is_supported() -> bool {
-    false
-}
-
-/// Returns an error indicating V8 is not supported.
-///
-/// # Errors
-///
-/// Always returns [`Error::UnsupportedVersion`].
-#[must_use]
-pub fn unsupported_error() -> Error {
-    Error::UnsupportedVersion {
-        version: XptVersion::V8,
-    }
-}
-
-/// Placeholder for future V8 reader.
-///
-/// # Errors
-///
-/// Always returns [`Error::UnsupportedVersion`] as V8 is not yet implemented.
-pub fn read_v8() -> Result<()> {
-    Err(unsupported_error())
-}
-
-/// Placeholder for future V8 writer.
-///
-/// # Errors
-///
-/// Always returns [`Error::UnsupportedVersion`] as V8 is not yet implemented.
-pub fn write_v8() -> Result<()> {
-    Err(unsupported_error())
-}
+pub(crate) mod constants;
+pub(crate) mod labels;
+pub(crate) mod namestr;
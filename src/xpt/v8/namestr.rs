@@ -0,0 +1,155 @@
+//! NAMESTR extensions for XPT v8.
+//!
+//! V8 NAMESTR records are 140 bytes like V5, but repurpose part of the
+//! trailing reserved area:
+//!
+//! | Offset  | Field      | Description                         |
+//! |---------|------------|-------------------------------------|
+//! | 88-119  | `longname` | Full variable name (32 bytes)       |
+//! | 120-121 | `lablen`   | Full label length in bytes          |
+//!
+//! The classic `nname` and `nlabel` fields hold the name and label truncated
+//! to 8 and 40 bytes; labels longer than 40 bytes are stored in full in the
+//! `LABELV8`/`LABELV9` section that follows the NAMESTR records.
+
+use crate::config::TextMode;
+use crate::error::{Error, Result};
+use crate::schema::VariableSpec;
+use crate::xpt::v5::constants::NAMESTR_LEN;
+use crate::xpt::v5::encoding::{decode_text, encode_text, encoded_len};
+use crate::xpt::v5::namestr::{self as v5_namestr, NamestrV5};
+
+use super::constants::LONG_NAME_LEN;
+
+/// Offset of `longname` within [`NamestrV5::rest`] (NAMESTR offset 88).
+const LONG_NAME_OFFSET: usize = 0;
+
+/// Offset of `lablen` within [`NamestrV5::rest`] (NAMESTR offset 120).
+const LABEL_LEN_OFFSET: usize = LONG_NAME_OFFSET + LONG_NAME_LEN;
+
+/// Offset of [`NamestrV5::rest`] within the NAMESTR record.
+const REST_OFFSET: usize = 88;
+
+/// Packs a [`VariableSpec`] into a 140-byte V8 NAMESTR record.
+///
+/// The V5 fields are filled as usual, with the name and label truncated to
+/// 8 and 40 bytes; the full name goes into `longname` and the full label
+/// length, in bytes of the character set of `mode`, into `lablen`.
+///
+/// # Errors
+///
+/// Returns an error if a text field cannot be encoded in `mode`.
+pub(crate) fn pack_namestr(
+    var: &VariableSpec,
+    var_num: usize,
+    mode: TextMode,
+) -> Result<[u8; NAMESTR_LEN]> {
+    let mut buf = v5_namestr::pack_namestr(var, var_num, mode)?;
+
+    let long_name_start = REST_OFFSET + LONG_NAME_OFFSET;
+    let long_name = encode_text(Some(&var.name), LONG_NAME_LEN, mode)
+        .map_err(|e| Error::encoding(e.to_string()))?;
+    buf[long_name_start..long_name_start + LONG_NAME_LEN].copy_from_slice(&long_name);
+
+    let label_len = i16::try_from(encoded_len(&var.label, mode)).unwrap_or(i16::MAX);
+    let label_len_start = REST_OFFSET + LABEL_LEN_OFFSET;
+    buf[label_len_start..label_len_start + 2].copy_from_slice(&label_len.to_be_bytes());
+
+    Ok(buf)
+}
+
+/// Returns the full variable name stored in the V8 `longname` field, if any.
+///
+/// # Errors
+///
+/// Returns an error if the name cannot be decoded with `mode`.
+pub fn long_name(namestr: &NamestrV5, mode: TextMode) -> Result<Option<String>> {
+    let raw = &namestr.rest[LONG_NAME_OFFSET..LONG_NAME_OFFSET + LONG_NAME_LEN];
+    let name = decode_text(raw, mode, false).map_err(|e| Error::encoding(e.to_string()))?;
+    let name = name.trim_end_matches([' ', '\0']);
+    Ok(if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    })
+}
+
+/// Replaces the truncated `nname` with the full V8 `longname`, if present.
+///
+/// # Errors
+///
+/// Returns an error if the name cannot be decoded with `mode`.
+pub fn apply_long_name(namestr: &mut NamestrV5, mode: TextMode) -> Result<()> {
+    if let Some(name) = long_name(namestr, mode)? {
+        namestr.nname = name;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xpt::v5::namestr::unpack_namestr;
+
+    /// Returns the full label length stored in the V8 `lablen` field.
+    fn label_len(namestr: &NamestrV5) -> usize {
+        let raw = [
+            namestr.rest[LABEL_LEN_OFFSET],
+            namestr.rest[LABEL_LEN_OFFSET + 1],
+        ];
+        usize::try_from(i16::from_be_bytes(raw)).unwrap_or(0)
+    }
+
+    fn namestr_with_rest(name: &[u8], lablen: i16) -> NamestrV5 {
+        let mut namestr = NamestrV5 {
+            nname: "LONGVARN".into(),
+            ..NamestrV5::default()
+        };
+        namestr.rest[..name.len()].copy_from_slice(name);
+        namestr.rest[name.len()..LONG_NAME_LEN].fill(b' ');
+        namestr.rest[LABEL_LEN_OFFSET..LABEL_LEN_OFFSET + 2].copy_from_slice(&lablen.to_be_bytes());
+        namestr
+    }
+
+    #[test]
+    fn test_long_name() {
+        let mut namestr = namestr_with_rest(b"LONGVARNAME", 47);
+        assert_eq!(
+            long_name(&namestr, TextMode::default()).unwrap().as_deref(),
+            Some("LONGVARNAME")
+        );
+        assert_eq!(label_len(&namestr), 47);
+
+        apply_long_name(&mut namestr, TextMode::default()).unwrap();
+        assert_eq!(namestr.nname, "LONGVARNAME");
+    }
+
+    #[test]
+    fn test_blank_long_name_keeps_short_name() {
+        let mut namestr = NamestrV5 {
+            nname: "AGE".into(),
+            ..NamestrV5::default()
+        };
+        assert_eq!(long_name(&namestr, TextMode::default()).unwrap(), None);
+        assert_eq!(label_len(&namestr), 0);
+
+        apply_long_name(&mut namestr, TextMode::default()).unwrap();
+        assert_eq!(namestr.nname, "AGE");
+    }
+
+    #[test]
+    fn test_pack_long_name_and_label() {
+        let label = "a label that is comfortably longer than forty bytes";
+        let var = VariableSpec::numeric("LONGVARIABLENAME").with_label(label);
+
+        let packed = pack_namestr(&var, 0, TextMode::default()).unwrap();
+        let mut unpacked = unpack_namestr(&packed, TextMode::default()).unwrap();
+
+        assert_eq!(unpacked.nname, "LONGVARI");
+        assert!(label.starts_with(&unpacked.nlabel));
+        assert_eq!(label_len(&unpacked), label.len());
+
+        apply_long_name(&mut unpacked, TextMode::default()).unwrap();
+        assert_eq!(unpacked.nname, "LONGVARIABLENAME");
+    }
+}
@@ -4,12 +4,13 @@
 
 /// The XPT format version.
 ///
-/// Currently, only [`XptVersion::V5`] is fully implemented. [`XptVersion::V8`] is API-ready but not yet implemented.
+/// Both versions can be read and written; the version of an existing file is
+/// detected automatically.
 ///
 /// # Versions
 ///
 /// - [`XptVersion::V5`] - Traditional SAS Transport format with 8-byte names, 40-byte labels
-/// - [`XptVersion::V8`] - Extended format with 32-byte names, 256-byte labels (not yet implemented)
+/// - [`XptVersion::V8`] - Extended format with 32-byte names, 256-byte labels
 ///
 /// # Example
 ///
@@ -37,10 +38,12 @@ pub enum XptVersion {
 
     /// XPT Version 8 (extended format).
     ///
-    /// **Not yet implemented.** This version supports:
+    /// Not accepted by regulatory agencies. This version supports:
     /// - Longer variable names (up to 32 bytes)
     /// - Longer labels (up to 256 bytes)
+    /// - Format names longer than 8 bytes
     /// - Different header structure
+    /// - IBM floating-point encoding (same as V5)
     V8,
 }
 
@@ -75,10 +78,10 @@ impl XptVersion {
         }
     }
 
-    /// Returns `true` if this version is implemented.
+    /// Returns `true` if reading and writing this version is implemented.
     #[must_use]
     pub const fn is_implemented(&self) -> bool {
-        matches!(self, Self::V5)
+        matches!(self, Self::V5 | Self::V8)
     }
 }
 
@@ -103,7 +106,7 @@ mod tests {
 
         assert!(!XptVersion::V8.is_v5());
         assert!(XptVersion::V8.is_v8());
-        assert!(!XptVersion::V8.is_implemented());
+        assert!(XptVersion::V8.is_implemented());
     }
 
     #[test]
@@ -4,9 +4,10 @@
 //! See: <https://rust-lang.github.io/api-guidelines/checklist.html>
 
 use xportrs::{
-    Agency, Column, ColumnData, ColumnNames, Dataset, DomainCode, Error, IntoIter, Issue, Iter,
-    IterMut, Label, Severity, TextMode, ValidatedWrite, VariableName, VariableRole, Verbosity,
-    XptReaderBuilder, XptVersion, XptWriterBuilder,
+    Agency, Column, ColumnData, ColumnNames, Dataset, DatasetMetadata, DomainCode, Error, Fix,
+    IntoIter, Issue, Iter, IterMut, Label, MemberIssues, Severity, TextMode, ValidatedWrite,
+    VariableMetadata, VariableName, VariableRole, Verbosity, XptReaderBuilder, XptVersion,
+    XptWriterBuilder,
 };
 
 // =============================================================================
@@ -29,11 +30,17 @@ fn public_types_are_send() {
     assert_send::<Label>();
     assert_send::<VariableName>();
 
+    // Metadata types
+    assert_send::<DatasetMetadata>();
+    assert_send::<VariableMetadata>();
+
     // Enums
     assert_send::<VariableRole>();
     assert_send::<Agency>();
     assert_send::<XptVersion>();
     assert_send::<Severity>();
+    assert_send::<Fix>();
+    assert_send::<MemberIssues>();
     assert_send::<Issue>();
     assert_send::<TextMode>();
     assert_send::<Verbosity>();
@@ -52,11 +59,17 @@ fn public_types_are_sync() {
     assert_sync::<Label>();
     assert_sync::<VariableName>();
 
+    // Metadata types
+    assert_sync::<DatasetMetadata>();
+    assert_sync::<VariableMetadata>();
+
     // Enums
     assert_sync::<VariableRole>();
     assert_sync::<Agency>();
     assert_sync::<XptVersion>();
     assert_sync::<Severity>();
+    assert_sync::<Fix>();
+    assert_sync::<MemberIssues>();
     assert_sync::<Issue>();
     assert_sync::<TextMode>();
     assert_sync::<Verbosity>();
@@ -82,11 +95,17 @@ fn public_types_implement_debug() {
     assert_debug::<Label>();
     assert_debug::<VariableName>();
 
+    // Metadata types
+    assert_debug::<DatasetMetadata>();
+    assert_debug::<VariableMetadata>();
+
     // Enums
     assert_debug::<VariableRole>();
     assert_debug::<Agency>();
     assert_debug::<XptVersion>();
     assert_debug::<Severity>();
+    assert_debug::<Fix>();
+    assert_debug::<MemberIssues>();
     assert_debug::<Issue>();
     assert_debug::<TextMode>();
     assert_debug::<Verbosity>();
@@ -205,10 +224,14 @@ fn common_traits_clone() {
     assert_clone::<DomainCode>();
     assert_clone::<Label>();
     assert_clone::<VariableName>();
+    assert_clone::<DatasetMetadata>();
+    assert_clone::<VariableMetadata>();
     assert_clone::<VariableRole>();
     assert_clone::<Agency>();
     assert_clone::<XptVersion>();
     assert_clone::<Severity>();
+    assert_clone::<Fix>();
+    assert_clone::<MemberIssues>();
     assert_clone::<Issue>();
 }
 
@@ -220,10 +243,14 @@ fn common_traits_partial_eq() {
     assert_partial_eq::<DomainCode>();
     assert_partial_eq::<Label>();
     assert_partial_eq::<VariableName>();
+    assert_partial_eq::<DatasetMetadata>();
+    assert_partial_eq::<VariableMetadata>();
     assert_partial_eq::<VariableRole>();
     assert_partial_eq::<Agency>();
     assert_partial_eq::<XptVersion>();
     assert_partial_eq::<Severity>();
+    assert_partial_eq::<Fix>();
+    assert_partial_eq::<MemberIssues>();
     assert_partial_eq::<Issue>();
 }
 
@@ -233,10 +260,14 @@ fn common_traits_eq() {
     assert_eq::<DomainCode>();
     assert_eq::<Label>();
     assert_eq::<VariableName>();
+    assert_eq::<DatasetMetadata>();
+    assert_eq::<VariableMetadata>();
     assert_eq::<VariableRole>();
     assert_eq::<Agency>();
     assert_eq::<XptVersion>();
     assert_eq::<Severity>();
+    assert_eq::<Fix>();
+    assert_eq::<MemberIssues>();
     assert_eq::<Issue>();
 
     // Note: Dataset, Column, ColumnData do NOT implement Eq because they contain f64
@@ -268,6 +299,7 @@ fn common_traits_display() {
     assert_display::<Agency>();
     assert_display::<XptVersion>();
     assert_display::<Severity>();
+    assert_display::<Fix>();
     assert_display::<Issue>();
     assert_display::<Error>();
 }
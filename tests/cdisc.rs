@@ -0,0 +1,236 @@
+//! CDISC Implementation Guide conformance integration tests.
+//!
+//! These tests check datasets against the bundled SDTM-IG v3.4 and ADaM-IG
+//! v1.3.
+
+#![cfg(feature = "cdisc")]
+
+use std::path::PathBuf;
+
+use tempfile::tempdir;
+use xportrs::cdisc::{
+    AddMissing, Standard, adam_ig_v1_3, conformance, load_terminology, sdtm_ig_v3_4, skeleton,
+};
+use xportrs::{
+    Column, ColumnData, Dataset, DatasetMetadata, Error, Issue, VariableMetadata, VariableRole, Xpt,
+};
+
+fn sdtm() -> Standard {
+    sdtm_ig_v3_4().unwrap()
+}
+
+fn text(name: &str, value: &str) -> Column {
+    Column::new(name, ColumnData::String(vec![Some(value.into())]))
+}
+
+fn ae_columns() -> Vec<Column> {
+    vec![
+        text("STUDYID", "STUDY1").with_label("Study Identifier"),
+        text("DOMAIN", "AE").with_label("Domain Abbreviation"),
+        text("USUBJID", "01-001").with_label("Unique Subject Identifier"),
+        Column::new("AESEQ", ColumnData::F64(vec![Some(1.0)])).with_label("Sequence Number"),
+        text("AETERM", "HEADACHE").with_label("Reported Term for the Adverse Event"),
+        text("AEDECOD", "Headache").with_label("Dictionary-Derived Term"),
+    ]
+}
+
+#[test]
+fn test_dm_file_has_required_variables() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/dm.xpt");
+    let dm = Xpt::read(path).unwrap();
+
+    let issues = conformance(&dm, &sdtm());
+    assert!(
+        !issues
+            .iter()
+            .any(|i| matches!(i, Issue::StandardVariableMissing { required: true, .. })),
+        "{issues:?}"
+    );
+}
+
+#[test]
+fn test_writer_reports_conformance_warnings() {
+    let dataset = Dataset::new("AE", ae_columns()).unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.standard(&sdtm());
+    let validated = builder.finalize().unwrap();
+
+    let issues = validated.issues();
+    assert!(issues.iter().all(|i| !i.is_error()), "{issues:?}");
+    // AESTDTC is Expected in SDTM-IG 3.4
+    assert!(issues.contains(&Issue::StandardVariableMissing {
+        variable: "AESTDTC".into(),
+        required: false,
+    }));
+}
+
+#[test]
+fn test_writer_applies_ig_defaults() {
+    let mut columns = ae_columns();
+    columns[0] = text("STUDYID", "STUDY1");
+    columns[4] = text("AETERM", "HEADACHE");
+    let dataset = Dataset::new("AE", columns).unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.standard(&sdtm()).variable_metadata(vec![
+        VariableMetadata::new("AE", "AETERM").with_label("Term"),
+    ]);
+    let validated = builder.finalize().unwrap();
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+    validated.write_path(&path).unwrap();
+
+    let ae = Xpt::read(&path).unwrap();
+    assert_eq!(ae.dataset_label(), Some("Adverse Events"));
+    assert_eq!(
+        ae.column("STUDYID").unwrap().label(),
+        Some("Study Identifier")
+    );
+    // Explicit metadata is kept
+    assert_eq!(ae.column("AETERM").unwrap().label(), Some("Term"));
+}
+
+#[test]
+fn test_metadata_from_standard() {
+    let standard = sdtm();
+    let aeseq = standard
+        .variables_for_dataset("AE")
+        .into_iter()
+        .find(|v| v.name == "AESEQ")
+        .unwrap();
+
+    let meta = VariableMetadata::from(aeseq);
+    assert_eq!(meta.label.as_deref(), Some("Sequence Number"));
+    assert_eq!(meta.role, Some(VariableRole::Identifier));
+    assert!(meta.xpt_type.is_some_and(|t| t.is_numeric()));
+
+    let meta = DatasetMetadata::from(standard.dataset("AE").unwrap());
+    assert_eq!(meta.dataset_label.as_deref(), Some("Adverse Events"));
+}
+
+#[test]
+fn test_writer_fails_on_type_mismatch() {
+    let mut columns = ae_columns();
+    columns[3] = text("AESEQ", "1");
+    let dataset = Dataset::new("AE", columns).unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.standard(&sdtm());
+    let err = builder.finalize().unwrap_err();
+    assert!(matches!(err, Error::ValidationFailed { .. }), "{err}");
+}
+
+#[test]
+fn test_adam_dataset_checked_against_structure() {
+    let standard = adam_ig_v1_3().unwrap();
+    let adlb = Dataset::new(
+        "ADLB",
+        vec![
+            text("STUDYID", "STUDY1"),
+            text("USUBJID", "01-001"),
+            text("PARAMCD", "ALT"),
+            Column::new("AVAL", ColumnData::F64(vec![Some(21.0)])),
+        ],
+    )
+    .unwrap();
+
+    let issues = conformance(&adlb, &standard);
+    assert!(
+        !issues
+            .iter()
+            .any(|i| matches!(i, Issue::DatasetNotInStandard { .. })),
+        "{issues:?}"
+    );
+    // PARAM is Required in the Basic Data Structure
+    assert!(issues.contains(&Issue::StandardVariableMissing {
+        variable: "PARAM".into(),
+        required: true,
+    }));
+}
+
+#[test]
+fn test_conformance_reports_order() {
+    let mut columns = ae_columns();
+    columns.swap(3, 4);
+    let dataset = Dataset::new("AE", columns).unwrap();
+
+    let issues = conformance(&dataset, &sdtm());
+    assert!(issues.contains(&Issue::StandardOrderMismatch {
+        variable: "AESEQ".into(),
+        after: "AETERM".into(),
+    }));
+    assert!(issues.iter().all(|i| !i.is_error()), "{issues:?}");
+}
+
+#[test]
+fn test_writer_reports_values_outside_codelist() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("sdtm_terminology.txt");
+    std::fs::write(
+        &path,
+        "Code\tCodelist Code\tCodelist Extensible (Yes/No)\tCodelist Name\t\
+         CDISC Submission Value\tCDISC Synonym(s)\tCDISC Definition\tNCI Preferred Term\n\
+         C66769\t\tNo\tSeverity/Intensity Scale for Adverse Events\tAESEV\t\t\t\n\
+         C41338\tC66769\t\tSeverity/Intensity Scale for Adverse Events\tMILD\t\t\t\n\
+         C41339\tC66769\t\tSeverity/Intensity Scale for Adverse Events\tMODERATE\t\t\t\n\
+         C41340\tC66769\t\tSeverity/Intensity Scale for Adverse Events\tSEVERE\t\t\t\n",
+    )
+    .unwrap();
+    let ct = load_terminology(&path).unwrap();
+
+    let mut columns = ae_columns();
+    columns.push(text("AESEV", "VERY BAD").with_label("Severity/Intensity"));
+    let dataset = Dataset::new("AE", columns).unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.standard(&sdtm()).terminology(&ct);
+    let err = builder.finalize().unwrap_err();
+    assert!(err.to_string().contains("not in codelist AESEV"), "{err}");
+}
+
+#[test]
+fn test_skeleton_arranges_dataset() {
+    let dataset = Dataset::new(
+        "AE",
+        vec![
+            text("EXTRA", "X"),
+            text("AETERM", "HEADACHE").with_label("Term"),
+            Column::new("AESEQ", ColumnData::F64(vec![Some(1.0)])),
+            text("USUBJID", "01-001"),
+        ],
+    )
+    .unwrap();
+
+    let ae = skeleton(dataset.clone(), &sdtm(), AddMissing::None);
+    let names: Vec<_> = ae.column_names().collect();
+    assert_eq!(names, ["USUBJID", "AESEQ", "AETERM", "EXTRA"]);
+    assert_eq!(ae.dataset_label(), Some("Adverse Events"));
+
+    let usubjid = ae.column("USUBJID").unwrap();
+    assert_eq!(usubjid.label(), Some("Unique Subject Identifier"));
+    assert_eq!(usubjid.role(), Some(VariableRole::Identifier));
+    // Existing labels are kept
+    assert_eq!(ae.column("AETERM").unwrap().label(), Some("Term"));
+    assert_eq!(
+        ae.column("AETERM").unwrap().role(),
+        Some(VariableRole::Topic)
+    );
+
+    let ae = skeleton(dataset, &sdtm(), AddMissing::Expected);
+    let aestdtc = ae.column("AESTDTC").unwrap();
+    assert!(aestdtc.is_character());
+    assert_eq!(aestdtc.len(), 1);
+    assert_eq!(aestdtc.role(), Some(VariableRole::Timing));
+    // Required and Permissible variables are not added
+    assert!(ae.column("STUDYID").is_none());
+    assert!(ae.column("AESEV").is_none());
+    assert!(!conformance(&ae, &sdtm()).iter().any(|i| matches!(
+        i,
+        Issue::StandardVariableMissing {
+            required: false,
+            ..
+        }
+    )));
+}
@@ -0,0 +1,260 @@
+//! Define-XML import integration tests.
+//!
+//! These tests drive writes from the Define-XML 2.1 document in
+//! `tests/data/define.xml`, and generate Define-XML from written datasets.
+
+#![cfg(feature = "define-xml")]
+
+use std::path::PathBuf;
+
+use tempfile::tempdir;
+use xportrs::define::{DefineDataset, DefineStandard, DefineXml};
+use xportrs::{
+    Column, ColumnData, Dataset, DatasetMetadata, Error, Issue, VariableMetadata, VariableRole,
+    Xpt, XptVarType,
+};
+
+fn define() -> DefineXml {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/define.xml");
+    DefineXml::from_path(path).unwrap()
+}
+
+fn ae_columns() -> Vec<Column> {
+    vec![
+        Column::new("AETERM", ColumnData::String(vec![Some("HEADACHE".into())])),
+        Column::new("AESEQ", ColumnData::I64(vec![Some(1)])),
+        Column::new(
+            "USUBJID",
+            ColumnData::String(vec![Some("STUDY1-001".into())]),
+        ),
+        Column::new("STUDYID", ColumnData::String(vec![Some("STUDY1".into())])),
+    ]
+}
+
+/// Test that the definition drives order, labels and lengths.
+#[test]
+fn test_write_from_define() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    let define = define();
+    assert_eq!(define.version(), Some("2.1.0"));
+    assert_eq!(define.study_name(), Some("STUDY1"));
+    let ae = define.dataset("AE").unwrap();
+    assert_eq!(ae.class.as_deref(), Some("EVENTS"));
+    assert_eq!(ae.archive_location.as_deref(), Some("ae.xpt"));
+
+    let mut builder = Xpt::writer(Dataset::new("AE", ae_columns()).unwrap());
+    builder.define_xml(&define);
+    let validated = builder.finalize().unwrap();
+
+    // AESTDTC and AESTDY are permissible, so their absence is only a warning
+    assert!(!validated.has_errors());
+    assert_eq!(
+        validated.issues(),
+        [
+            Issue::DefineVariableMissing {
+                variable: "AESTDTC".into(),
+                mandatory: false,
+            },
+            Issue::DefineVariableMissing {
+                variable: "AESTDY".into(),
+                mandatory: false,
+            },
+        ]
+    );
+    validated.write_path(&path).unwrap();
+
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(loaded.dataset_label(), Some("Adverse Events"));
+
+    let names: Vec<&str> = loaded.columns().iter().map(Column::name).collect();
+    assert_eq!(names, ["STUDYID", "USUBJID", "AESEQ", "AETERM"]);
+
+    let aeterm = loaded.column("AETERM").unwrap();
+    assert_eq!(aeterm.label(), Some("Reported Term for the Adverse Event"));
+    assert_eq!(aeterm.explicit_length(), Some(200));
+    assert_eq!(loaded.column("STUDYID").unwrap().explicit_length(), Some(8));
+}
+
+/// Test that mismatches between the data and the definition are reported.
+#[test]
+fn test_define_mismatches() {
+    let mut columns = ae_columns();
+    columns.push(Column::new(
+        "AEDECOD",
+        ColumnData::String(vec![Some("Headache".into())]),
+    ));
+    // STUDYID value longer than its defined length of 8
+    columns[3] = Column::new(
+        "STUDYID",
+        ColumnData::String(vec![Some("STUDY1-EXTENSION".into())]),
+    );
+
+    let mut builder = Xpt::writer(Dataset::new("AE", columns).unwrap());
+    builder.define_xml(&define());
+    let Err(Error::ValidationFailed { message }) = builder.finalize() else {
+        panic!("expected validation to fail");
+    };
+    assert!(message.contains("STUDYID"));
+
+    // A dataset that is not defined only gets a warning
+    let dm = Dataset::new("DM", ae_columns()).unwrap();
+    let mut builder = Xpt::writer(dm);
+    builder.define_xml(&define());
+    let validated = builder.finalize().unwrap();
+    assert!(!validated.has_errors());
+    assert_eq!(
+        validated.issues()[0],
+        Issue::DatasetNotInDefine {
+            dataset: "DM".into()
+        }
+    );
+}
+
+/// Test that a generated define.xml describes the files written and can
+/// drive the next write without mismatches.
+#[test]
+fn test_generate_define() {
+    let dir = tempdir().unwrap();
+
+    let ae = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![
+            Column::with_role(
+                "USUBJID",
+                VariableRole::Identifier,
+                ColumnData::String(vec![Some("001".into()), Some("001".into())]),
+            )
+            .with_label("Unique Subject Identifier"),
+            Column::with_role(
+                "AETERM",
+                VariableRole::Topic,
+                ColumnData::String(vec![Some("HEADACHE".into()), None]),
+            )
+            .with_label("Reported Term for the Adverse Event"),
+            Column::new("AETOXGR", ColumnData::F64(vec![Some(1.0), Some(2.5)]))
+                .with_label("Standard Toxicity Grade"),
+        ],
+    )
+    .unwrap();
+
+    let validated = Xpt::writer(ae.clone()).finalize().unwrap();
+    let ae_def = DefineDataset::from(&validated)
+        .with_standard(DefineStandard::new("SDTMIG", "3.4"))
+        .with_class("EVENTS")
+        .with_structure("One record per adverse event per subject");
+    let files = validated.write_path(dir.path().join("ae.xpt")).unwrap();
+
+    let mut define = DefineXml::new("STUDY1");
+    for dataset in ae_def.for_files(&files) {
+        define.push(dataset);
+    }
+
+    let path = dir.path().join("define.xml");
+    define.write_path(&path).unwrap();
+    let parsed = DefineXml::from_path(&path).unwrap();
+    assert_eq!(parsed.datasets(), define.datasets());
+
+    let ae_def = parsed.dataset("AE").unwrap();
+    assert_eq!(ae_def.label.as_deref(), Some("Adverse Events"));
+    assert_eq!(ae_def.class.as_deref(), Some("EVENTS"));
+    assert_eq!(ae_def.archive_location.as_deref(), Some("ae.xpt"));
+    assert!(ae_def.repeating);
+
+    let usubjid = ae_def.variable("USUBJID").unwrap();
+    assert_eq!(usubjid.data_type, "text");
+    assert_eq!(usubjid.length, Some(3));
+    assert_eq!(usubjid.role, Some(VariableRole::Identifier));
+    assert!(usubjid.mandatory);
+    assert!(!ae_def.variable("AETERM").unwrap().mandatory);
+
+    let grade = ae_def.variable("AETOXGR").unwrap();
+    assert_eq!(grade.data_type, "float");
+    assert_eq!(grade.length, Some(2));
+    assert_eq!(grade.significant_digits, Some(1));
+    assert_eq!(grade.order, Some(3));
+
+    // The generated define matches the data it was generated from
+    let mut builder = Xpt::writer(ae);
+    builder.define_xml(&parsed);
+    assert!(builder.finalize().unwrap().issues().is_empty());
+}
+
+/// Test that the parts of a split dataset are defined as datasets of their
+/// own, archived in their files.
+#[test]
+fn test_generate_define_split_files() {
+    let ae = DefineDataset {
+        name: "AE".into(),
+        ..DefineDataset::default()
+    };
+
+    let single = ae.clone().for_files(&["out/ae.xpt"]);
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].name, "AE");
+    assert_eq!(single[0].archive_location.as_deref(), Some("ae.xpt"));
+
+    let parts = ae.for_files(&["out/ae_001.xpt", "out/ae_002.xpt"]);
+    let names: Vec<&str> = parts.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["AE_001", "AE_002"]);
+    assert!(parts.iter().all(|d| d.sas_name.as_deref() == Some("AE")));
+    assert_eq!(parts[1].archive_location.as_deref(), Some("ae_002.xpt"));
+}
+
+/// Test defining a dataset from its specification.
+#[test]
+fn test_generate_define_from_metadata() {
+    let dataset = DatasetMetadata::new("AE").with_label("Adverse Events");
+    let variables = [
+        VariableMetadata::new("AE", "AESEQ")
+            .with_xpt_type(XptVarType::Numeric)
+            .with_order(2),
+        VariableMetadata::new("AE", "USUBJID")
+            .with_xpt_type(XptVarType::Character)
+            .with_length(20)
+            .with_order(1),
+        VariableMetadata::new("DM", "AGE"),
+    ];
+
+    let ae = DefineDataset::from_metadata(&dataset, &variables);
+    assert_eq!(ae.label.as_deref(), Some("Adverse Events"));
+    let names: Vec<&str> = ae.variables.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, ["USUBJID", "AESEQ"]);
+    assert_eq!(ae.variables[0].data_type, "text");
+    assert_eq!(ae.variables[0].length, Some(20));
+    assert_eq!(ae.variables[1].data_type, "float");
+}
+
+/// Test taking the standard, class and structure from the bundled SDTM-IG.
+#[cfg(feature = "cdisc")]
+#[test]
+fn test_generate_define_with_ig() {
+    let standard = xportrs::cdisc::sdtm_ig_v3_4().unwrap();
+    let ae = DefineDataset {
+        name: "AE".into(),
+        ..DefineDataset::default()
+    }
+    .with_ig(&standard);
+
+    assert_eq!(ae.standard, Some(DefineStandard::new("SDTMIG", "3.4")));
+    assert_eq!(ae.class.as_deref(), Some("EVENTS"));
+    assert!(ae.structure.is_some());
+
+    // The IG's "Special-Purpose" is "SPECIAL PURPOSE" in Define-XML
+    let dm = DefineDataset {
+        name: "DM".into(),
+        ..DefineDataset::default()
+    }
+    .with_ig(&standard);
+    assert_eq!(dm.class.as_deref(), Some("SPECIAL PURPOSE"));
+
+    // ADaM datasets take the class of their data structure
+    let adlb = DefineDataset {
+        name: "ADLB".into(),
+        ..DefineDataset::default()
+    }
+    .with_ig(&xportrs::cdisc::adam_ig_v1_3().unwrap());
+    assert_eq!(adlb.class.as_deref(), Some("BASIC DATA STRUCTURE"));
+}
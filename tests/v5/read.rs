@@ -3,7 +3,8 @@
 use std::path::PathBuf;
 
 use cdisc_metadata::sdtm_ig_v3_4;
-use xportrs::Xpt;
+use tempfile::tempdir;
+use xportrs::{Agency, Column, ColumnData, Dataset, Issue, TextMode, Xpt};
 
 /// Get the path to test data directory.
 fn test_data_dir() -> PathBuf {
@@ -46,6 +47,54 @@ fn test_read_lb() {
     assert!(dataset.nrows() > 100, "LB should have many rows");
 }
 
+/// Test reading lb.xpt in batches matches reading it whole.
+#[test]
+fn test_read_lb_batches() {
+    let path = test_data_dir().join("lb.xpt");
+    let whole = Xpt::read(&path).expect("Failed to read lb.xpt");
+
+    let batches: Vec<_> = Xpt::reader(&path)
+        .unwrap()
+        .batches(100)
+        .expect("Failed to open batches")
+        .collect::<Result<_, _>>()
+        .expect("Failed to read batch");
+
+    assert_eq!(batches.len(), whole.nrows().div_ceil(100));
+    assert!(
+        batches[..batches.len() - 1]
+            .iter()
+            .all(|b| b.nrows() == 100)
+    );
+    assert_eq!(
+        batches.iter().map(xportrs::Dataset::nrows).sum::<usize>(),
+        whole.nrows()
+    );
+
+    // Metadata is carried on every batch, and values line up across batches
+    let last = batches.last().unwrap();
+    assert_eq!(last.domain_code(), "LB");
+    assert_eq!(last.dataset_label(), whole.dataset_label());
+    let (ColumnData::String(batch_ids), ColumnData::String(all_ids)) = (
+        last.column("USUBJID").unwrap().data(),
+        whole.column("USUBJID").unwrap().data(),
+    ) else {
+        panic!("USUBJID should be character");
+    };
+    assert_eq!(batch_ids.last(), all_ids.last());
+    assert_eq!(
+        last.column("USUBJID").unwrap().label(),
+        whole.column("USUBJID").unwrap().label()
+    );
+
+    assert!(
+        Xpt::reader(&path)
+            .unwrap()
+            .member_batches("XX", 10)
+            .is_err()
+    );
+}
+
 /// Test reading relrec.xpt (Related Records domain).
 #[test]
 fn test_read_relrec() {
@@ -122,6 +171,125 @@ fn test_inspect_dm() {
     assert_eq!(info.members.len(), 1);
     let names: Vec<_> = info.member_names().collect();
     assert!(names.contains(&"DM"));
+    assert_eq!(info.members[0].obs_count, Xpt::read(&path).unwrap().nrows());
+}
+
+/// Test validating existing files.
+#[test]
+fn test_validate_file() {
+    let members = Xpt::validate(test_data_dir().join("dm.xpt"), Some(Agency::FDA)).unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].name, "DM");
+    assert!(!members[0].has_errors(), "{:?}", members[0].issues);
+
+    // A file with non-ASCII values, tampered with after writing
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+    let dataset = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![
+            Column::new("AESEQ", ColumnData::F64(vec![Some(1.0)])).with_label("Sequence Number"),
+            Column::new("AETERM", ColumnData::String(vec![Some("Céphalée".into())]))
+                .with_label("Reported Term"),
+        ],
+    )
+    .unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.text_mode(TextMode::StrictUtf8);
+    builder.finalize().unwrap().write_path(&path).unwrap();
+
+    let members = Xpt::validate(&path, Some(Agency::FDA)).unwrap();
+    assert_eq!(
+        members[0].issues,
+        [Issue::NonAsciiValue {
+            variable: "AETERM".into(),
+            row: 0,
+            count: 1,
+        }]
+    );
+    assert!(Xpt::validate(&path, None).unwrap()[0].issues.is_empty());
+
+    let obs_offset = Xpt::inspect(&path).unwrap().members[0].obs_offset as usize;
+    let mut bytes = std::fs::read(&path).unwrap();
+    // The second NAMESTR record starts 140 bytes into the NAMESTR section
+    let npos = obs_offset - 80 - 4 * 80 + 140 + 84;
+    bytes[npos..npos + 4].copy_from_slice(&4i32.to_be_bytes());
+    bytes.extend_from_slice(&[b' '; 80]);
+    bytes[obs_offset + 80..obs_offset + 100].copy_from_slice(b"HEADER RECORD*******");
+    *bytes.last_mut().unwrap() = b'X';
+    std::fs::write(&path, &bytes).unwrap();
+
+    let members = Xpt::validate(&path, Some(Agency::FDA)).unwrap();
+    let obs_offset = obs_offset as u64;
+    assert_eq!(
+        members[0].issues,
+        [
+            Issue::PositionInconsistent {
+                variable: "AETERM".into(),
+                recorded: 4,
+                expected: 8,
+            },
+            Issue::UnknownRecord {
+                dataset: "AE".into(),
+                offset: obs_offset + 80,
+            },
+            Issue::NonBlankPadding {
+                dataset: "AE".into(),
+                offset: obs_offset + 159,
+            },
+        ]
+    );
+}
+
+/// Test validating a file in a legacy code page.
+#[test]
+fn test_validate_file_text_mode() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+    let dataset = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![
+            Column::new("AESEQ", ColumnData::F64(vec![Some(1.0)])).with_label("Sequence Number"),
+            Column::new("AETERM", ColumnData::String(vec![Some("Céphalée".into())]))
+                .with_label("Reported Term"),
+        ],
+    )
+    .unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.text_mode(TextMode::Latin1);
+    builder.finalize().unwrap().write_path(&path).unwrap();
+
+    let members = Xpt::validate_with(&path, None, TextMode::Latin1).unwrap();
+    assert!(members[0].issues.is_empty(), "{:?}", members[0].issues);
+    let members = Xpt::validate_with(&path, Some(Agency::FDA), TextMode::Latin1).unwrap();
+    assert_eq!(
+        members[0].issues,
+        [Issue::NonAsciiValue {
+            variable: "AETERM".into(),
+            row: 0,
+            count: 1,
+        }]
+    );
+
+    // Decoded as UTF-8, each invalid byte becomes a 3-byte replacement
+    // character; the value still fits the variable it was stored in
+    let members = Xpt::validate(&path, None).unwrap();
+    assert!(members[0].issues.is_empty(), "{:?}", members[0].issues);
+
+    // A single Windows-1252 byte in a $1 variable
+    let dataset = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![Column::new("AEFLAG", ColumnData::String(vec![Some("é".into())])).with_label("Flag")],
+    )
+    .unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.text_mode(TextMode::Windows1252);
+    builder.finalize().unwrap().write_path(&path).unwrap();
+    let members = Xpt::validate(&path, None).unwrap();
+    assert!(members[0].issues.is_empty(), "{:?}", members[0].issues);
 }
 
 /// Test reading all XPT files in test data directory.
@@ -143,3 +311,127 @@ fn test_read_all_xpt_files() {
         assert!(dataset.ncols() > 0, "{} should have columns", filename);
     }
 }
+
+/// Test reading a subset of columns.
+#[test]
+fn test_read_projected_columns() {
+    let path = test_data_dir().join("dm.xpt");
+    let whole = Xpt::read(&path).unwrap();
+
+    let dm = Xpt::reader(&path)
+        .unwrap()
+        .columns(["age", "USUBJID"])
+        .read()
+        .expect("Failed to read projected columns");
+
+    // File order is kept and metadata is preserved
+    let names: Vec<_> = dm.columns().iter().map(xportrs::Column::name).collect();
+    assert_eq!(names, vec!["USUBJID", "AGE"]);
+    assert_eq!(dm.nrows(), whole.nrows());
+    assert_eq!(
+        dm.column("AGE").unwrap().label(),
+        whole.column("AGE").unwrap().label()
+    );
+    assert_eq!(
+        dm.column("AGE").unwrap().data(),
+        whole.column("AGE").unwrap().data()
+    );
+
+    let batch = Xpt::reader(&path)
+        .unwrap()
+        .columns(["USUBJID"])
+        .batches(10)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.ncols(), 1);
+
+    assert!(
+        Xpt::reader(&path)
+            .unwrap()
+            .columns(["NOTAVAR"])
+            .read()
+            .is_err()
+    );
+}
+
+/// Test reading a range of rows by seeking.
+#[test]
+fn test_read_row_range() {
+    let path = test_data_dir().join("lb.xpt");
+    let whole = Xpt::read(&path).unwrap();
+    let ColumnData::String(all_ids) = whole.column("USUBJID").unwrap().data() else {
+        panic!("USUBJID should be character");
+    };
+
+    let page = Xpt::reader(&path)
+        .unwrap()
+        .row_range(100..150)
+        .read()
+        .unwrap();
+    assert_eq!(page.nrows(), 50);
+    let ColumnData::String(ids) = page.column("USUBJID").unwrap().data() else {
+        panic!("USUBJID should be character");
+    };
+    assert_eq!(ids.as_slice(), &all_ids[100..150]);
+
+    let tail = Xpt::reader(&path)
+        .unwrap()
+        .skip_rows(whole.nrows() - 3)
+        .read()
+        .unwrap();
+    assert_eq!(tail.nrows(), 3);
+
+    let past_end = Xpt::reader(&path)
+        .unwrap()
+        .skip_rows(whole.nrows() + 10)
+        .read()
+        .unwrap();
+    assert_eq!(past_end.nrows(), 0);
+    assert_eq!(past_end.ncols(), whole.ncols());
+
+    // Batches respect the range too
+    let rows: usize = Xpt::reader(&path)
+        .unwrap()
+        .row_range(10..35)
+        .batches(10)
+        .unwrap()
+        .map(|b| b.unwrap().nrows())
+        .sum();
+    assert_eq!(rows, 25);
+}
+
+/// Test the text decoding, blank handling and row limit options.
+#[test]
+fn test_read_text_options() {
+    let path = test_data_dir().join("dm.xpt");
+
+    let strict = Xpt::reader(&path)
+        .unwrap()
+        .text_mode(TextMode::StrictUtf8)
+        .row_limit(5)
+        .read()
+        .unwrap();
+    assert_eq!(strict.nrows(), 5);
+
+    let padded = Xpt::reader(&path)
+        .unwrap()
+        .preserve_blanks(true)
+        .row_limit(5)
+        .read()
+        .unwrap();
+    let col = padded.column("USUBJID").unwrap();
+    let ColumnData::String(ids) = col.data() else {
+        panic!("USUBJID should be character");
+    };
+    let width = col.explicit_length().unwrap();
+    assert!(ids.iter().flatten().all(|id| id.len() == width));
+
+    let ColumnData::String(trimmed) = strict.column("USUBJID").unwrap().data() else {
+        panic!("USUBJID should be character");
+    };
+    for (trimmed, padded) in trimmed.iter().zip(ids) {
+        assert_eq!(trimmed.as_deref(), padded.as_deref().map(str::trim_end));
+    }
+}
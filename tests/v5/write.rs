@@ -3,7 +3,10 @@
 use std::path::PathBuf;
 
 use tempfile::tempdir;
-use xportrs::{Agency, Column, ColumnData, Dataset, Format, Xpt};
+use xportrs::{
+    Agency, Column, ColumnData, Dataset, DatasetMetadata, Error, Fix, Format, Issue,
+    SasMissingValue, TextMode, VariableMetadata, Xpt, XptVarType,
+};
 
 /// Get the path to test data directory.
 fn test_data_dir() -> PathBuf {
@@ -75,6 +78,217 @@ fn test_fda_agency_validation() {
     assert_eq!(loaded.domain_code(), "DM");
 }
 
+/// Test that character values are checked row by row at finalize.
+#[test]
+fn test_character_value_checks() {
+    let terms = ColumnData::String(vec![Some("HEADACHE".into()), Some("Céphalée".into())]);
+
+    // FDA requires ASCII values
+    let dataset = Dataset::new("AE", vec![Column::new("AETERM", terms.clone())]).unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.agency(Agency::FDA);
+    let Err(Error::ValidationFailed { message }) = builder.finalize() else {
+        panic!("expected validation to fail");
+    };
+    assert!(message.contains("value of variable 'AETERM' in row 1 contains non-ASCII"));
+
+    // PMDA allows them
+    let dataset = Dataset::new("AE", vec![Column::new("AETERM", terms)]).unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.agency(Agency::PMDA);
+    assert!(!builder.finalize().unwrap().has_errors());
+
+    // ISO 8601 dates do not fit a length of 8
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 15);
+    let dataset = Dataset::new(
+        "AE",
+        vec![Column::new("AESTDTC", ColumnData::Date(vec![None, date]))],
+    )
+    .unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.variable_metadata([VariableMetadata::new("AE", "AESTDTC")
+        .with_xpt_type(XptVarType::Character)
+        .with_length(8)]);
+    let Err(Error::ValidationFailed { message }) = builder.finalize() else {
+        panic!("expected validation to fail");
+    };
+    assert!(message.contains("in row 1 is truncated from 10 to 8 bytes"));
+}
+
+/// Test that truncation fails finalize unless it is allowed.
+#[test]
+fn test_allow_truncation() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    let dataset = || {
+        Dataset::new(
+            "AE",
+            vec![
+                Column::new(
+                    "AETERM",
+                    ColumnData::String(vec![Some("HEADACHE".into()), Some("RASH".into())]),
+                )
+                .with_label("Reported Term for the Adverse Event (Verbatim)"),
+            ],
+        )
+        .unwrap()
+    };
+    let spec = [VariableMetadata::new("AE", "AETERM").with_length(4)];
+
+    let mut builder = Xpt::writer(dataset());
+    builder.variable_metadata(spec.clone());
+    let Err(Error::ValidationFailed { message }) = builder.finalize() else {
+        panic!("expected validation to fail");
+    };
+    assert!(message.contains("label is truncated from 46 to 40 bytes"));
+    assert!(message.contains("in row 0 is truncated from 8 to 4 bytes"));
+
+    let mut builder = Xpt::writer(dataset());
+    builder.variable_metadata(spec).allow_truncation(true);
+    let validated = builder.finalize().unwrap();
+    assert!(!validated.has_errors());
+    assert!(validated.issues().contains(&Issue::ValueTruncated {
+        variable: "AETERM".into(),
+        row: 0,
+        count: 1,
+        actual: 8,
+        truncated: 4,
+        allowed: true,
+    }));
+    validated.write_path(&path).unwrap();
+
+    let loaded = Xpt::read(&path).unwrap();
+    let aeterm = loaded.column("AETERM").unwrap();
+    assert_eq!(
+        aeterm.label(),
+        Some("Reported Term for the Adverse Event (Ver")
+    );
+    assert_eq!(
+        aeterm.data(),
+        &ColumnData::String(vec![Some("HEAD".into()), Some("RASH".into())])
+    );
+}
+
+/// Test that auto-fix repairs an FDA dataset and records every change.
+#[test]
+fn test_auto_fix() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    let dataset = Dataset::new(
+        "ae",
+        vec![
+            Column::new(
+                "aeterm",
+                ColumnData::String(vec![Some("Céphalée  ".into()), Some("RASH".into())]),
+            )
+            .with_label("Reported Term for the Adverse Event (Verbatim)"),
+        ],
+    )
+    .unwrap();
+
+    // Without auto-fix the dataset is rejected
+    let mut builder = Xpt::writer(dataset.clone());
+    builder.agency(Agency::FDA);
+    assert!(builder.finalize().is_err());
+
+    let mut builder = Xpt::writer(dataset);
+    builder.agency(Agency::FDA).auto_fix(true);
+    let validated = builder.finalize().unwrap();
+    assert!(!validated.has_errors());
+
+    let fixes: Vec<&Issue> = validated
+        .issues()
+        .iter()
+        .filter(|i| matches!(i, Issue::Fixed { .. }))
+        .collect();
+    assert_eq!(fixes.len(), 5, "{fixes:?}");
+    assert!(fixes.contains(&&Issue::Fixed {
+        name: "AETERM".into(),
+        is_dataset: false,
+        fix: Fix::ValuesTransliterated { values: 1 },
+    }));
+    validated.write_path(&path).unwrap();
+
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(loaded.domain_code(), "AE");
+    let aeterm = loaded.column("AETERM").unwrap();
+    assert_eq!(aeterm.label(), Some("Reported Term for the Adverse Event"));
+    assert_eq!(aeterm.explicit_length(), Some(8));
+    assert_eq!(
+        aeterm.data(),
+        &ColumnData::String(vec![Some("Cephalee".into()), Some("RASH".into())])
+    );
+
+    // Lengths set on the column are kept
+    let column = |value: &str| {
+        Dataset::new(
+            "AE",
+            vec![
+                Column::new("AETERM", ColumnData::String(vec![Some(value.into())])).with_length(20),
+            ],
+        )
+        .unwrap()
+    };
+    let mut builder = Xpt::writer(column("AB"));
+    builder.auto_fix(true);
+    let validated = builder.finalize().unwrap();
+    assert!(
+        !validated
+            .issues()
+            .iter()
+            .any(|i| matches!(i, Issue::Fixed { .. }))
+    );
+    let column_path = dir.path().join("ae_column.xpt");
+    validated.write_path(&column_path).unwrap();
+    let written = Xpt::read(&column_path).unwrap();
+    assert_eq!(
+        written.column("AETERM").unwrap().explicit_length(),
+        Some(20)
+    );
+
+    // Lengths from variable metadata are kept
+    let mut builder = Xpt::writer(loaded);
+    builder
+        .variable_metadata([VariableMetadata::new("AE", "AETERM").with_length(200)])
+        .auto_fix(true);
+    let validated = builder.finalize().unwrap();
+    assert!(
+        !validated
+            .issues()
+            .iter()
+            .any(|i| matches!(i, Issue::Fixed { .. }))
+    );
+    validated.write_path(&path).unwrap();
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(
+        loaded.column("AETERM").unwrap().explicit_length(),
+        Some(200)
+    );
+
+    // Streaming writes keep room for longer values in later chunks
+    let chunk = |value: &str| {
+        Dataset::new(
+            "AE",
+            vec![
+                Column::new("AETERM", ColumnData::String(vec![Some(value.into())]))
+                    .with_length(200),
+            ],
+        )
+        .unwrap()
+    };
+    let mut builder = Xpt::writer(chunk("RASH"));
+    builder.auto_fix(true);
+    let mut stream = builder
+        .finalize()
+        .unwrap()
+        .stream_to(std::io::Cursor::new(Vec::new()))
+        .unwrap();
+    stream.write_chunk(&chunk("ABDOMINAL PAIN")).unwrap();
+    assert!(stream.issues().is_empty());
+}
+
 /// Test column length mismatch detection.
 #[test]
 fn test_column_length_mismatch() {
@@ -169,6 +383,236 @@ fn test_missing_values() {
     assert_eq!(loaded.columns()[0].len(), original_nrows);
 }
 
+/// Test that rows of blank character values are read back.
+#[test]
+fn test_blank_rows_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("suppae.xpt");
+
+    let qval = |v: Option<&str>| v.map(|v| format!("{v:<100}"));
+    let dataset = Dataset::new(
+        "SUPPAE",
+        vec![
+            Column::new(
+                "QNAM",
+                ColumnData::String(vec![
+                    Some("AETRTEM".into()),
+                    None,
+                    Some("AESOSP".into()),
+                    None,
+                ]),
+            ),
+            Column::new(
+                "QVAL",
+                ColumnData::String(vec![qval(Some("Y")), None, qval(Some("RASH")), None]),
+            ),
+        ],
+    )
+    .unwrap();
+    Xpt::writer(dataset)
+        .finalize()
+        .unwrap()
+        .write_path(&path)
+        .unwrap();
+
+    assert_eq!(Xpt::inspect(&path).unwrap().members[0].obs_count, 4);
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(loaded.nrows(), 4);
+    assert_eq!(
+        loaded.column("QNAM").unwrap().data(),
+        &ColumnData::String(vec![
+            Some("AETRTEM".into()),
+            None,
+            Some("AESOSP".into()),
+            None
+        ])
+    );
+
+    // A blank row followed by further rows is not the end of the data
+    let dataset = Dataset::new(
+        "SUPPAE",
+        vec![Column::new(
+            "QNAM",
+            ColumnData::String(vec![Some("A".into()), None, Some("B".into())]),
+        )],
+    )
+    .unwrap();
+    Xpt::writer(dataset)
+        .finalize()
+        .unwrap()
+        .write_path(&path)
+        .unwrap();
+    assert_eq!(Xpt::read(&path).unwrap().nrows(), 3);
+}
+
+/// Test numeric variables shorter than 8 bytes.
+#[test]
+fn test_short_numeric_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    let dataset = Dataset::new(
+        "AE",
+        vec![
+            Column::new(
+                "AEDY",
+                ColumnData::F64(vec![Some(1.0), Some(2_097_152.0), Some(0.1), None]),
+            )
+            .with_numeric_length(4),
+            Column::new("AESEQ", ColumnData::F64(vec![Some(0.1); 4])),
+        ],
+    )
+    .unwrap();
+
+    let validated = Xpt::writer(dataset).finalize().unwrap();
+    assert!(
+        validated
+            .issues()
+            .contains(&Issue::NumericPrecisionReduced {
+                variable: "AEDY".into(),
+                length: 4,
+            })
+    );
+    validated.write_path(&path).unwrap();
+
+    let info = Xpt::inspect(&path).unwrap();
+    assert_eq!(info.members[0].variables[0].length(), 4);
+    assert_eq!(info.members[0].row_len, 12);
+
+    let loaded = Xpt::read(&path).unwrap();
+    let ColumnData::F64(days) = loaded.columns()[0].data() else {
+        panic!("expected numeric data");
+    };
+    assert_eq!(days[0], Some(1.0));
+    assert_eq!(days[1], Some(2_097_152.0));
+    let tenth = days[2].unwrap();
+    assert!(tenth != 0.1 && (tenth - 0.1).abs() < 1e-6);
+    assert_eq!(days[3], None);
+    assert_eq!(
+        loaded.columns()[1].data(),
+        &ColumnData::F64(vec![Some(0.1); 4])
+    );
+
+    // Lengths outside 3-8 bytes are rejected
+    let dataset = Dataset::new(
+        "AE",
+        vec![Column::new("AEDY", ColumnData::F64(vec![Some(1.0)])).with_numeric_length(2)],
+    )
+    .unwrap();
+    assert!(Xpt::writer(dataset).finalize().is_err());
+}
+
+/// Test that SAS special missing values survive a write/read round trip.
+#[test]
+fn test_special_missing_values_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("lb.xpt");
+
+    let codes = vec![
+        None,
+        Some(SasMissingValue::Special('U')),
+        None,
+        Some(SasMissingValue::Underscore),
+    ];
+    let dataset = Dataset::new(
+        "LB",
+        vec![
+            Column::new(
+                "LBSTRESN",
+                ColumnData::F64(vec![Some(4.5), None, None, None]),
+            )
+            .with_special_missing(codes.clone()),
+        ],
+    )
+    .unwrap();
+
+    Xpt::writer(dataset)
+        .finalize()
+        .unwrap()
+        .write_path(&path)
+        .unwrap();
+
+    // Each code is written as its own marker byte
+    let bytes = std::fs::read(&path).unwrap();
+    let obs_header = b"HEADER RECORD*******OBS     ";
+    let obs_start = bytes
+        .windows(obs_header.len())
+        .position(|w| w == obs_header)
+        .unwrap()
+        + 80;
+    assert_eq!(bytes[obs_start + 8], b'U');
+    assert_eq!(bytes[obs_start + 16], b'.');
+    assert_eq!(bytes[obs_start + 24], b'_');
+
+    let loaded = Xpt::read(&path).unwrap();
+    let col = loaded.column("LBSTRESN").unwrap();
+    assert_eq!(
+        col.data(),
+        &ColumnData::F64(vec![Some(4.5), None, None, None])
+    );
+    assert_eq!(col.special_missing(), Some(&codes[..]));
+}
+
+/// Test writing and reading character data in legacy code pages.
+#[test]
+fn test_legacy_code_page_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    // "Headache" and "Adverse event term" in Japanese
+    let term = "\u{982d}\u{75db}";
+    let label = "\u{6709}\u{5bb3}\u{4e8b}\u{8c61}\u{540d}";
+    let dataset = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![
+            Column::new(
+                "AETERM",
+                ColumnData::String(vec![Some(term.into()), Some("CUT".into())]),
+            )
+            .with_label(label),
+        ],
+    )
+    .unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.text_mode(TextMode::ShiftJis);
+    builder.finalize().unwrap().write_path(&path).unwrap();
+
+    // Two bytes per character in Shift-JIS
+    let loaded = Xpt::reader(&path)
+        .unwrap()
+        .text_mode(TextMode::ShiftJis)
+        .read()
+        .unwrap();
+    let col = loaded.column("AETERM").unwrap();
+    assert_eq!(col.explicit_length(), Some(4));
+    assert_eq!(col.label(), Some(label));
+    assert_eq!(
+        col.data(),
+        &ColumnData::String(vec![Some(term.into()), Some("CUT".into())])
+    );
+
+    let info = Xpt::inspect_with(&path, TextMode::ShiftJis).unwrap();
+    assert_eq!(info.members[0].variables[0].nlabel, label);
+
+    // Text that Windows-1252 cannot represent is rejected before writing
+    let dataset = Dataset::new(
+        "AE",
+        vec![Column::new(
+            "AETERM",
+            ColumnData::String(vec![Some(term.into())]),
+        )],
+    )
+    .unwrap();
+    let mut builder = Xpt::writer(dataset);
+    builder.text_mode(TextMode::Windows1252);
+    assert!(matches!(
+        builder.finalize(),
+        Err(xportrs::Error::Encoding { .. })
+    ));
+}
+
 /// Test multiple columns with various types.
 #[test]
 fn test_multiple_column_types() {
@@ -202,14 +646,9 @@ fn test_multiple_column_types() {
 /// Test that all agencies can be used.
 #[test]
 fn test_all_agencies() {
-    let dir = tempdir().unwrap();
-
-    for (agency, name) in [
-        (Agency::FDA, "fda.xpt"),
-        (Agency::PMDA, "pmda.xpt"),
-        (Agency::NMPA, "nmpa.xpt"),
-    ] {
-        let path = dir.path().join(name);
+    for agency in [Agency::FDA, Agency::PMDA, Agency::NMPA] {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ae.xpt");
 
         let dataset = Dataset::new(
             "AE",
@@ -228,6 +667,51 @@ fn test_all_agencies() {
     }
 }
 
+/// Test that the file stem is checked before anything is written.
+#[test]
+fn test_file_stem_check() {
+    let dir = tempdir().unwrap();
+    let dataset = || {
+        Dataset::new(
+            "AE",
+            vec![Column::new("AESEQ", ColumnData::F64(vec![Some(1.0)]))],
+        )
+        .unwrap()
+    };
+
+    let mut builder = Xpt::writer(dataset());
+    builder.agency(Agency::FDA);
+    let err = builder
+        .finalize_for_path(dir.path().join("adverse.xpt"))
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("dataset name 'AE' does not match file stem 'adverse'")
+    );
+
+    let mut builder = Xpt::writer(dataset());
+    builder.agency(Agency::FDA);
+    let path = dir.path().join("adverse.xpt");
+    assert!(builder.finalize().unwrap().write_path(&path).is_err());
+    assert!(!path.exists());
+
+    // Without an agency, any file name is accepted
+    let path = dir.path().join("adverse.xpt");
+    Xpt::writer(dataset())
+        .finalize()
+        .unwrap()
+        .write_path(&path)
+        .unwrap();
+    assert!(path.exists());
+
+    let mut builder = Xpt::writer(dataset());
+    builder.agency(Agency::FDA);
+    let validated = builder
+        .finalize_for_path(dir.path().join("ae.xpt"))
+        .unwrap();
+    assert!(!validated.has_errors());
+}
+
 /// Test round-trip preserves data from real XPT files.
 #[test]
 fn test_roundtrip_real_files() {
@@ -363,6 +847,71 @@ fn test_metadata_roundtrip() {
     assert_eq!(loaded.columns()[1].explicit_length(), Some(40));
 }
 
+/// Test that a dataset and variable specification drive the written file.
+#[test]
+fn test_specification_driven_write() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ae.xpt");
+
+    // Bare data, in a different order from the specification
+    let dataset = Dataset::new(
+        "AE",
+        vec![
+            Column::new("AESTDT", ColumnData::F64(vec![Some(21185.0)])),
+            Column::new("AETERM", ColumnData::String(vec![Some("HEADACHE".into())])),
+            Column::new("USUBJID", ColumnData::String(vec![Some("001-001".into())])),
+        ],
+    )
+    .unwrap();
+
+    let spec = vec![
+        VariableMetadata::new("AE", "USUBJID")
+            .with_length(20)
+            .with_label("Unique Subject Identifier")
+            .with_order(1),
+        VariableMetadata::new("AE", "AETERM")
+            .with_xpt_type(XptVarType::Character)
+            .with_length(200)
+            .with_label("Reported Term for the Adverse Event")
+            .with_order(2),
+        VariableMetadata::new("AE", "AESTDT")
+            .with_label("Start Date of Adverse Event")
+            .with_format("DATE9.")
+            .with_order(3),
+    ];
+
+    let mut builder = Xpt::writer(dataset);
+    builder
+        .dataset_metadata(DatasetMetadata::new("AE").with_label("Adverse Events"))
+        .variable_metadata(spec);
+    let validated = builder.finalize().unwrap();
+    assert!(!validated.has_warnings());
+    validated.write_path(&path).unwrap();
+
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(loaded.dataset_label(), Some("Adverse Events"));
+
+    let names: Vec<&str> = loaded.columns().iter().map(Column::name).collect();
+    assert_eq!(names, ["USUBJID", "AETERM", "AESTDT"]);
+
+    let usubjid = loaded.column("USUBJID").unwrap();
+    assert_eq!(usubjid.explicit_length(), Some(20));
+    assert_eq!(usubjid.label(), Some("Unique Subject Identifier"));
+
+    let aeterm = loaded.column("AETERM").unwrap();
+    assert_eq!(aeterm.explicit_length(), Some(200));
+    assert_eq!(
+        aeterm.data(),
+        &ColumnData::String(vec![Some("HEADACHE".into())])
+    );
+
+    let aestdt = loaded.column("AESTDT").unwrap();
+    assert_eq!(aestdt.label(), Some("Start Date of Adverse Event"));
+    let format = aestdt.format().expect("AESTDT should have format");
+    assert_eq!(format.name_without_prefix(), "DATE");
+    assert_eq!(format.length(), 9);
+}
+
 /// Test that missing labels generate warnings.
 #[test]
 fn test_missing_labels_warning() {
@@ -435,3 +984,119 @@ fn test_format_constructors() {
     assert_eq!(char_fmt.length(), 200);
     assert!(char_fmt.is_character());
 }
+
+/// Test writing several datasets into one library file.
+#[test]
+fn test_library_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("study.xpt");
+
+    let dm = Xpt::read(test_data_dir().join("dm.xpt")).unwrap();
+    let dm_rows = dm.nrows();
+
+    let ae = Dataset::with_label(
+        "AE",
+        "Adverse Events",
+        vec![
+            Column::new(
+                "USUBJID",
+                ColumnData::String(vec![Some("001".into()), Some("002".into()), None]),
+            ),
+            Column::new(
+                "AESEQ",
+                ColumnData::F64(vec![Some(1.0), Some(2.0), Some(3.0)]),
+            ),
+        ],
+    )
+    .unwrap();
+
+    let mut library = Xpt::library_writer();
+    let mut builder = Xpt::writer(ae);
+    builder.agency(Agency::FDA);
+    library.add(builder.finalize().unwrap());
+    library.add_dataset(dm).unwrap();
+    assert_eq!(library.len(), 2);
+    library.write_path(&path).unwrap();
+
+    // Only the first member is found without scanning its data
+    let info = Xpt::inspect(&path).unwrap();
+    assert_eq!(info.member_names().collect::<Vec<_>>(), vec!["AE"]);
+
+    let mut reader = Xpt::reader(&path).unwrap();
+    let info = reader.find_members().unwrap();
+    assert_eq!(info.member_names().collect::<Vec<_>>(), vec!["AE", "DM"]);
+    assert_eq!(info.members[0].obs_count, 3);
+    assert_eq!(info.members[1].obs_count, dm_rows);
+
+    // The first member ends at the second member's header
+    let ae = Xpt::reader(&path).unwrap().read().unwrap();
+    assert_eq!(ae.nrows(), 3);
+    let batches = Xpt::reader(&path).unwrap().batches(2).unwrap();
+    let rows: Vec<_> = batches.map(|b| b.unwrap().nrows()).collect();
+    assert_eq!(rows, vec![2, 1]);
+    let tail = Xpt::reader(&path).unwrap().row_range(2..10).read().unwrap();
+    assert_eq!(tail.nrows(), 1);
+
+    let datasets = Xpt::reader(&path).unwrap().read_all().unwrap();
+    assert_eq!(datasets.len(), 2);
+    assert_eq!(datasets[0].nrows(), 3);
+    assert_eq!(datasets[0].dataset_label(), Some("Adverse Events"));
+    assert_eq!(datasets[1].nrows(), dm_rows);
+
+    let dm = Xpt::reader(&path).unwrap().read_member("DM").unwrap();
+    assert_eq!(dm.nrows(), dm_rows);
+    assert_eq!(dm.dataset_label(), Some("Demographics"));
+}
+
+/// Test streaming a dataset to a file in chunks.
+#[test]
+fn test_stream_chunks_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("lb.xpt");
+
+    let chunk = |start: usize, len: usize| {
+        Dataset::new(
+            "LB",
+            vec![
+                Column::new(
+                    "USUBJID",
+                    ColumnData::String(
+                        (start..start + len)
+                            .map(|i| Some(format!("SUBJ-{i:04}")))
+                            .collect(),
+                    ),
+                )
+                .with_length(12)
+                .with_label("Unique Subject Identifier"),
+                Column::new(
+                    "LBSTRESN",
+                    ColumnData::F64((start..start + len).map(|i| Some(i as f64 / 2.0)).collect()),
+                )
+                .with_label("Numeric Result"),
+            ],
+        )
+        .unwrap()
+    };
+
+    let mut stream = Xpt::writer(chunk(0, 5))
+        .finalize()
+        .unwrap()
+        .stream_path(&path)
+        .unwrap();
+    for start in (5..50).step_by(15) {
+        stream.write_chunk(&chunk(start, 15)).unwrap();
+    }
+    assert_eq!(stream.rows_written(), 50);
+    stream.finish().unwrap();
+
+    let loaded = Xpt::read(&path).unwrap();
+    assert_eq!(loaded.nrows(), 50);
+    match loaded.column("USUBJID").unwrap().data() {
+        ColumnData::String(values) => assert_eq!(values[49].as_deref(), Some("SUBJ-0049")),
+        other => panic!("expected String data, got {other:?}"),
+    }
+    match loaded.column("LBSTRESN").unwrap().data() {
+        ColumnData::F64(values) => assert_eq!(values[21], Some(10.5)),
+        other => panic!("expected F64 data, got {other:?}"),
+    }
+}
@@ -1,7 +1,11 @@
 //! XPT v8 format integration tests.
 //!
-//! This module is a placeholder for future XPT v8 format tests.
-//! XPT v8 support is planned but not yet implemented.
+//! This module contains tests for XPT v8 format support:
+//! - `read` - Tests for reading hand-built V8 files and version detection
+//! - `write` - Round-trip tests for writing V8 files
 
-#[path = "v8/placeholder.rs"]
-mod placeholder;
+#[path = "v8/read.rs"]
+mod read;
+
+#[path = "v8/write.rs"]
+mod write;
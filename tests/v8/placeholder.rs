@@ -1,11 +0,0 @@
-//! XPT v8 format tests.
-//!
-//! This module is a placeholder for future XPT v8 format tests.
-//! XPT v8 support is planned but not yet implemented.
-
-// TODO: Add XPT v8 tests when v8 support is implemented.
-// XPT v8 features:
-// - Extended variable name length (32 characters)
-// - Extended label length (256 characters)
-// - Unicode support
-// - Extended numeric precision
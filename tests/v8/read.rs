@@ -0,0 +1,238 @@
+//! Tests for reading XPT v8 files.
+//!
+//! No SAS-produced V8 fixture is bundled, so these tests assemble V8 files
+//! byte by byte following the SAS technical note layout.
+
+use std::path::PathBuf;
+
+use tempfile::tempdir;
+use xportrs::{ColumnData, Xpt, XptVersion};
+
+/// IBM float encoding of 1.0.
+const IBM_ONE: [u8; 8] = [0x41, 0x10, 0, 0, 0, 0, 0, 0];
+
+/// IBM float encoding of 2.0.
+const IBM_TWO: [u8; 8] = [0x41, 0x20, 0, 0, 0, 0, 0, 0];
+
+/// Standard SAS missing value.
+const IBM_MISSING: [u8; 8] = [b'.', 0, 0, 0, 0, 0, 0, 0];
+
+const LONG_LABEL: &str = "this is a label that is over 40 characters long";
+
+/// Get the path to test data directory.
+fn test_data_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data")
+}
+
+/// Builds an 80-byte header record: prefix, record name, data field.
+fn header(name: &str, data: &str) -> Vec<u8> {
+    let mut record =
+        format!("HEADER RECORD*******{name:<8}HEADER RECORD!!!!!!!{data}").into_bytes();
+    record.resize(80, b' ');
+    record
+}
+
+/// Pads `bytes` with spaces to the next 80-byte boundary.
+fn pad_record(bytes: &mut Vec<u8>) {
+    let rem = bytes.len() % 80;
+    if rem != 0 {
+        bytes.resize(bytes.len() + 80 - rem, b' ');
+    }
+}
+
+/// Space-pads `s` to `len` bytes, truncating if longer.
+fn padded(s: &str, len: usize) -> Vec<u8> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.truncate(len);
+    bytes.resize(len, b' ');
+    bytes
+}
+
+/// A variable definition for a hand-built file.
+struct Var {
+    name: &'static str,
+    numeric: bool,
+    length: i16,
+    label: &'static str,
+    format: &'static str,
+}
+
+/// Builds a 140-byte V8 NAMESTR record.
+fn namestr(var: &Var, varnum: i16, position: i32) -> Vec<u8> {
+    let ntype: i16 = if var.numeric { 1 } else { 2 };
+    let mut rec = Vec::with_capacity(140);
+    rec.extend_from_slice(&ntype.to_be_bytes());
+    rec.extend_from_slice(&0i16.to_be_bytes());
+    rec.extend_from_slice(&var.length.to_be_bytes());
+    rec.extend_from_slice(&varnum.to_be_bytes());
+    rec.extend(padded(var.name, 8));
+    rec.extend(padded(var.label, 40));
+    rec.extend(padded(var.format, 8));
+    rec.extend_from_slice(&[0u8; 8]); // nfl, nfd, nfj, nfill
+    rec.extend(padded("", 8)); // niform
+    rec.extend_from_slice(&[0u8; 4]); // nifl, nifd
+    rec.extend_from_slice(&position.to_be_bytes());
+    rec.extend(padded(var.name, 32)); // longname
+    rec.extend_from_slice(&(var.label.len() as i16).to_be_bytes()); // lablen
+    rec.resize(140, 0);
+    rec
+}
+
+/// Builds one V8 member with a `LABELV9` entry for the first variable.
+fn member(name: &str, vars: &[Var], rows: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = header("MEMBV8", "000000000000000001600000000140");
+    bytes.extend(header("DSCPTV8", "000000000000000000000000000000"));
+
+    // Member descriptor: 'SAS', 32-byte name, 'SASDATA', version, OS, created
+    bytes.extend_from_slice(b"SAS     ");
+    bytes.extend(padded(name, 32));
+    bytes.extend_from_slice(b"SASDATA 9.4     X64_7PRO01JAN24:00:00:00");
+    bytes.extend_from_slice(b"01JAN24:00:00:00");
+    bytes.extend(padded("", 16));
+    bytes.extend(padded("Long Names Dataset", 40));
+    bytes.extend(padded("", 8));
+
+    bytes.extend(header(
+        "NAMSTV8",
+        &format!("{:010}00000000000000000000", vars.len()),
+    ));
+    let mut namestrs = Vec::new();
+    let mut position = 0;
+    for (i, var) in vars.iter().enumerate() {
+        namestrs.extend(namestr(var, i as i16 + 1, position));
+        position += i32::from(var.length);
+    }
+    pad_record(&mut namestrs);
+    bytes.extend(namestrs);
+
+    // LABELV9 section: one entry with the long name, label and format
+    bytes.extend(header("LABELV9", "1"));
+    let first = &vars[0];
+    let format_text = format!("{}.", first.format);
+    let mut section = Vec::new();
+    for value in [
+        1,
+        first.name.len() as i16,
+        first.label.len() as i16,
+        format_text.len() as i16,
+        0,
+    ] {
+        section.extend_from_slice(&value.to_be_bytes());
+    }
+    section.extend_from_slice(first.name.as_bytes());
+    section.extend_from_slice(first.label.as_bytes());
+    section.extend_from_slice(format_text.as_bytes());
+    pad_record(&mut section);
+    bytes.extend(section);
+
+    bytes.extend(header("OBSV8", "000000000000000000000000000000"));
+    let mut data: Vec<u8> = rows.concat();
+    pad_record(&mut data);
+    bytes.extend(data);
+    bytes
+}
+
+/// Builds a complete V8 library with the given members.
+fn library(members: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = header("LIBV8", "000000000000000000000000000000");
+    bytes.extend_from_slice(b"SAS     SAS     SASLIB  9.4     X64_7PRO");
+    bytes.extend(padded("", 24));
+    bytes.extend_from_slice(b"01JAN24:00:00:00");
+    bytes.extend(padded("01JAN24:00:00:00", 80));
+    for member in members {
+        bytes.extend_from_slice(member);
+    }
+    bytes
+}
+
+fn long_vars() -> Vec<Var> {
+    vec![
+        Var {
+            name: "LONGVARNAME",
+            numeric: true,
+            length: 8,
+            label: LONG_LABEL,
+            format: "LONGFMTNAME",
+        },
+        Var {
+            name: "SUBJECT_IDENTIFIER",
+            numeric: false,
+            length: 3,
+            label: "Subject",
+            format: "",
+        },
+    ]
+}
+
+fn row(value: [u8; 8], text: &[u8; 3]) -> Vec<u8> {
+    let mut row = value.to_vec();
+    row.extend_from_slice(text);
+    row
+}
+
+fn write_file(bytes: &[u8]) -> (tempfile::TempDir, PathBuf) {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("long.xpt");
+    std::fs::write(&path, bytes).unwrap();
+    (dir, path)
+}
+
+/// Test reading long names, labels and formats from a V8 file.
+#[test]
+fn test_read_long_names_and_labels() {
+    let rows = vec![
+        row(IBM_ONE, b"A01"),
+        row(IBM_MISSING, b"A02"),
+        row(IBM_TWO, b"A03"),
+    ];
+    let bytes = library(&[member("LONG_DATASET_NAME", &long_vars(), &rows)]);
+    let (_dir, path) = write_file(&bytes);
+
+    let dataset = Xpt::read(&path).expect("Failed to read V8 file");
+
+    assert_eq!(dataset.domain_code(), "LONG_DATASET_NAME");
+    assert_eq!(dataset.dataset_label(), Some("Long Names Dataset"));
+    assert_eq!(dataset.nrows(), 3);
+
+    let col = dataset.column("LONGVARNAME").expect("long name column");
+    assert_eq!(col.label(), Some(LONG_LABEL));
+    assert_eq!(col.format().map(xportrs::Format::name), Some("LONGFMTNAME"));
+    match col.data() {
+        ColumnData::F64(values) => assert_eq!(values, &vec![Some(1.0), None, Some(2.0)]),
+        other => panic!("expected F64 data, got {other:?}"),
+    }
+
+    let col = dataset
+        .column("SUBJECT_IDENTIFIER")
+        .expect("32-byte name column");
+    assert_eq!(col.label(), Some("Subject"));
+    match col.data() {
+        ColumnData::String(values) => assert_eq!(values[2].as_deref(), Some("A03")),
+        other => panic!("expected String data, got {other:?}"),
+    }
+}
+
+/// Test that inspect reports the detected version.
+#[test]
+fn test_inspect_detects_version() {
+    let bytes = library(&[member("LONGDS", &long_vars(), &[row(IBM_ONE, b"A01")])]);
+    let (_dir, path) = write_file(&bytes);
+
+    let info = Xpt::inspect(&path).expect("Failed to inspect V8 file");
+    assert_eq!(info.version, XptVersion::V8);
+    assert_eq!(info.member_names().collect::<Vec<_>>(), vec!["LONGDS"]);
+    assert_eq!(info.members[0].variables[0].nname, "LONGVARNAME");
+
+    let info = Xpt::inspect(test_data_dir().join("dm.xpt")).expect("Failed to inspect dm.xpt");
+    assert_eq!(info.version, XptVersion::V5);
+}
+
+/// Test that a file that is neither V5 nor V8 is rejected.
+#[test]
+fn test_reject_unknown_header() {
+    let mut bytes = header("LIBV7", "000000000000000000000000000000");
+    bytes.extend(padded("", 160));
+    let (_dir, path) = write_file(&bytes);
+
+    assert!(Xpt::read(&path).is_err());
+}
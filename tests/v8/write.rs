@@ -0,0 +1,125 @@
+//! Tests for writing XPT v8 files.
+
+use tempfile::tempdir;
+use xportrs::{Column, ColumnData, Dataset, Format, Xpt, XptVersion};
+
+/// A label longer than the 40 bytes a V5 NAMESTR can hold.
+const LONG_LABEL: &str =
+    "Analysis value derived from the original result after unit conversion and imputation rules";
+
+fn long_dataset() -> Dataset {
+    Dataset::with_label(
+        "LONG_DATASET_NAME",
+        "Archive Dataset",
+        vec![
+            Column::new(
+                "ANALYSIS_VALUE_NUMERIC",
+                ColumnData::F64(vec![Some(1.5), None, Some(-3.25)]),
+            )
+            .with_label(LONG_LABEL)
+            .with_format(Format::parse("E8601DATETIME19.").unwrap()),
+            Column::new(
+                "SUBJECT_IDENTIFIER",
+                ColumnData::String(vec![Some("SUBJ-001".into()), Some("SUBJ-002".into()), None]),
+            )
+            .with_label("Subject Identifier"),
+        ],
+    )
+    .unwrap()
+}
+
+/// Test writing long names, labels and formats and reading them back.
+#[test]
+fn test_roundtrip_long_names_and_labels() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("archive.xpt");
+
+    let mut builder = Xpt::writer(long_dataset());
+    builder.xpt_version(XptVersion::V8);
+    builder
+        .finalize()
+        .expect("V8 finalize failed")
+        .write_path(&path)
+        .expect("V8 write failed");
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes.len() % 80, 0);
+    assert!(bytes.starts_with(b"HEADER RECORD*******LIBV8   HEADER RECORD!!!!!!!"));
+
+    let info = Xpt::inspect(&path).unwrap();
+    assert_eq!(info.version, XptVersion::V8);
+
+    let dataset = Xpt::read(&path).expect("V8 read failed");
+    assert_eq!(dataset.domain_code(), "LONG_DATASET_NAME");
+    assert_eq!(dataset.dataset_label(), Some("Archive Dataset"));
+    assert_eq!(dataset.nrows(), 3);
+
+    let col = dataset
+        .column("ANALYSIS_VALUE_NUMERIC")
+        .expect("long name column");
+    assert_eq!(col.label(), Some(LONG_LABEL));
+    let format = col.format().expect("format");
+    assert_eq!(format.name(), "E8601DATETIME");
+    assert_eq!(format.length(), 19);
+    match col.data() {
+        ColumnData::F64(values) => assert_eq!(values, &vec![Some(1.5), None, Some(-3.25)]),
+        other => panic!("expected F64 data, got {other:?}"),
+    }
+
+    let col = dataset.column("SUBJECT_IDENTIFIER").expect("second column");
+    assert_eq!(col.label(), Some("Subject Identifier"));
+    match col.data() {
+        ColumnData::String(values) => assert_eq!(values[1].as_deref(), Some("SUBJ-002")),
+        other => panic!("expected String data, got {other:?}"),
+    }
+}
+
+/// Test that the v8 limits are enforced instead of the v5 ones.
+#[test]
+fn test_v8_limits_enforced() {
+    let dataset = Dataset::new(
+        "AE",
+        vec![
+            Column::new(
+                "A_VARIABLE_NAME_LONGER_THAN_32_BYTES",
+                ColumnData::F64(vec![Some(1.0)]),
+            )
+            .with_label("Too long"),
+        ],
+    )
+    .unwrap();
+
+    let mut builder = Xpt::writer(dataset);
+    builder.xpt_version(XptVersion::V8);
+    let result = builder.finalize();
+    assert!(result.is_err());
+}
+
+/// Test writing several V8 members into one library file.
+#[test]
+fn test_library_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("archive.xpt");
+
+    let second = Dataset::new(
+        "SECOND_LONG_DATASET",
+        vec![Column::new(
+            "A_NUMERIC_VALUE",
+            ColumnData::F64(vec![Some(7.0)]),
+        )],
+    )
+    .unwrap();
+
+    let mut library = Xpt::library_writer();
+    library.xpt_version(XptVersion::V8);
+    library.add_dataset(long_dataset()).unwrap();
+    library.add_dataset(second).unwrap();
+    library.write_path(&path).unwrap();
+
+    let datasets = Xpt::reader(&path).unwrap().read_all().unwrap();
+    assert_eq!(datasets.len(), 2);
+    assert_eq!(datasets[0].nrows(), 3);
+    assert_eq!(datasets[1].domain_code(), "SECOND_LONG_DATASET");
+    assert!(datasets[1].column("A_NUMERIC_VALUE").is_some());
+    assert_eq!(datasets[1].nrows(), 1);
+}